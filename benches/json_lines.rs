@@ -0,0 +1,105 @@
+// Copyright © 2024 Pathway
+
+//! Compares the parsing of JSON lines with and without `simd-json`. Run with
+//! `cargo bench --bench json_lines --features simd-json`, as both parsers use
+//! `serde_json` otherwise.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use pathway_engine::connectors::data_format::{InnerSchemaField, JsonLinesParser, Parser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::connectors::SessionType;
+use pathway_engine::engine::Type;
+
+const FIELD_COUNT: usize = 16;
+const LINE_COUNT: usize = 10_000;
+const ROUNDS: usize = 10;
+
+fn field_names() -> Vec<String> {
+    (0..FIELD_COUNT).map(|i| format!("field_{i}")).collect()
+}
+
+fn new_parser() -> JsonLinesParser {
+    let schema = field_names()
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let type_ = match i % 4 {
+                0 => Type::Int,
+                1 => Type::Float,
+                2 => Type::Bool,
+                _ => Type::String,
+            };
+            (name, InnerSchemaField::new(type_, None))
+        })
+        .collect();
+    JsonLinesParser::new(
+        None,
+        field_names(),
+        HashMap::new(),
+        true,
+        schema,
+        SessionType::Native,
+    )
+    .expect("the schema should be valid")
+}
+
+// Objects of about 1KB, with the padding in the string fields
+fn contexts() -> Vec<ReaderContext> {
+    (0..LINE_COUNT)
+        .map(|line| {
+            let fields: Vec<_> = field_names()
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let value = match i % 4 {
+                        0 => format!("{}", line * i),
+                        1 => format!("{}.25", line + i),
+                        2 => format!("{}", (line + i) % 2 == 0),
+                        _ => format!("{:?}", format!("{line}-{i}-").repeat(25)),
+                    };
+                    format!("{name:?}: {value}")
+                })
+                .collect();
+            let object = format!("{{{}}}", fields.join(", "));
+            ReaderContext::from_raw_bytes(DataEventType::Insert, object.into_bytes())
+        })
+        .collect()
+}
+
+fn measure(mut parser: JsonLinesParser, contexts: &[ReaderContext]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for context in contexts {
+            let events = parser.parse(context).expect("parsing should not fail");
+            black_box(events);
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let contexts = contexts();
+    let bytes: usize = contexts
+        .iter()
+        .map(|context| match context {
+            ReaderContext::RawBytes(_, bytes) => bytes.len(),
+            _ => 0,
+        })
+        .sum();
+    println!(
+        "{LINE_COUNT} objects of {} bytes on average, {ROUNDS} rounds",
+        bytes / LINE_COUNT
+    );
+    for (name, parser) in [
+        ("serde_json", new_parser().without_simd()),
+        ("simd-json", new_parser()),
+    ] {
+        let elapsed = measure(parser, &contexts);
+        #[allow(clippy::cast_precision_loss)]
+        let throughput = (bytes * ROUNDS) as f64 / elapsed.as_secs_f64() / 1e6;
+        println!("{name}: {elapsed:?} ({throughput:.1} MB/s)");
+    }
+}
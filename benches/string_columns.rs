@@ -0,0 +1,101 @@
+// Copyright © 2024 Pathway
+
+//! Compares passing string columns of 1KB through `TransparentParser` with
+//! copying them, as a parser building new strings from the reader values would.
+//! Run with `cargo bench --bench string_columns`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use arcstr::ArcStr;
+use pathway_engine::connectors::data_format::{InnerSchemaField, Parser, TransparentParser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::connectors::SessionType;
+use pathway_engine::engine::{Type, Value};
+
+const FIELD_COUNT: usize = 16;
+const STRING_LEN: usize = 1024;
+const ROW_COUNT: usize = 10_000;
+const ROUNDS: usize = 10;
+
+fn field_names() -> Vec<String> {
+    (0..FIELD_COUNT).map(|i| format!("field_{i}")).collect()
+}
+
+fn new_parser() -> TransparentParser {
+    let schema = field_names()
+        .into_iter()
+        .map(|name| (name, InnerSchemaField::new(Type::String, None)))
+        .collect();
+    TransparentParser::new(None, field_names(), schema, SessionType::Native)
+        .expect("the schema should be valid")
+}
+
+fn contexts() -> Vec<ReaderContext> {
+    (0..ROW_COUNT)
+        .map(|row| {
+            let values: HashMap<_, _> = field_names()
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let mut text = format!("{row}-{i}-");
+                    text.extend(std::iter::repeat('x').take(STRING_LEN - text.len()));
+                    (name, Ok(Value::from(ArcStr::from(text))))
+                })
+                .collect();
+            ReaderContext::from_diff(DataEventType::Insert, None, values.into())
+        })
+        .collect()
+}
+
+// Rebuilds the context, as a reader would for each row, either sharing the
+// string buffers or copying them
+fn rebuilt(context: &ReaderContext, copy: bool) -> ReaderContext {
+    let ReaderContext::Diff((data_event, key, values)) = context else {
+        unreachable!("the benchmark only uses diff contexts");
+    };
+    let values: HashMap<_, _> = values
+        .iter()
+        .map(|(name, value)| {
+            let text = value
+                .as_ref()
+                .expect("the values should be valid")
+                .as_string()
+                .expect("the values should be strings");
+            let value = if copy {
+                Value::from(text.as_str())
+            } else {
+                Value::from(text.clone())
+            };
+            (name.clone(), Ok(value))
+        })
+        .collect();
+    ReaderContext::from_diff(*data_event, key.clone(), values.into())
+}
+
+fn measure(copy: bool, contexts: &[ReaderContext]) -> Duration {
+    let mut parser = new_parser();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for context in contexts {
+            let events = parser
+                .parse(&rebuilt(context, copy))
+                .expect("parsing should not fail");
+            black_box(events);
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let contexts = contexts();
+    let bytes = ROW_COUNT * FIELD_COUNT * STRING_LEN;
+    println!("{ROW_COUNT} rows of {FIELD_COUNT} strings of {STRING_LEN} bytes, {ROUNDS} rounds");
+    for (name, copy) in [("zero-copy", false), ("copy", true)] {
+        let elapsed = measure(copy, &contexts);
+        #[allow(clippy::cast_precision_loss)]
+        let throughput = (bytes * ROUNDS) as f64 / elapsed.as_secs_f64() / 1e6;
+        println!("{name}: {elapsed:?} ({throughput:.1} MB/s)");
+    }
+}
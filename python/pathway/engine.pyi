@@ -40,6 +40,12 @@ class PathwayType:
     DATE_TIME_NAIVE: PathwayType
     DATE_TIME_UTC: PathwayType
     DURATION: PathwayType
+    DECIMAL: PathwayType
+    UUID: PathwayType
+    DATE: PathwayType
+    TIME: PathwayType
+    INT32: PathwayType
+    INT128: PathwayType
     @staticmethod
     def array(dim: int | None, wrapped: PathwayType) -> PathwayType: ...
     JSON: PathwayType
@@ -47,6 +53,8 @@ class PathwayType:
     def tuple(*args: PathwayType) -> PathwayType: ...
     @staticmethod
     def list(arg: PathwayType) -> PathwayType: ...
+    @staticmethod
+    def map(arg: PathwayType) -> PathwayType: ...
     BYTES: PathwayType
     PY_OBJECT_WRAPPER: PathwayType
     @staticmethod
@@ -860,6 +868,7 @@ class PythonConnectorEventType(Enum):
 class SessionType(Enum):
     NATIVE: SessionType
     UPSERT: SessionType
+    SNAPSHOT: SessionType
 
 class SqlWriterInitMode(Enum):
     DEFAULT: SqlWriterInitMode
@@ -20,6 +20,10 @@ pub type ValuesSessionAdaptor<Timestamp> = Box<dyn InputAdaptor<Timestamp>>;
 pub enum SessionType {
     Native,
     Upsert,
+    // Native session, fed with the net state of each key produced by the parser.
+    // Only the events of a single `parse` call, that is of a single message, are
+    // coalesced, the changes of a key spread over several messages are not
+    Snapshot,
 }
 
 pub trait InputAdaptor<Timestamp> {
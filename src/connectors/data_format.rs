@@ -3,39 +3,68 @@
 use std::any::type_name;
 use std::borrow::Cow;
 use std::clone::Clone;
-use std::collections::HashMap;
-use std::io::Write;
-use std::iter::zip;
-use std::mem::take;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{Read, Write};
+use std::iter::{once, zip};
+use std::mem::{discriminant, size_of, take};
 use std::str::{from_utf8, Utf8Error};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
+use crate::connectors::data_lake::{columns_into_pathway_values, parquet_row_into_values_map};
 use crate::connectors::metadata::SourceMetadata;
-use crate::connectors::ReaderContext::{Diff, Empty, KeyValue, RawBytes, TokenizedEntries};
+use crate::connectors::ReaderContext::{
+    Diff, Empty, KeyValue, KeyValueWithHeaders, RawBytes, TokenizedEntries,
+};
 use crate::connectors::{DataEventType, Offset, ReaderContext, SessionType, SnapshotEvent};
+use crate::engine::coerce::{
+    create_bincoded_value, ensure_enum_variant, ensure_int32, parse_bincoded_value, parse_date,
+    parse_time, parse_uuid, parse_value_from_json, serialize_value_to_json, trim_fixed_string,
+    value_fits_type, CoercionOptions, Error as CoerceError, Utf8Mode,
+};
 use crate::engine::error::{limit_length, DynError, DynResult, STANDARD_OBJECT_LENGTH_LIMIT};
 use crate::engine::time::DateTime;
 use crate::engine::{
     value::parse_pathway_pointer, DateTimeNaive, DateTimeUtc, Duration as EngineDuration, Error,
-    Key, Result, Timestamp, Type, Value,
+    Key, KeyImpl, Result, Timestamp, Type, Value,
 };
 
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
 use async_nats::header::HeaderMap as NatsHeaders;
 use base64::engine::general_purpose::STANDARD as base64encoder;
 use base64::Engine;
 use bincode::ErrorKind as BincodeError;
+use bytes::Bytes;
+use ciborium::Value as CborValue;
+use deltalake::arrow::error::ArrowError;
+use deltalake::arrow::ipc::reader::StreamReader as ArrowIpcStreamReader;
+use deltalake::parquet::errors::ParquetError;
+use deltalake::parquet::file::reader::{FileReader, SerializedFileReader as ParquetFileReader};
+use flate2::read::MultiGzDecoder;
+use indexmap::IndexMap;
 use itertools::{chain, Itertools};
-use log::error;
+use log::{debug, error, log_enabled, Level as LogLevel};
 use mongodb::bson::{
     bson, spec::BinarySubtype as BsonBinarySubtype, Binary as BsonBinaryContents,
     Bson as BsonValue, DateTime as BsonDateTime, Document as BsonDocument,
 };
-use ndarray::ArrayD;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, Value as ProtobufValue};
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader as XmlReader;
 use rdkafka::message::{Header as KafkaHeader, OwnedHeaders as KafkaHeaders};
+use rmpv::Value as MsgPackValue;
 use serde::ser::{SerializeMap, Serializer};
-use serde_json::json;
 use serde_json::{Map as JsonMap, Value as JsonValue};
+use serde_yaml::Value as YamlValue;
+use sha2::{Digest, Sha256};
+use toml_edit::{DocumentMut as TomlDocument, Item as TomlItem, TableLike, Value as TomlValue};
 
-use super::data_storage::{ConversionError, SpecialEvent};
+use super::data_storage::{ConversionError, SpecialEvent, ValuesMap};
 
 pub const COMMIT_LITERAL: &str = "*COMMIT*";
 const DEBEZIUM_EMPTY_KEY_PAYLOAD: &str = "{\"payload\": {\"before\": {}, \"after\": {}}}";
@@ -58,6 +87,8 @@ pub enum ParsedEventWithErrors {
     Insert((KeyFieldsWithErrors, ValueFieldsWithErrors)),
     // If None, finding the key for the provided values becomes responsibility of the connector
     Delete((KeyFieldsWithErrors, ValueFieldsWithErrors)),
+    // Replaces the row stored for the key, only used with `SessionType::Upsert`
+    Upsert((KeyFieldsWithErrors, ValueFieldsWithErrors)),
 }
 
 impl ParsedEventWithErrors {
@@ -70,7 +101,9 @@ impl ParsedEventWithErrors {
         match data_event_type {
             DataEventType::Insert => ParsedEventWithErrors::Insert((key, values)),
             DataEventType::Delete => match session_type {
-                SessionType::Native => ParsedEventWithErrors::Delete((key, values)),
+                SessionType::Native | SessionType::Snapshot => {
+                    ParsedEventWithErrors::Delete((key, values))
+                }
                 SessionType::Upsert => ParsedEventWithErrors::Delete((key, vec![])),
             },
         }
@@ -84,8 +117,41 @@ impl ParsedEventWithErrors {
             Self::Delete((key, values)) => key
                 .transpose()
                 .and_then(|key| Ok(ParsedEvent::Delete((key, logic(values)?)))),
+            Self::Upsert((key, values)) => key
+                .transpose()
+                .and_then(|key| Ok(ParsedEvent::Upsert((key, logic(values)?)))),
         }
     }
+
+    /// Names the value fields that failed to parse, together with the reasons.
+    /// Doesn't allocate if the event contains no errors.
+    pub fn field_diagnostics(&self, value_field_names: &[String]) -> Vec<FieldDiagnostic> {
+        let values = match self {
+            Self::Insert((_, values)) | Self::Delete((_, values)) | Self::Upsert((_, values)) => {
+                values
+            }
+            Self::AdvanceTime => return Vec::new(),
+        };
+        zip(value_field_names, values)
+            .filter_map(|(field_name, value)| {
+                let error = value.as_ref().err()?;
+                let reason = match error.downcast_ref::<ParseError>() {
+                    Some(ParseError::SchemaNotSatisfied { error, .. }) => error.to_string(),
+                    _ => error.to_string(),
+                };
+                Some(FieldDiagnostic {
+                    field_name: field_name.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldDiagnostic {
+    pub field_name: String,
+    pub reason: String,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -94,6 +160,8 @@ pub enum ParsedEvent {
     Insert((Option<Vec<Value>>, Vec<Value>)),
     // If None, finding the key for the provided values becomes responsibility of the connector
     Delete((Option<Vec<Value>>, Vec<Value>)),
+    // Replaces the row stored for the key, only used with `SessionType::Upsert`
+    Upsert((Option<Vec<Value>>, Vec<Value>)),
 }
 
 impl ParsedEvent {
@@ -103,22 +171,105 @@ impl ParsedEvent {
         offset: Option<&Offset>,
     ) -> Option<Key> {
         match self {
-            ParsedEvent::Insert((raw_key, _)) | ParsedEvent::Delete((raw_key, _)) => {
-                Some(values_to_key(raw_key.as_ref(), offset))
-            }
+            ParsedEvent::Insert((raw_key, _))
+            | ParsedEvent::Delete((raw_key, _))
+            | ParsedEvent::Upsert((raw_key, _)) => Some(values_to_key(raw_key.as_ref(), offset)),
             ParsedEvent::AdvanceTime => None,
         }
     }
 
+    /// The values of the key fields, if the event carries them.
+    pub fn raw_key(&self) -> Option<&[Value]> {
+        match self {
+            ParsedEvent::Insert((raw_key, _))
+            | ParsedEvent::Delete((raw_key, _))
+            | ParsedEvent::Upsert((raw_key, _)) => raw_key.as_deref(),
+            ParsedEvent::AdvanceTime => None,
+        }
+    }
+
+    /// An estimate of the memory taken by the event, with its key, built from
+    /// [`Value::size_bytes`].
+    pub fn size_bytes(&self) -> usize {
+        let values_size = |values: &[Value]| values.iter().map(Value::size_bytes).sum::<usize>();
+        let payload_size = match self {
+            ParsedEvent::Insert((raw_key, values))
+            | ParsedEvent::Delete((raw_key, values))
+            | ParsedEvent::Upsert((raw_key, values)) => {
+                raw_key.as_deref().map_or(0, values_size) + values_size(values)
+            }
+            ParsedEvent::AdvanceTime => 0,
+        };
+        size_of::<Self>() + payload_size
+    }
+
+    /// Renders the event with its values annotated by
+    /// [`Value::display_annotated`], e.g. `Insert(key: [Int(1)], values: [Err])`.
+    pub fn display_annotated(&self) -> String {
+        let annotated = |values: &[Value]| {
+            format!(
+                "[{}]",
+                values.iter().map(Value::display_annotated).format(", ")
+            )
+        };
+        let (name, raw_key, values) = match self {
+            ParsedEvent::Insert((raw_key, values)) => ("Insert", raw_key, values),
+            ParsedEvent::Delete((raw_key, values)) => ("Delete", raw_key, values),
+            ParsedEvent::Upsert((raw_key, values)) => ("Upsert", raw_key, values),
+            ParsedEvent::AdvanceTime => return "AdvanceTime".to_string(),
+        };
+        let raw_key = raw_key
+            .as_deref()
+            .map_or_else(|| "None".to_string(), annotated);
+        format!("{name}(key: {raw_key}, values: {})", annotated(values))
+    }
+
+    /// Wraps the event so that it is compared and hashed by its key only.
+    pub fn by_key(self) -> ByKey {
+        // Each keyless event gets an identity of its own, so that it only
+        // equals its own clones
+        let keyless_id = self
+            .raw_key()
+            .is_none()
+            .then(|| NEXT_KEYLESS_ID.fetch_add(1, Ordering::Relaxed));
+        ByKey(self, keyless_id)
+    }
+
     pub fn snapshot_event(&self, key: Key) -> Option<SnapshotEvent> {
         match self {
-            ParsedEvent::Insert((_, values)) => Some(SnapshotEvent::Insert(key, values.clone())),
+            // In the upsert session an insertion replaces the previous row
+            ParsedEvent::Insert((_, values)) | ParsedEvent::Upsert((_, values)) => {
+                Some(SnapshotEvent::Insert(key, values.clone()))
+            }
             ParsedEvent::Delete((_, values)) => Some(SnapshotEvent::Delete(key, values.clone())),
             ParsedEvent::AdvanceTime => None,
         }
     }
 }
 
+static NEXT_KEYLESS_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A `ParsedEvent` that is equal to the events with the same key, whatever
+/// their kinds and values are. An event without a key is only equal to the
+/// clones of its own wrapper, so keyless events never collapse in a map.
+#[derive(Clone, Debug)]
+pub struct ByKey(pub ParsedEvent, Option<u64>);
+
+impl PartialEq for ByKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.raw_key() == other.0.raw_key() && self.1 == other.1
+    }
+}
+
+impl Eq for ByKey {}
+
+impl Hash for ByKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.raw_key().hash(state);
+        self.1.hash(state);
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum ParseError {
@@ -139,6 +290,18 @@ pub enum ParseError {
     #[error("too small number of csv tokens in the line: {0}")]
     UnexpectedNumberOfCsvTokens(usize),
 
+    #[error("unexpected number of csv tokens in the line: {actual} instead of {expected}")]
+    CsvTokensCountMismatch { actual: usize, expected: usize },
+
+    #[error("csv payload ends inside a quoted field")]
+    UnclosedCsvQuote,
+
+    #[error("field {field_name:?} starts beyond the end of the line of {line_length} bytes")]
+    FixedWidthFieldBeyondLine {
+        field_name: String,
+        line_length: usize,
+    },
+
     #[error("failed to create a field {field_name:?} with type {type_} from json payload: {}", limit_length(format!("{payload}"), STANDARD_OBJECT_LENGTH_LIMIT))]
     FailedToParseFromJson {
         field_name: String,
@@ -146,6 +309,103 @@ pub enum ParseError {
         type_: Type,
     },
 
+    #[error("failed to create a field {field_name:?} with type {type_} from avro value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromAvro {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to decode avro record: {0}")]
+    FailedToDecodeAvro(String),
+
+    #[error("failed to create a field {field_name:?} with type {type_} from protobuf value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromProtobuf {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to decode protobuf message: {0}")]
+    FailedToDecodeProtobuf(String),
+
+    #[error("failed to create a field {field_name:?} with type {type_} from msgpack value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromMsgPack {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to decode msgpack record: {0}")]
+    FailedToDecodeMsgPack(String),
+
+    #[error("msgpack record is not a map")]
+    MsgPackRecordIsNotAMap,
+
+    #[error("failed to create a field {field_name:?} with type {type_} from cbor value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromCbor {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to decode cbor record: {0}")]
+    FailedToDecodeCbor(String),
+
+    #[error("cbor record is not a map")]
+    CborRecordIsNotAMap,
+
+    #[error("failed to create a field {field_name:?} with type {type_} from bson value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromBson {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to decode bson document: {0}")]
+    FailedToDecodeBson(String),
+
+    #[error("the \"_id\" field of a bson document is not an ObjectId")]
+    BsonIdIsNotAnObjectId,
+
+    #[error("failed to create a field {field_name:?} with type {type_} from yaml value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromYaml {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error(transparent)]
+    Parquet(#[from] ParquetError),
+
+    #[error("failed to read arrow ipc stream: {0}")]
+    ArrowIpc(#[source] ArrowError),
+
+    #[error("failed to parse yaml document: {0}")]
+    FailedToParseYamlDocument(String),
+
+    #[error("failed to parse xml: {0}")]
+    FailedToParseXml(String),
+
+    #[error("yaml document is not a mapping")]
+    YamlDocumentIsNotAMapping,
+
+    #[error("failed to create a field {field_name:?} with type {type_} from toml value: {}", limit_length(value.clone(), STANDARD_OBJECT_LENGTH_LIMIT))]
+    FailedToParseFromToml {
+        field_name: String,
+        value: String,
+        type_: Type,
+    },
+
+    #[error("failed to parse toml document: {0}")]
+    FailedToParseTomlDocument(String),
+
+    #[error("invalid protobuf descriptor set: {0}")]
+    InvalidProtobufDescriptor(String),
+
+    #[error("message {0:?} is not present in the protobuf descriptor set")]
+    ProtobufMessageNotFound(String),
+
     #[error("key-value pair has unexpected number of tokens: {0} instead of 2")]
     KeyValueTokensIncorrect(usize),
 
@@ -192,8 +452,81 @@ pub enum ParseError {
     #[error(transparent)]
     Base64(#[from] base64::DecodeError),
 
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
     #[error("malformed complex field JSON representation")]
     MalformedComplexField,
+
+    #[error("failed to parse the context {index} of the batch: {error}")]
+    BatchContextFailed { index: usize, error: DynError },
+
+    #[error("coercion hook of field {field_name:?} rejected the value: {message}")]
+    CoercionHookFailed { field_name: String, message: String },
+
+    #[error("derived field {field_name:?} could not be computed: {message}")]
+    DerivedFieldFailed { field_name: String, message: String },
+
+    #[error("key field {field_name:?} is missing")]
+    KeyFieldMissing { field_name: String },
+
+    #[error("field {field_name:?} is missing")]
+    FieldMissing { field_name: String },
+
+    #[error("field {field_name:?} with raw value {raw_value} failed to parse: {error}")]
+    FieldFailedInStrictMode {
+        field_name: String,
+        raw_value: String,
+        error: String,
+    },
+
+    #[error("the record declares schema version {0:?}, which is incompatible with the schema")]
+    IncompatibleSchemaVersion(String),
+
+    #[error("the value denotes null, but the field is not optional")]
+    NullInNonOptionalField,
+
+    #[error("the element of the exploded list is an error")]
+    ErrorInExplodedElement,
+
+    #[error("the record has fields not present in the schema: {0:?}")]
+    UnknownFields(Vec<String>),
+
+    #[error("the message key can only be parsed into a single key field, got {0} key fields")]
+    KeyBytesWithoutSingleKeyField(usize),
+
+    #[error("discriminator field {0:?} is missing")]
+    DiscriminatorMissing(String),
+
+    #[error("no schema is registered for the discriminator value {0:?}")]
+    UnknownDiscriminator(String),
+
+    #[error("image field {field_name:?} holds {value}, which is not a JSON object")]
+    CdcImageNotAnObject { field_name: String, value: Value },
+
+    #[error("frame of {length} bytes exceeds the limit of {max_frame_size} bytes")]
+    FrameTooLarge {
+        length: usize,
+        max_frame_size: usize,
+    },
+
+    #[error("failed to decompress the {codec:?} data: {error}")]
+    DecompressionFailed {
+        codec: CompressionCodec,
+        error: std::io::Error,
+    },
+
+    #[error("sub-record field {field_name:?} holds {value}, which is not a JSON object")]
+    SubRecordNotAnObject { field_name: String, value: Value },
+
+    #[error("field {field_name:?} has values of different types in the merged sub-records")]
+    MergedFieldTypesConflict { field_name: String },
+
+    #[error(transparent)]
+    Coercion(#[from] CoerceError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -219,15 +552,179 @@ impl From<DynError> for ParseError {
 pub type ParseResult = DynResult<Vec<ParsedEventWithErrors>>;
 type PrepareStringResult = Result<String, ParseError>;
 
-#[derive(Clone, Debug)]
+/// A default produced anew for every row in which the field is missing.
+pub type ComputedDefault = Arc<dyn Fn() -> Value + Send + Sync>;
+
+/// What takes the place of a field value that failed to parse, for the sinks
+/// that can't store errors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// `Value::Error`
+    #[default]
+    Error,
+    /// `Value::None`, only allowed for optional fields
+    Null,
+    /// The default of the field, which must have one
+    Default,
+}
+
+/// What an optional field absent from a record is filled with. It is told
+/// apart from a field present with a null value, which is always `Value::None`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MissingBehavior {
+    /// `Value::None`
+    Null,
+    /// The given value, which must fit the type of the field
+    Default(Value),
+    /// `Value::Error`
+    Error,
+}
+
+#[derive(Clone)]
 pub struct InnerSchemaField {
     type_: Type,
     default: Option<Value>, // None means that there is no default for the field
+    // Takes precedence over `default`, only used by `TransparentParser`
+    computed_default: Option<ComputedDefault>,
+    constraints: Option<FieldConstraints>,
+    // Only used by `TransparentParser`
+    on_error: OnError,
+    bytes_encoding: BytesEncoding,
+    epoch: Option<EpochTimestamps>,
+    // Takes precedence over the defaults, only used by `TransparentParser`
+    missing: Option<MissingBehavior>,
+}
+
+impl fmt::Debug for InnerSchemaField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InnerSchemaField")
+            .field("type_", &self.type_)
+            .field("default", &self.default)
+            .field("computed_default", &self.computed_default.is_some())
+            .field("constraints", &self.constraints)
+            .field("on_error", &self.on_error)
+            .field("bytes_encoding", &self.bytes_encoding)
+            .field("epoch", &self.epoch)
+            .field("missing", &self.missing)
+            .finish()
+    }
 }
 
 impl InnerSchemaField {
     pub fn new(type_: Type, default: Option<Value>) -> Self {
-        Self { type_, default }
+        Self {
+            type_: type_.flatten_optional(),
+            default,
+            computed_default: None,
+            constraints: None,
+            on_error: OnError::Error,
+            bytes_encoding: BytesEncoding::Raw,
+            epoch: None,
+            missing: None,
+        }
+    }
+
+    /// Makes the missing values of the field be filled with the results of
+    /// `default`, called separately for each row in which the field is missing.
+    /// `TransparentParser::new` calls it once more to check the type of the
+    /// result.
+    #[must_use]
+    pub fn with_computed_default(
+        mut self,
+        default: impl Fn() -> Value + Send + Sync + 'static,
+    ) -> Self {
+        self.computed_default = Some(Arc::new(default));
+        self
+    }
+
+    #[must_use]
+    pub fn with_constraints(mut self, constraints: FieldConstraints) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    // A record without the field can't be parsed
+    fn is_required(&self) -> bool {
+        match &self.missing {
+            Some(MissingBehavior::Error) => true,
+            Some(MissingBehavior::Null | MissingBehavior::Default(_)) => false,
+            None => {
+                !self.type_.is_optional()
+                    && self.default.is_none()
+                    && self.computed_default.is_none()
+            }
+        }
+    }
+
+    /// Sets what an optional field absent from a record is filled with,
+    /// regardless of its default. `TransparentParser::new` rejects it for the
+    /// fields that are not optional.
+    #[must_use]
+    pub fn with_missing_behavior(mut self, missing: MissingBehavior) -> Self {
+        self.missing = Some(missing);
+        self
+    }
+
+    fn value_if_missing(&self, name: &str) -> DynResult<Value> {
+        match &self.missing {
+            Some(MissingBehavior::Null) => Ok(Value::None),
+            Some(MissingBehavior::Default(default)) => Ok(default.clone()),
+            Some(MissingBehavior::Error) => Err(ParseError::FieldMissing {
+                field_name: name.to_string(),
+            }
+            .into()),
+            None => self.maybe_use_default(name, None),
+        }
+    }
+
+    #[must_use]
+    pub fn with_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Sets the notation of the strings in a `Bytes` field. A string default
+    /// is decoded by `TransparentParser::new`.
+    #[must_use]
+    pub fn with_bytes_encoding(mut self, bytes_encoding: BytesEncoding) -> Self {
+        self.bytes_encoding = bytes_encoding;
+        self
+    }
+
+    fn decode_bytes(&self, value: &Value) -> Option<Result<Value, ParseError>> {
+        match (self.type_.unoptionalize(), value) {
+            (Type::Bytes, Value::String(s)) => self
+                .bytes_encoding
+                .decode(s)
+                .map(|decoded| decoded.map(|bytes| Value::Bytes(bytes.into()))),
+            _ => None,
+        }
+    }
+
+    /// Makes the integers and the numeric strings in a `DateTimeUtc` field be
+    /// read as Unix timestamps.
+    #[must_use]
+    pub fn with_epoch_timestamps(mut self, epoch: EpochTimestamps) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    fn convert_epoch(&self, value: &Value) -> Option<Result<Value, ParseError>> {
+        let epoch = self.epoch.as_ref()?;
+        let timestamp = match (self.type_.unoptionalize(), value) {
+            (Type::DateTimeUtc, Value::Int(i)) => *i,
+            (Type::DateTimeUtc, Value::String(s)) => s.trim().parse().ok()?,
+            _ => return None,
+        };
+        Some(epoch.to_datetime(timestamp).map(Value::DateTimeUtc))
+    }
+
+    fn replace_error(&self, name: &str, value: DynResult<Value>) -> DynResult<Value> {
+        match (value, self.on_error) {
+            (Err(_), OnError::Null) => Ok(Value::None),
+            (Err(_), OnError::Default) => self.maybe_use_default(name, None),
+            (value, _) => value,
+        }
     }
 
     pub fn maybe_use_default(
@@ -235,9 +732,10 @@ impl InnerSchemaField {
         name: &str,
         value: Option<Result<Value, Box<ConversionError>>>,
     ) -> DynResult<Value> {
-        match value {
-            Some(value) => Ok(value?),
-            None => self.default.clone().ok_or(
+        match (value, &self.computed_default) {
+            (Some(value), _) => Ok(value?),
+            (None, Some(computed_default)) => Ok(computed_default()),
+            (None, None) => self.default.clone().ok_or(
                 ParseError::NoDefault {
                     field_name: name.to_string(),
                 }
@@ -245,92 +743,366 @@ impl InnerSchemaField {
             ),
         }
     }
-}
 
-fn prepare_plaintext_string(bytes: &[u8]) -> PrepareStringResult {
-    Ok(from_utf8(bytes)?.trim().to_string())
+    /// Casts a value provided by a reader to the type declared in the schema,
+    /// if such a cast is needed. Fails if the result still doesn't fit the type.
+    pub fn coerce(&self, name: &str, value: &Value, options: &CoercionOptions) -> DynResult<Value> {
+        let is_null = matches!(value, Value::String(s) if options.is_null_sentinel(s));
+        if is_null && self.type_.is_optional() {
+            return Ok(self.default.clone().unwrap_or(Value::None));
+        }
+        let coerced = if is_null {
+            Err(ParseError::NullInNonOptionalField.into())
+        } else if let Some(decoded) = self.decode_bytes(value) {
+            decoded.map_err(Into::into)
+        } else if let Some(converted) = self.convert_epoch(value) {
+            converted.map_err(Into::into)
+        } else {
+            value
+                .try_coerce_with(&self.type_, options)
+                .map_err(Into::into)
+        };
+        coerced
+            .and_then(|coerced| {
+                if let Some(constraints) = &self.constraints {
+                    constraints.check(&coerced)?;
+                }
+                Ok(coerced)
+            })
+            .map_err(|e| {
+                ParseError::SchemaNotSatisfied {
+                    field_name: name.to_string(),
+                    value: value.to_string(),
+                    type_: self.type_.clone(),
+                    error: e,
+                }
+                .into()
+            })
+    }
 }
 
-pub trait Parser: Send {
-    fn parse(&mut self, data: &ReaderContext) -> ParseResult;
-    fn on_new_source_started(&mut self, metadata: &SourceMetadata);
-    fn column_count(&self) -> usize;
+/// Bounds on the values of a field. All of them are inclusive, and the ones
+/// that don't apply to the type of a value are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldConstraints {
+    // Bounds for `Int` and `Float` values
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    // Bounds for the number of characters in `String` values
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
 
-    fn short_description(&self) -> Cow<'static, str> {
-        type_name::<Self>().into()
+impl FieldConstraints {
+    fn check(&self, value: &Value) -> Result<(), ParseError> {
+        #[allow(clippy::cast_precision_loss)]
+        let number = match value {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(f.0),
+            _ => None,
+        };
+        if let Some(number) = number {
+            if self.min.is_some_and(|min| number < min) || self.max.is_some_and(|max| number > max)
+            {
+                return Err(CoerceError::ValueOutOfRange(value.to_string()).into());
+            }
+        }
+        if let Value::String(s) = value {
+            let length = s.chars().count();
+            if self.min_length.is_some_and(|min| length < min)
+                || self.max_length.is_some_and(|max| length > max)
+            {
+                return Err(CoerceError::StringLengthOutOfRange(length).into());
+            }
+        }
+        Ok(())
     }
+}
 
-    fn session_type(&self) -> SessionType {
-        SessionType::Native
+/// The notation of the strings in a `Bytes` field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// Strings are decoded only if `CoercionOptions::base64_bytes` is set
+    #[default]
+    Raw,
+    Base64,
+    /// Two digits of either case per byte
+    Hex,
+}
+
+impl BytesEncoding {
+    fn decode(self, encoded: &str) -> Option<Result<Vec<u8>, ParseError>> {
+        match self {
+            Self::Raw => None,
+            Self::Base64 => Some(base64encoder.decode(encoded).map_err(Into::into)),
+            Self::Hex => Some(hex::decode(encoded).map_err(Into::into)),
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PreparedMessageHeader {
-    key: String,
-    value: Vec<u8>,
+/// The unit of the Unix timestamps in a `DateTimeUtc` field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpochUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
 }
 
-impl PreparedMessageHeader {
-    pub fn new(key: impl Into<String>, value: Vec<u8>) -> Self {
-        Self {
-            key: key.into(),
-            value,
+impl EpochUnit {
+    fn per_second(self) -> i64 {
+        match self {
+            Self::Seconds => 1,
+            Self::Milliseconds => 1_000,
+            Self::Microseconds => 1_000_000,
         }
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum FormattedDocument {
-    RawBytes(Vec<u8>),
-    Bson(BsonDocument),
+/// The way Unix timestamps are read in a `DateTimeUtc` field. A timestamp
+/// outside of `min_seconds..=max_seconds` is an error.
+#[derive(Clone, Copy, Debug)]
+pub struct EpochTimestamps {
+    pub unit: EpochUnit,
+    pub min_seconds: i64,
+    pub max_seconds: i64,
 }
 
-impl FormattedDocument {
-    pub fn into_raw_bytes(self) -> Result<Vec<u8>, FormatterError> {
-        if let Self::RawBytes(b) = self {
-            Ok(b)
-        } else {
-            Err(FormatterError::UnexpectedContextType)
+impl EpochTimestamps {
+    /// Accepts the instants from 1970-01-01 to 2100-01-01.
+    pub fn new(unit: EpochUnit) -> Self {
+        Self {
+            unit,
+            min_seconds: 0,
+            max_seconds: 4_102_444_800,
         }
     }
 
-    pub fn into_bson_document(self) -> Result<BsonDocument, FormatterError> {
-        if let Self::Bson(b) = self {
-            Ok(b)
-        } else {
-            Err(FormatterError::UnexpectedContextType)
+    fn to_datetime(self, timestamp: i64) -> Result<DateTimeUtc, ParseError> {
+        let per_second = self.unit.per_second();
+        let out_of_range =
+            || ParseError::Coercion(CoerceError::ValueOutOfRange(timestamp.to_string()));
+        if !(self.min_seconds..=self.max_seconds).contains(&timestamp.div_euclid(per_second)) {
+            return Err(out_of_range());
         }
+        timestamp
+            .checked_mul(1_000_000_000 / per_second)
+            .map(DateTimeUtc::new)
+            .ok_or_else(out_of_range)
     }
 }
 
-impl From<Vec<u8>> for FormattedDocument {
-    fn from(payload: Vec<u8>) -> FormattedDocument {
-        FormattedDocument::RawBytes(payload)
-    }
+fn prepare_plaintext_string(bytes: &[u8]) -> PrepareStringResult {
+    Ok(from_utf8(bytes)?.trim().to_string())
 }
 
-impl From<BsonDocument> for FormattedDocument {
-    fn from(payload: BsonDocument) -> FormattedDocument {
-        FormattedDocument::Bson(payload)
-    }
+/// Counters of the work done by a parser. Only the rows are counted as
+/// events, time advancements are not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParserStats {
+    pub events: u64,
+    pub rows_with_errors: u64,
+    pub errors: u64,
+    // Only grows for the contexts that carry a raw payload
+    pub bytes_consumed: u64,
 }
 
-#[derive(Debug)]
-pub struct FormatterContext {
-    pub payloads: Vec<FormattedDocument>,
-    pub key: Key,
-    pub values: Vec<Value>,
-    pub time: Timestamp,
-    pub diff: isize,
+/// Outcomes of the values parsed for a single field. A value that is absent in
+/// the input and taken from the default counts as defaulted, not as ok.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FieldStats {
+    pub ok: u64,
+    pub defaulted: u64,
+    pub errored: u64,
 }
 
-impl FormatterContext {
-    pub fn new(
-        payloads: Vec<impl Into<FormattedDocument>>,
-        key: Key,
-        values: Vec<Value>,
-        time: Timestamp,
+impl FieldStats {
+    fn record(&mut self, value: &DynResult<Value>, defaulted: bool) {
+        match value {
+            Err(_) => self.errored += 1,
+            Ok(_) if defaulted => self.defaulted += 1,
+            Ok(_) => self.ok += 1,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The running summary of the numeric values of a field, taken as floats.
+/// The errors and the missing values are skipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumericStats {
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Default for NumericStats {
+    fn default() -> Self {
+        Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl NumericStats {
+    // Only the numeric fields are summarized
+    fn for_type(type_: &Type) -> Option<Self> {
+        matches!(
+            type_.unoptionalize(),
+            Type::Int | Type::Int32 | Type::Int128 | Type::Float | Type::Decimal
+        )
+        .then(Self::default)
+    }
+
+    fn record(&mut self, value: &Value) {
+        #[allow(clippy::cast_precision_loss)]
+        let value = match value {
+            Value::Int(i) => *i as f64,
+            Value::Int128(i) => *i as f64,
+            Value::Float(f) => f.0,
+            Value::Decimal(d) => match f64::try_from(*d) {
+                Ok(f) => f,
+                Err(_) => return,
+            },
+            _ => return,
+        };
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+impl ParserStats {
+    fn record(&mut self, events: &[ParsedEventWithErrors]) {
+        for event in events {
+            let (key, values) = match event {
+                ParsedEventWithErrors::Insert((key, values))
+                | ParsedEventWithErrors::Delete((key, values))
+                | ParsedEventWithErrors::Upsert((key, values)) => (key, values),
+                ParsedEventWithErrors::AdvanceTime => continue,
+            };
+            let key_errors = usize::from(matches!(key, Some(Err(_))));
+            let errors = key_errors + values.iter().filter(|value| value.is_err()).count();
+            self.events += 1;
+            if errors > 0 {
+                self.rows_with_errors += 1;
+                self.errors += errors as u64;
+            }
+        }
+    }
+}
+
+pub trait Parser: Send {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult;
+
+    /// Parses the contexts in order, as if `parse` was called for each of
+    /// them. Stops at the first context that fails and reports its index.
+    fn parse_batch(&mut self, contexts: &[ReaderContext]) -> ParseResult {
+        let mut events = Vec::with_capacity(contexts.len());
+        for (index, context) in contexts.iter().enumerate() {
+            let parsed = self
+                .parse(context)
+                .map_err(|error| ParseError::BatchContextFailed { index, error })?;
+            events.extend(parsed);
+        }
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata);
+    fn column_count(&self) -> usize;
+
+    fn short_description(&self) -> Cow<'static, str> {
+        type_name::<Self>().into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        SessionType::Native
+    }
+
+    /// Drops the state accumulated from the parsed events, so that a rewound
+    /// source is parsed the same way as the first time.
+    fn reset(&mut self) {}
+
+    /// The counters accumulated since the parser was created. They are kept
+    /// when the parser is reset.
+    fn stats(&self) -> ParserStats {
+        ParserStats::default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreparedMessageHeader {
+    key: String,
+    value: Vec<u8>,
+}
+
+impl PreparedMessageHeader {
+    pub fn new(key: impl Into<String>, value: Vec<u8>) -> Self {
+        Self {
+            key: key.into(),
+            value,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FormattedDocument {
+    RawBytes(Vec<u8>),
+    Bson(BsonDocument),
+}
+
+impl FormattedDocument {
+    pub fn into_raw_bytes(self) -> Result<Vec<u8>, FormatterError> {
+        if let Self::RawBytes(b) = self {
+            Ok(b)
+        } else {
+            Err(FormatterError::UnexpectedContextType)
+        }
+    }
+
+    pub fn into_bson_document(self) -> Result<BsonDocument, FormatterError> {
+        if let Self::Bson(b) = self {
+            Ok(b)
+        } else {
+            Err(FormatterError::UnexpectedContextType)
+        }
+    }
+}
+
+impl From<Vec<u8>> for FormattedDocument {
+    fn from(payload: Vec<u8>) -> FormattedDocument {
+        FormattedDocument::RawBytes(payload)
+    }
+}
+
+impl From<BsonDocument> for FormattedDocument {
+    fn from(payload: BsonDocument) -> FormattedDocument {
+        FormattedDocument::Bson(payload)
+    }
+}
+
+#[derive(Debug)]
+pub struct FormatterContext {
+    pub payloads: Vec<FormattedDocument>,
+    pub key: Key,
+    pub values: Vec<Value>,
+    pub time: Timestamp,
+    pub diff: isize,
+}
+
+impl FormatterContext {
+    pub fn new(
+        payloads: Vec<impl Into<FormattedDocument>>,
+        key: Key,
+        values: Vec<Value>,
+        time: Timestamp,
         diff: isize,
     ) -> FormatterContext {
         let mut prepared_payloads: Vec<FormattedDocument> = Vec::with_capacity(payloads.len());
@@ -425,15 +1197,9 @@ pub enum FormatterError {
     #[error("type {type_:?} is not bson-serializable")]
     TypeNonBsonSerializable { type_: Type },
 
-    #[error("Error value is not json-serializable")]
-    ErrorValueNonJsonSerializable,
-
     #[error("Error value is not bson-serializable")]
     ErrorValueNonBsonSerializable,
 
-    #[error("Pending value is not json-serializable")]
-    PendingValueNonJsonSerializable,
-
     #[error("Pending value is not bson-serializable")]
     PendingValueNonBsonSerializable,
 
@@ -451,6 +1217,9 @@ pub enum FormatterError {
 
     #[error("CSV separator must be a 8-bit character, but '{0}' is provided")]
     UnsupportedCsvSeparator(char),
+
+    #[error(transparent)]
+    Coercion(#[from] CoerceError),
 }
 
 pub trait Formatter: Send {
@@ -553,8 +1322,16 @@ fn parse_str_with_type(raw_value: &str, type_: &Type) -> Result<Value, DynError>
             | Type::DateTimeUtc
             | Type::DateTimeNaive
             | Type::Duration
+            | Type::Decimal
+            | Type::Uuid
+            | Type::Date
+            | Type::Time
+            | Type::Int32
+            | Type::Int128
             | Type::Array(_, _)
             | Type::List(_)
+            | Type::FixedArray { .. }
+            | Type::Map(_)
             | Type::Tuple(_) => return Ok(Value::None),
             // "null" is ambiguous, since it can also correspond to a serialized JSON
             // Anything else can be safely treated as a `Value::None`
@@ -564,9 +1341,21 @@ fn parse_str_with_type(raw_value: &str, type_: &Type) -> Result<Value, DynError>
     }
     match type_.unoptionalize() {
         Type::Any | Type::String => Ok(Value::from(raw_value)),
+        Type::Enum(variants) => {
+            ensure_enum_variant(raw_value, variants)?;
+            Ok(Value::from(raw_value))
+        }
         Type::Bool => Ok(Value::Bool(parse_bool_advanced(raw_value)?)),
         Type::Int => Ok(Value::Int(raw_value.parse()?)),
         Type::Float => Ok(Value::Float(raw_value.parse()?)),
+        Type::Uuid => Ok(Value::Uuid(parse_uuid(raw_value)?)),
+        Type::Date => Ok(Value::Date(parse_date(raw_value, &[])?)),
+        Type::Time => Ok(Value::Time(parse_time(raw_value)?)),
+        Type::Int32 => Ok(Value::Int(ensure_int32(raw_value.parse()?)?)),
+        Type::Int128 => Ok(Value::Int128(raw_value.parse()?)),
+        Type::FixedString { len, pad, align } => Ok(Value::from(trim_fixed_string(
+            raw_value, *len, *pad, *align,
+        )?)),
         Type::Json => {
             let json: JsonValue = serde_json::from_str(raw_value)?;
             Ok(Value::from(json))
@@ -597,7 +1386,12 @@ fn parse_str_with_type(raw_value: &str, type_: &Type) -> Result<Value, DynError>
             let bytes = base64::engine::general_purpose::STANDARD.decode(raw_value)?;
             Ok(Value::Bytes(bytes.into()))
         }
-        Type::Array(_, _) | Type::List(_) | Type::Tuple(_) => {
+        Type::Decimal => Ok(Value::Decimal(raw_value.parse()?)),
+        Type::Array(_, _)
+        | Type::List(_)
+        | Type::FixedArray { .. }
+        | Type::Map(_)
+        | Type::Tuple(_) => {
             let json: JsonValue = serde_json::from_str(raw_value)?;
             let value =
                 parse_value_from_json(&json, type_).ok_or(ParseError::MalformedComplexField)?;
@@ -821,7 +1615,7 @@ impl Parser for DsvParser {
             TokenizedEntries(event, tokenized_entries) => {
                 self.parse_tokenized_entries(*event, tokenized_entries)
             }
-            KeyValue((_key, value)) => match value {
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
                 Some(bytes) => self.parse_bytes_simple(DataEventType::Insert, bytes), // In Kafka we only have additions now
                 None => Err(ParseError::EmptyKafkaPayload.into()),
             },
@@ -844,6 +1638,295 @@ impl Parser for DsvParser {
     }
 }
 
+/// Parses CSV records with a configurable delimiter.
+///
+/// Unlike `DsvParser`, it handles quoted fields, so that a field may contain
+/// the delimiter, a line break or an escaped quote. A payload may contain
+/// several records. A payload ending inside a quoted field fails as a whole.
+pub struct CsvParser {
+    delimiter: u8,
+    has_header: bool,
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+
+    metadata_column_value: Value,
+    header: Vec<String>,
+    header_read: bool,
+}
+
+impl CsvParser {
+    pub fn new(
+        delimiter: u8,
+        has_header: bool,
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<CsvParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(CsvParser {
+            delimiter,
+            has_header,
+            header: if has_header {
+                Vec::new()
+            } else {
+                value_field_names.clone()
+            },
+            value_field_names,
+            schema,
+            metadata_column_value: Value::None,
+            header_read: !has_header,
+        })
+    }
+
+    fn parse_header(&mut self, tokens: Vec<String>) -> Result<(), ParseError> {
+        for name in &self.value_field_names {
+            if name != METADATA_FIELD_NAME && !tokens.contains(name) {
+                return Err(ParseError::FieldsNotFoundInHeader {
+                    parsed: tokens,
+                    requested: self.value_field_names.clone(),
+                });
+            }
+        }
+        self.header = tokens;
+        self.header_read = true;
+        Ok(())
+    }
+
+    fn parse_record(&self, event: DataEventType, tokens: &[String]) -> ParsedEventWithErrors {
+        let expected = self.header.len();
+        let mut values = Vec::with_capacity(self.value_field_names.len());
+        for name in &self.value_field_names {
+            let value = if name == METADATA_FIELD_NAME {
+                Ok(self.metadata_column_value.clone())
+            } else if tokens.len() > expected {
+                Err(ParseError::CsvTokensCountMismatch {
+                    actual: tokens.len(),
+                    expected,
+                }
+                .into())
+            } else {
+                let schema_item = &self.schema[name];
+                let index = self
+                    .header
+                    .iter()
+                    .position(|column| column == name)
+                    .expect("all value fields must be present in the header");
+                if let Some(token) = tokens.get(index) {
+                    parse_with_type(token, schema_item, name)
+                } else if let Some(default) = &schema_item.default {
+                    Ok(default.clone())
+                } else {
+                    Err(ParseError::CsvTokensCountMismatch {
+                        actual: tokens.len(),
+                        expected,
+                    }
+                    .into())
+                }
+            };
+            values.push(value);
+        }
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+
+    fn parse_tokenized_entries(
+        &mut self,
+        event: DataEventType,
+        tokens: Vec<String>,
+    ) -> ParseResult {
+        if tokens.len() == 1 && tokens[0] == COMMIT_LITERAL {
+            return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
+        }
+        if !self.header_read {
+            self.parse_header(tokens)?;
+            return Ok(Vec::new());
+        }
+        Ok(vec![self.parse_record(event, &tokens)])
+    }
+
+    // The reader of the csv crate silently closes such a field at the end of the input
+    fn ensure_quotes_closed(&self, raw_bytes: &[u8]) -> Result<(), ParseError> {
+        let mut in_quotes = false;
+        let mut at_field_start = true;
+        let mut bytes = raw_bytes.iter().peekable();
+        while let Some(&byte) = bytes.next() {
+            if in_quotes {
+                // A doubled quote stands for a quote within the field
+                if byte == b'"' && bytes.next_if_eq(&&b'"').is_none() {
+                    in_quotes = false;
+                }
+                continue;
+            }
+            in_quotes = byte == b'"' && at_field_start;
+            at_field_start = matches!(byte, b'\n' | b'\r') || byte == self.delimiter;
+        }
+        if in_quotes {
+            return Err(ParseError::UnclosedCsvQuote);
+        }
+        Ok(())
+    }
+
+    fn parse_bytes(&mut self, event: DataEventType, raw_bytes: &[u8]) -> ParseResult {
+        self.ensure_quotes_closed(raw_bytes)?;
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_reader(raw_bytes);
+        let mut events = Vec::new();
+        for record in reader.byte_records() {
+            let record = record.map_err(ParseError::from)?;
+            let tokens: Vec<String> = record
+                .iter()
+                .map(|token| from_utf8(token).map(ToString::to_string))
+                .try_collect()
+                .map_err(ParseError::from)?;
+            events.extend(self.parse_tokenized_entries(event, tokens)?);
+        }
+        Ok(events)
+    }
+}
+
+impl Parser for CsvParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => self.parse_bytes(*event, raw_bytes),
+            TokenizedEntries(event, tokenized_entries) => {
+                self.parse_tokenized_entries(*event, tokenized_entries.clone())
+            }
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => self.parse_bytes(DataEventType::Insert, bytes),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+            Empty => Ok(vec![]),
+        }
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        if self.has_header && !metadata.commits_allowed_in_between() {
+            self.header_read = false;
+        }
+        let metadata_serialized: JsonValue = metadata.serialize();
+        self.metadata_column_value = metadata_serialized.into();
+    }
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// What [`FixedWidthParser`] does with a field starting beyond the end of a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldBeyondLine {
+    /// Use the default of the field, or fail it if there is none
+    #[default]
+    Default,
+    Error,
+}
+
+/// Parses the lines of fixed-width records, in which each field occupies the
+/// given range of bytes, as the `(start, length)` pair. The ranges may overlap.
+/// The padding whitespace around a value is removed before it is parsed.
+///
+/// A field cut short by the end of the line is read as far as it goes. A payload
+/// may contain several lines.
+pub struct FixedWidthParser {
+    value_field_names: Vec<String>,
+    ranges: Vec<(usize, usize)>,
+    schema: HashMap<String, InnerSchemaField>,
+    field_beyond_line: FieldBeyondLine,
+}
+
+impl FixedWidthParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        mut ranges: HashMap<String, (usize, usize)>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<FixedWidthParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        let ranges: Vec<_> = value_field_names
+            .iter()
+            .map(|name| {
+                ranges
+                    .remove(name)
+                    .ok_or_else(|| Error::FixedWidthRangeMissing(name.clone()))
+            })
+            .try_collect()?;
+        Ok(FixedWidthParser {
+            value_field_names,
+            ranges,
+            schema,
+            field_beyond_line: FieldBeyondLine::Default,
+        })
+    }
+
+    #[must_use]
+    pub fn with_field_beyond_line(mut self, field_beyond_line: FieldBeyondLine) -> Self {
+        self.field_beyond_line = field_beyond_line;
+        self
+    }
+
+    fn parse_field(
+        &self,
+        name: &str,
+        (start, length): (usize, usize),
+        line: &[u8],
+    ) -> DynResult<Value> {
+        let schema_item = &self.schema[name];
+        if start >= line.len() {
+            return match self.field_beyond_line {
+                FieldBeyondLine::Default => schema_item.maybe_use_default(name, None),
+                FieldBeyondLine::Error => Err(ParseError::FixedWidthFieldBeyondLine {
+                    field_name: name.to_string(),
+                    line_length: line.len(),
+                }
+                .into()),
+            };
+        }
+        let end = line.len().min(start.saturating_add(length));
+        let raw_value = from_utf8(&line[start..end]).map_err(ParseError::from)?;
+        parse_with_type(raw_value.trim(), schema_item, name)
+    }
+
+    fn parse_line(&self, event: DataEventType, line: &[u8]) -> ParsedEventWithErrors {
+        if line == COMMIT_LITERAL.as_bytes() {
+            return ParsedEventWithErrors::AdvanceTime;
+        }
+        let values = zip(&self.value_field_names, &self.ranges)
+            .map(|(name, range)| self.parse_field(name, *range, line))
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+
+    fn parse_bytes(&self, event: DataEventType, raw_bytes: &[u8]) -> Vec<ParsedEventWithErrors> {
+        raw_bytes
+            .split(|byte| *byte == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(|line| self.parse_line(event, line))
+            .collect()
+    }
+}
+
+impl Parser for FixedWidthParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(self.parse_bytes(*event, raw_bytes)),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(self.parse_bytes(DataEventType::Insert, bytes)),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+            Empty => Ok(vec![]),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
 fn value_from_bytes(bytes: &[u8], parse_utf8: bool) -> DynResult<Value> {
     if parse_utf8 {
         Ok(Value::String(prepare_plaintext_string(bytes)?.into()))
@@ -903,7 +1986,7 @@ impl Parser for IdentityParser {
                 value_from_bytes(raw_bytes, self.parse_utf8),
                 Ok(None),
             ),
-            KeyValue((key, value)) => match value {
+            KeyValue((key, value)) | KeyValueWithHeaders((key, value, _)) => match value {
                 Some(bytes) => (
                     DataEventType::Insert,
                     self.key_generation_policy.generate(key, self.parse_utf8),
@@ -1100,222 +2183,6 @@ pub struct DebeziumMessageParser {
     db_type: DebeziumDBType,
 }
 
-fn parse_list_from_json(values: &[JsonValue], dtype: &Type) -> Option<Value> {
-    let mut list = Vec::with_capacity(values.len());
-    for value in values {
-        list.push(parse_value_from_json(value, dtype)?);
-    }
-    Some(Value::from(list))
-}
-
-fn parse_tuple_from_json(values: &[JsonValue], dtypes: &[Type]) -> Option<Value> {
-    if values.len() != dtypes.len() {
-        return None;
-    }
-    let mut tuple = Vec::with_capacity(values.len());
-    for (value, dtype) in values.iter().zip_eq(dtypes.iter()) {
-        tuple.push(parse_value_from_json(value, dtype)?);
-    }
-    Some(Value::from(tuple))
-}
-
-fn parse_ndarray_from_json(value: &JsonMap<String, JsonValue>, dtype: &Type) -> Option<Value> {
-    let JsonValue::Array(ref elements) = value["elements"] else {
-        return None;
-    };
-    let JsonValue::Array(ref json_field_shape) = value["shape"] else {
-        return None;
-    };
-    let mut shape = Vec::new();
-    for shape_element in json_field_shape {
-        let JsonValue::Number(shape_element) = shape_element else {
-            return None;
-        };
-        let shape_element = shape_element.as_u64()?;
-        let Ok(shape_element) = TryInto::<usize>::try_into(shape_element) else {
-            return None;
-        };
-        shape.push(shape_element);
-    }
-
-    match dtype {
-        Type::Int => {
-            let mut flat_elements = Vec::new();
-            for element in elements {
-                let JsonValue::Number(number_element) = element else {
-                    return None;
-                };
-                let int_element = number_element.as_i64()?;
-                flat_elements.push(int_element);
-            }
-            let array_impl = ArrayD::<i64>::from_shape_vec(shape, flat_elements);
-            if let Ok(array_impl) = array_impl {
-                Some(Value::from(array_impl))
-            } else {
-                None
-            }
-        }
-        Type::Float => {
-            let mut flat_elements = Vec::new();
-            for element in elements {
-                let JsonValue::Number(number_element) = element else {
-                    return None;
-                };
-                let float_element = number_element.as_f64()?;
-                flat_elements.push(float_element);
-            }
-            let array_impl = ArrayD::<f64>::from_shape_vec(shape, flat_elements);
-            if let Ok(array_impl) = array_impl {
-                Some(Value::from(array_impl))
-            } else {
-                None
-            }
-        }
-        type_ => {
-            unreachable!("ndarray elements can only be either int or float, but {type_} was used")
-        }
-    }
-}
-
-fn create_bincoded_value(value: &Value) -> Result<String, FormatterError> {
-    let raw_bytes = bincode::serialize(value).map_err(|e| *e)?;
-    let encoded = base64::engine::general_purpose::STANDARD.encode(raw_bytes);
-    Ok(encoded)
-}
-
-fn parse_bincoded_value(s: &str) -> Result<Value, ParseError> {
-    let raw_bytes = base64::engine::general_purpose::STANDARD.decode(s)?;
-    Ok(bincode::deserialize::<Value>(&raw_bytes).map_err(|e| *e)?)
-}
-
-/// Parses a value of type `dtype` from JSON variable `value`.
-/// If the field is not present or has incorrect format in the
-/// given payload, returns `None`. This `None` is further converted
-/// into `ParseError::FailedToParseFromJson` containing verbose
-/// information about parsing problem.
-fn parse_value_from_json(value: &JsonValue, dtype: &Type) -> Option<Value> {
-    if value.is_null() {
-        if dtype.is_optional() {
-            return Some(Value::None);
-        }
-        return None;
-    }
-    match (dtype.unoptionalize(), value) {
-        (Type::Bool | Type::Any, JsonValue::Bool(v)) => Some(Value::Bool(*v)),
-        // Numbers parsing
-        (Type::Int, JsonValue::Number(v)) => {
-            let i64_field = v.as_i64()?;
-            Some(Value::from(i64_field))
-        }
-        (Type::Float, JsonValue::Number(v)) => {
-            let f64_field = v.as_f64()?;
-            Some(Value::from(f64_field))
-        }
-        (Type::Duration, JsonValue::Number(v)) => {
-            let duration_ns = v.as_i64()?;
-            let engine_duration = EngineDuration::new_with_unit(duration_ns, "ns")
-                .expect("new_with_unit can't fail when 'ns' is used as a unit");
-            Some(Value::Duration(engine_duration))
-        }
-        (Type::Any, JsonValue::Number(v)) => {
-            if let Some(parsed_i64) = v.as_i64() {
-                Some(Value::from(parsed_i64))
-            } else {
-                v.as_f64().map(Value::from)
-            }
-        }
-        // Strings parsing
-        (Type::String | Type::Any, JsonValue::String(s)) => Some(Value::from(s.as_str())),
-        (Type::Bytes, JsonValue::String(s)) => {
-            let decoded = base64::engine::general_purpose::STANDARD.decode(s);
-            if let Ok(decoded) = decoded {
-                Some(Value::Bytes(decoded.into()))
-            } else {
-                None
-            }
-        }
-        (Type::PyObjectWrapper, JsonValue::String(s)) => parse_bincoded_value(s).ok(),
-        (Type::Pointer, JsonValue::String(s)) => parse_pathway_pointer(s).ok(),
-        (Type::DateTimeUtc, JsonValue::String(s)) => {
-            let engine_datetime = DateTimeUtc::strptime(s, "%Y-%m-%dT%H:%M:%S%.f%z");
-            if let Ok(engine_datetime) = engine_datetime {
-                Some(Value::DateTimeUtc(engine_datetime))
-            } else {
-                None
-            }
-        }
-        (Type::DateTimeNaive, JsonValue::String(s)) => {
-            let engine_datetime = DateTimeNaive::strptime(s, "%Y-%m-%dT%H:%M:%S%.f");
-            if let Ok(engine_datetime) = engine_datetime {
-                Some(Value::DateTimeNaive(engine_datetime))
-            } else {
-                None
-            }
-        }
-        (Type::Json, value) => Some(Value::from(value.clone())),
-        (Type::Tuple(dtypes), JsonValue::Array(v)) => parse_tuple_from_json(v, dtypes),
-        (Type::List(arg), JsonValue::Array(v)) => parse_list_from_json(v, arg),
-        (Type::Array(_, nested_type), JsonValue::Object(v)) => {
-            parse_ndarray_from_json(v, nested_type.as_ref())
-        }
-        _ => None,
-    }
-}
-
-fn serialize_value_to_json(value: &Value) -> Result<JsonValue, FormatterError> {
-    match value {
-        Value::None => Ok(JsonValue::Null),
-        Value::Int(i) => Ok(json!(i)),
-        Value::Float(f) => Ok(json!(f)),
-        Value::Bool(b) => Ok(json!(b)),
-        Value::String(s) => Ok(json!(s)),
-        Value::Pointer(p) => Ok(json!(p.to_string())),
-        Value::Tuple(t) => {
-            let mut items = Vec::with_capacity(t.len());
-            for item in t.iter() {
-                items.push(serialize_value_to_json(item)?);
-            }
-            Ok(JsonValue::Array(items))
-        }
-        Value::Bytes(b) => {
-            let encoded = base64::engine::general_purpose::STANDARD.encode(b);
-            Ok(json!(encoded))
-        }
-        Value::IntArray(a) => {
-            let mut flat_elements = Vec::with_capacity(a.len());
-            for item in a.iter() {
-                flat_elements.push(json!(item));
-            }
-            let serialized_values = json!({
-                "shape": a.shape(),
-                "elements": flat_elements,
-            });
-            Ok(serialized_values)
-        }
-        Value::FloatArray(a) => {
-            let mut flat_elements = Vec::with_capacity(a.len());
-            for item in a.iter() {
-                flat_elements.push(json!(item));
-            }
-            let serialized_values = json!({
-                "shape": a.shape(),
-                "elements": flat_elements,
-            });
-            Ok(serialized_values)
-        }
-        Value::DateTimeNaive(dt) => Ok(json!(dt.to_string())),
-        Value::DateTimeUtc(dt) => Ok(json!(dt.to_string())),
-        Value::Duration(d) => Ok(json!(d.nanoseconds())),
-        Value::Json(j) => Ok((**j).clone()),
-        Value::PyObjectWrapper(_) => {
-            let encoded = create_bincoded_value(value)?;
-            Ok(json!(encoded))
-        }
-        Value::Error => Err(FormatterError::ErrorValueNonJsonSerializable),
-        Value::Pending => Err(FormatterError::PendingValueNonJsonSerializable),
-    }
-}
-
 fn values_by_names_from_json(
     payload: &JsonValue,
     field_names: &[String],
@@ -1501,278 +2368,4925 @@ impl DebeziumMessageParser {
     }
 }
 
-impl Parser for DebeziumMessageParser {
-    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
-        let (raw_key_change, raw_value_change) = match data {
-            RawBytes(event, raw_bytes) => {
-                // We don't use `event` type here, because it's Debezium message parser,
-                // whose messages can only arrive from Kafka.
-                //
-                // There is no snapshot scenario for Kafka.
-                //
-                // In addition, this branch of `match` condition is only used in unit-tests.
-                assert_eq!(event, &DataEventType::Insert);
+impl Parser for DebeziumMessageParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let (raw_key_change, raw_value_change) = match data {
+            RawBytes(event, raw_bytes) => {
+                // We don't use `event` type here, because it's Debezium message parser,
+                // whose messages can only arrive from Kafka.
+                //
+                // There is no snapshot scenario for Kafka.
+                //
+                // In addition, this branch of `match` condition is only used in unit-tests.
+                assert_eq!(event, &DataEventType::Insert);
+
+                let line = prepare_plaintext_string(raw_bytes)?;
+
+                let key_and_value: Vec<&str> = line.trim().split(&self.separator).collect();
+                if key_and_value.len() != 2 {
+                    return Err(ParseError::KeyValueTokensIncorrect(key_and_value.len()).into());
+                }
+                (key_and_value[0].to_string(), key_and_value[1].to_string())
+            }
+            KeyValue((k, v)) | KeyValueWithHeaders((k, v, _)) => {
+                let key = if let Some(bytes) = k {
+                    prepare_plaintext_string(bytes)?
+                } else {
+                    if self.key_field_names.is_some() {
+                        return Err(ParseError::EmptyKafkaPayload.into());
+                    }
+                    DEBEZIUM_EMPTY_KEY_PAYLOAD.to_string()
+                };
+                let value = match v {
+                    Some(bytes) => prepare_plaintext_string(bytes)?,
+                    None => return Err(ParseError::EmptyKafkaPayload.into()),
+                };
+                (key, value)
+            }
+            Diff(_) | TokenizedEntries(_, _) | Empty => {
+                return Err(ParseError::UnsupportedReaderContext.into());
+            }
+        };
+
+        let Ok(value_change) = serde_json::from_str(&raw_value_change) else {
+            return Err(ParseError::FailedToParseJson(raw_value_change).into());
+        };
+
+        let change_payload = match value_change {
+            JsonValue::Object(payload_value) => payload_value,
+            JsonValue::Null => return Ok(Vec::new()), // tombstone event for kafka: nothing to do for us
+            _ => {
+                return Err(ParseError::DebeziumFormatViolated(
+                    DebeziumFormatError::IncorrectJsonRoot,
+                )
+                .into())
+            }
+        };
+
+        let Ok(change_key) = serde_json::from_str::<JsonValue>(&raw_key_change) else {
+            return Err(ParseError::FailedToParseJson(raw_key_change).into());
+        };
+
+        if !change_payload.contains_key("payload") {
+            return Err(ParseError::DebeziumFormatViolated(
+                DebeziumFormatError::NoPayloadAtTopLevel,
+            )
+            .into());
+        }
+
+        match &change_payload["payload"]["op"] {
+            JsonValue::String(op) => match op.as_ref() {
+                "r" | "c" => {
+                    self.parse_read_or_create(&change_key["payload"], &change_payload["payload"])
+                }
+                "u" => self.parse_update(&change_key["payload"], &change_payload["payload"]),
+                "d" => self.parse_delete(&change_key["payload"], &change_payload["payload"]),
+                _ => Err(ParseError::UnsupportedDebeziumOperation(op.to_string()).into()),
+            },
+            _ => Err(ParseError::DebeziumFormatViolated(
+                DebeziumFormatError::OperationFieldMissing,
+            )
+            .into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+
+    fn session_type(&self) -> SessionType {
+        match self.db_type {
+            DebeziumDBType::Postgres => SessionType::Native,
+
+            // MongoDB events don't contain the previous state of the record
+            // therefore we can only do the upsert with the same key and the
+            // new value
+            DebeziumDBType::MongoDB => SessionType::Upsert,
+        }
+    }
+}
+
+pub struct JsonLinesParser {
+    key_field_names: Option<Vec<String>>,
+    value_field_names: Vec<String>,
+    column_paths: HashMap<String, String>,
+    field_absence_is_error: bool,
+    schema: HashMap<String, InnerSchemaField>,
+    metadata_column_value: Value,
+    session_type: SessionType,
+    // Only has an effect with the `simd-json` feature
+    simd: bool,
+}
+
+impl JsonLinesParser {
+    pub fn new(
+        key_field_names: Option<Vec<String>>,
+        value_field_names: Vec<String>,
+        column_paths: HashMap<String, String>,
+        field_absence_is_error: bool,
+        schema: HashMap<String, InnerSchemaField>,
+        session_type: SessionType,
+    ) -> Result<JsonLinesParser> {
+        ensure_all_fields_in_schema(&key_field_names, &value_field_names, &schema)?;
+        Ok(JsonLinesParser {
+            key_field_names,
+            value_field_names,
+            column_paths,
+            field_absence_is_error,
+            schema,
+            metadata_column_value: Value::None,
+            session_type,
+            simd: true,
+        })
+    }
+
+    /// Makes the parser use `serde_json` only, even if the crate is built
+    /// with the `simd-json` feature.
+    #[must_use]
+    pub fn without_simd(mut self) -> Self {
+        self.simd = false;
+        self
+    }
+
+    // Both ways give the same value, as the documents rejected by `simd-json`
+    // are parsed again with `serde_json`
+    fn parse_json(&self, text: &str) -> Option<JsonValue> {
+        if self.simd {
+            if let Some(payload) = parse_json_with_simd(text) {
+                return Some(payload);
+            }
+        }
+        serde_json::from_str(text).ok()
+    }
+
+    fn parse_json_payload(
+        &self,
+        data_event: DataEventType,
+        payload: &JsonValue,
+    ) -> ParsedEventWithErrors {
+        let key = self.key_field_names.as_ref().map(|key_field_names| {
+            values_by_names_from_json(
+                payload,
+                key_field_names,
+                &self.column_paths,
+                self.field_absence_is_error,
+                &self.schema,
+                &self.metadata_column_value,
+            )
+            .into_iter()
+            .collect()
+        });
+
+        let values = values_by_names_from_json(
+            payload,
+            &self.value_field_names,
+            &self.column_paths,
+            self.field_absence_is_error,
+            &self.schema,
+            &self.metadata_column_value,
+        );
+
+        ParsedEventWithErrors::new(self.session_type, data_event, key, values)
+    }
+
+    /// Creates a row for a line that is not a valid JSON. Such line only spoils
+    /// its own row, so that the other lines of the same payload are still parsed.
+    fn parse_malformed_line(&self, data_event: DataEventType, line: &str) -> ParsedEventWithErrors {
+        let field_value = |name: &String| -> DynResult<Value> {
+            if name == METADATA_FIELD_NAME {
+                Ok(self.metadata_column_value.clone())
+            } else {
+                Err(ParseError::FailedToParseJson(line.to_string()).into())
+            }
+        };
+        let key = self
+            .key_field_names
+            .as_ref()
+            .map(|key_field_names| key_field_names.iter().map(field_value).collect());
+        let values = self.value_field_names.iter().map(field_value).collect();
+        ParsedEventWithErrors::new(self.session_type, data_event, key, values)
+    }
+}
+
+#[cfg(feature = "simd-json")]
+fn parse_json_with_simd(text: &str) -> Option<JsonValue> {
+    // The document is parsed in place, so it needs a copy of its own
+    let mut bytes = text.as_bytes().to_vec();
+    simd_json::serde::from_slice(&mut bytes).ok()
+}
+
+#[cfg(not(feature = "simd-json"))]
+fn parse_json_with_simd(_text: &str) -> Option<JsonValue> {
+    None
+}
+
+impl Parser for JsonLinesParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let (data_event, line) = match data {
+            RawBytes(event, line) => {
+                let line = prepare_plaintext_string(line)?;
+                (*event, line)
+            }
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => {
+                if let Some(line) = value {
+                    let line = prepare_plaintext_string(line)?;
+                    (DataEventType::Insert, line)
+                } else {
+                    return Err(ParseError::EmptyKafkaPayload.into());
+                }
+            }
+            Diff(_) | TokenizedEntries(..) => {
+                return Err(ParseError::UnsupportedReaderContext.into());
+            }
+            Empty => return Ok(vec![]),
+        };
+
+        if line.is_empty() {
+            return Ok(vec![]);
+        }
+
+        if line == COMMIT_LITERAL {
+            return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
+        }
+
+        if let Some(payload) = self.parse_json(&line) {
+            return Ok(vec![self.parse_json_payload(data_event, &payload)]);
+        }
+
+        // The payload may also contain several newline-separated objects,
+        // in which case each of them becomes a separate event.
+        let lines: Vec<&str> = line
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.len() < 2 {
+            return Err(ParseError::FailedToParseJson(line).into());
+        }
+
+        let mut events = Vec::with_capacity(lines.len());
+        for line in lines {
+            let event = if line == COMMIT_LITERAL {
+                ParsedEventWithErrors::AdvanceTime
+            } else if let Some(payload) = self.parse_json(line) {
+                self.parse_json_payload(data_event, &payload)
+            } else {
+                self.parse_malformed_line(data_event, line)
+            };
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        let metadata_serialized: JsonValue = metadata.serialize();
+        self.metadata_column_value = metadata_serialized.into();
+    }
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.session_type
+    }
+}
+
+/// Decodes Avro datums written with the given writer schema.
+///
+/// The fields of the decoded record are mapped onto the columns by name, the
+/// fields that are not a part of the table schema are ignored.
+pub struct AvroParser {
+    writer_schema: AvroSchema,
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+}
+
+impl AvroParser {
+    pub fn new(
+        writer_schema: AvroSchema,
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<AvroParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(AvroParser {
+            writer_schema,
+            value_field_names,
+            schema,
+        })
+    }
+
+    fn parse_datum(&self, event: DataEventType, mut datum: &[u8]) -> ParsedEventWithErrors {
+        let fields =
+            apache_avro::from_avro_datum(&self.writer_schema, &mut datum, None).map(|record| {
+                match record {
+                    AvroValue::Record(fields) => fields.into_iter().collect(),
+                    _ => HashMap::new(),
+                }
+            });
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let fields: &HashMap<String, AvroValue> = match &fields {
+                    Ok(fields) => fields,
+                    Err(e) => return Err(ParseError::FailedToDecodeAvro(e.to_string()).into()),
+                };
+                let schema_item = &self.schema[name];
+                match fields.get(name) {
+                    Some(value) => {
+                        parse_value_from_avro(value, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromAvro {
+                                field_name: name.to_string(),
+                                value: format!("{value:?}"),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+}
+
+fn parse_value_from_avro(value: &AvroValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (_, AvroValue::Union(_, value)) => parse_value_from_avro(value, dtype),
+        (Type::Optional(_) | Type::Any, AvroValue::Null) => Some(Value::None),
+        (Type::Optional(arg), value) => parse_value_from_avro(value, arg),
+        (Type::Bool | Type::Any, AvroValue::Boolean(b)) => Some(Value::Bool(*b)),
+        (Type::Int | Type::Any, AvroValue::Int(i)) => Some(Value::Int((*i).into())),
+        (Type::Int | Type::Any, AvroValue::Long(i)) => Some(Value::Int(*i)),
+        (Type::Float, AvroValue::Int(i)) => Some(Value::Float(f64::from(*i).into())),
+        #[allow(clippy::cast_precision_loss)]
+        (Type::Float, AvroValue::Long(i)) => Some(Value::Float((*i as f64).into())),
+        (Type::Float | Type::Any, AvroValue::Float(f)) => Some(Value::Float(f64::from(*f).into())),
+        (Type::Float | Type::Any, AvroValue::Double(f)) => Some(Value::Float((*f).into())),
+        (Type::String | Type::Any, AvroValue::String(s) | AvroValue::Enum(_, s)) => {
+            Some(Value::from(s.as_str()))
+        }
+        (Type::Bytes | Type::Any, AvroValue::Bytes(b) | AvroValue::Fixed(_, b)) => {
+            Some(Value::Bytes(b.as_slice().into()))
+        }
+        (Type::DateTimeUtc, AvroValue::TimestampMillis(t)) => DateTimeUtc::from_timestamp(*t, "ms")
+            .ok()
+            .map(Value::DateTimeUtc),
+        (Type::DateTimeUtc, AvroValue::TimestampMicros(t)) => DateTimeUtc::from_timestamp(*t, "us")
+            .ok()
+            .map(Value::DateTimeUtc),
+        (Type::DateTimeUtc, AvroValue::TimestampNanos(t)) => {
+            Some(Value::DateTimeUtc(DateTimeUtc::new(*t)))
+        }
+        (Type::List(arg), AvroValue::Array(values)) => values
+            .iter()
+            .map(|value| parse_value_from_avro(value, arg))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for AvroParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(vec![self.parse_datum(*event, raw_bytes)]),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(vec![self.parse_datum(DataEventType::Insert, bytes)]),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Decodes Protobuf messages of a given type, described by a compiled
+/// `FileDescriptorSet`.
+///
+/// The fields of the message are mapped onto the columns by name, the fields
+/// that are not a part of the table schema are ignored.
+pub struct ProtobufParser {
+    message_descriptor: MessageDescriptor,
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+}
+
+impl ProtobufParser {
+    pub fn new(
+        descriptor_set: &[u8],
+        message_name: &str,
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<ProtobufParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        let pool = DescriptorPool::decode(descriptor_set).map_err(|e| {
+            Error::Other(ParseError::InvalidProtobufDescriptor(e.to_string()).into())
+        })?;
+        let message_descriptor = pool.get_message_by_name(message_name).ok_or_else(|| {
+            Error::Other(ParseError::ProtobufMessageNotFound(message_name.to_string()).into())
+        })?;
+        Ok(ProtobufParser {
+            message_descriptor,
+            value_field_names,
+            schema,
+        })
+    }
+
+    fn parse_message(&self, event: DataEventType, raw_bytes: &[u8]) -> ParsedEventWithErrors {
+        let message = DynamicMessage::decode(self.message_descriptor.clone(), raw_bytes);
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let message = match &message {
+                    Ok(message) => message,
+                    Err(e) => return Err(ParseError::FailedToDecodeProtobuf(e.to_string()).into()),
+                };
+                let schema_item = &self.schema[name];
+                let Some(field) = self.message_descriptor.get_field_by_name(name) else {
+                    return schema_item.maybe_use_default(name, None);
+                };
+                // Fields without presence tracking are always set, possibly to a default value
+                if field.supports_presence() && !message.has_field(&field) {
+                    return schema_item.maybe_use_default(name, None);
+                }
+                let value = message.get_field(&field);
+                parse_value_from_protobuf(&value, &schema_item.type_).ok_or_else(|| {
+                    ParseError::FailedToParseFromProtobuf {
+                        field_name: name.to_string(),
+                        value: format!("{value:?}"),
+                        type_: schema_item.type_.clone(),
+                    }
+                    .into()
+                })
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+}
+
+fn parse_value_from_protobuf(value: &ProtobufValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (Type::Optional(arg), value) => parse_value_from_protobuf(value, arg),
+        (Type::Bool | Type::Any, ProtobufValue::Bool(b)) => Some(Value::Bool(*b)),
+        (Type::Int | Type::Any, ProtobufValue::I32(i) | ProtobufValue::EnumNumber(i)) => {
+            Some(Value::Int((*i).into()))
+        }
+        (Type::Int | Type::Any, ProtobufValue::I64(i)) => Some(Value::Int(*i)),
+        (Type::Int | Type::Any, ProtobufValue::U32(i)) => Some(Value::Int((*i).into())),
+        (Type::Int | Type::Any, ProtobufValue::U64(i)) => i64::try_from(*i).ok().map(Value::Int),
+        (Type::Float | Type::Any, ProtobufValue::F32(f)) => {
+            Some(Value::Float(f64::from(*f).into()))
+        }
+        (Type::Float | Type::Any, ProtobufValue::F64(f)) => Some(Value::Float((*f).into())),
+        (Type::String | Type::Any, ProtobufValue::String(s)) => Some(Value::from(s.as_str())),
+        (Type::Bytes | Type::Any, ProtobufValue::Bytes(b)) => Some(Value::Bytes(b.as_ref().into())),
+        (Type::List(arg), ProtobufValue::List(values)) => values
+            .iter()
+            .map(|value| parse_value_from_protobuf(value, arg))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for ProtobufParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(vec![self.parse_message(*event, raw_bytes)]),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(vec![self.parse_message(DataEventType::Insert, bytes)]),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Decodes `MessagePack` maps. A payload may contain several consecutive records.
+///
+/// The keys of a map are matched with the columns by name, the keys that are
+/// not a part of the table schema are ignored.
+pub struct MsgPackParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+}
+
+impl MsgPackParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<MsgPackParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(MsgPackParser {
+            value_field_names,
+            schema,
+        })
+    }
+
+    fn parse_record(
+        &self,
+        event: DataEventType,
+        record: Result<MsgPackValue, ParseError>,
+    ) -> ParsedEventWithErrors {
+        let fields = record.and_then(|record| match record {
+            MsgPackValue::Map(entries) => Ok(entries
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.as_str()?.to_string(), value)))
+                .collect::<HashMap<_, _>>()),
+            _ => Err(ParseError::MsgPackRecordIsNotAMap),
+        });
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let fields = match &fields {
+                    Ok(fields) => fields,
+                    Err(e) => return Err(ParseError::FailedToDecodeMsgPack(e.to_string()).into()),
+                };
+                let schema_item = &self.schema[name];
+                match fields.get(name) {
+                    Some(value) => {
+                        parse_value_from_msgpack(value, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromMsgPack {
+                                field_name: name.to_string(),
+                                value: value.to_string(),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+
+    fn parse_bytes(
+        &self,
+        event: DataEventType,
+        mut raw_bytes: &[u8],
+    ) -> Vec<ParsedEventWithErrors> {
+        let mut events = Vec::new();
+        while !raw_bytes.is_empty() {
+            match rmpv::decode::read_value(&mut raw_bytes) {
+                Ok(record) => events.push(self.parse_record(event, Ok(record))),
+                Err(e) => {
+                    // The boundary of the next record can't be found after a malformed one
+                    let error = ParseError::FailedToDecodeMsgPack(e.to_string());
+                    events.push(self.parse_record(event, Err(error)));
+                    break;
+                }
+            }
+        }
+        events
+    }
+}
+
+fn parse_value_from_msgpack(value: &MsgPackValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (Type::Optional(_) | Type::Any, MsgPackValue::Nil) => Some(Value::None),
+        (Type::Optional(arg), value) => parse_value_from_msgpack(value, arg),
+        (Type::Bool | Type::Any, MsgPackValue::Boolean(b)) => Some(Value::Bool(*b)),
+        (Type::Int | Type::Any, MsgPackValue::Integer(i)) => i.as_i64().map(Value::Int),
+        #[allow(clippy::cast_precision_loss)]
+        (Type::Float, MsgPackValue::Integer(i)) => {
+            i.as_i64().map(|i| Value::Float((i as f64).into()))
+        }
+        (Type::Float | Type::Any, MsgPackValue::F32(f)) => Some(Value::Float(f64::from(*f).into())),
+        (Type::Float | Type::Any, MsgPackValue::F64(f)) => Some(Value::Float((*f).into())),
+        (Type::String | Type::Any, MsgPackValue::String(s)) => s.as_str().map(Value::from),
+        (Type::Bytes | Type::Any, MsgPackValue::Binary(b)) => {
+            Some(Value::Bytes(b.as_slice().into()))
+        }
+        (Type::List(arg), MsgPackValue::Array(values)) => values
+            .iter()
+            .map(|value| parse_value_from_msgpack(value, arg))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for MsgPackParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(self.parse_bytes(*event, raw_bytes)),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(self.parse_bytes(DataEventType::Insert, bytes)),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Decodes CBOR maps. A payload may contain several consecutive records.
+///
+/// The keys of a map are matched with the columns by name, the keys that are
+/// not a part of the table schema are ignored.
+pub struct CborParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+}
+
+impl CborParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<CborParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(CborParser {
+            value_field_names,
+            schema,
+        })
+    }
+
+    fn parse_record(
+        &self,
+        event: DataEventType,
+        record: Result<CborValue, ParseError>,
+    ) -> ParsedEventWithErrors {
+        let fields = record.and_then(|record| match record {
+            CborValue::Map(entries) => Ok(entries
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.into_text().ok()?, value)))
+                .collect::<HashMap<_, _>>()),
+            _ => Err(ParseError::CborRecordIsNotAMap),
+        });
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let fields = match &fields {
+                    Ok(fields) => fields,
+                    Err(e) => return Err(ParseError::FailedToDecodeCbor(e.to_string()).into()),
+                };
+                let schema_item = &self.schema[name];
+                match fields.get(name) {
+                    Some(value) => {
+                        parse_value_from_cbor(value, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromCbor {
+                                field_name: name.to_string(),
+                                value: format!("{value:?}"),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+
+    fn parse_bytes(
+        &self,
+        event: DataEventType,
+        mut raw_bytes: &[u8],
+    ) -> Vec<ParsedEventWithErrors> {
+        let mut events = Vec::new();
+        while !raw_bytes.is_empty() {
+            match ciborium::de::from_reader::<CborValue, _>(&mut raw_bytes) {
+                Ok(record) => events.push(self.parse_record(event, Ok(record))),
+                Err(e) => {
+                    // The boundary of the next record can't be found after a malformed one
+                    let error = ParseError::FailedToDecodeCbor(e.to_string());
+                    events.push(self.parse_record(event, Err(error)));
+                    break;
+                }
+            }
+        }
+        events
+    }
+}
+
+fn parse_value_from_cbor(value: &CborValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (_, CborValue::Tag(_, value)) => parse_value_from_cbor(value, dtype),
+        (Type::Optional(_) | Type::Any, CborValue::Null) => Some(Value::None),
+        (Type::Optional(arg), value) => parse_value_from_cbor(value, arg),
+        (Type::Bool | Type::Any, CborValue::Bool(b)) => Some(Value::Bool(*b)),
+        (Type::Int | Type::Any, CborValue::Integer(i)) => i64::try_from(*i).ok().map(Value::Int),
+        #[allow(clippy::cast_precision_loss)]
+        (Type::Float, CborValue::Integer(i)) => i64::try_from(*i)
+            .ok()
+            .map(|i| Value::Float((i as f64).into())),
+        (Type::Float | Type::Any, CborValue::Float(f)) => Some(Value::Float((*f).into())),
+        (Type::String | Type::Any, CborValue::Text(s)) => Some(Value::from(s.as_str())),
+        (Type::Bytes | Type::Any, CborValue::Bytes(b)) => Some(Value::Bytes(b.as_slice().into())),
+        (Type::List(arg), CborValue::Array(values)) => values
+            .iter()
+            .map(|value| parse_value_from_cbor(value, arg))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for CborParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(self.parse_bytes(*event, raw_bytes)),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(self.parse_bytes(DataEventType::Insert, bytes)),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// The representation of the `_id` field when it is used as a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BsonObjectIdKey {
+    Bytes,
+    HexString,
+}
+
+/// Reads a stream of BSON documents, such as the ones delivered by Mongo
+/// change streams, and produces an insertion for each document.
+///
+/// A malformed document results in a row of errors, and the rest of the
+/// payload is skipped, since the boundary of the next document can't be found.
+pub struct BsonParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    object_id_key: Option<BsonObjectIdKey>,
+}
+
+impl BsonParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<BsonParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(BsonParser {
+            value_field_names,
+            schema,
+            object_id_key: None,
+        })
+    }
+
+    /// Uses the `_id` `ObjectId` of each document as the key of its row.
+    #[must_use]
+    pub fn with_object_id_key(mut self, representation: BsonObjectIdKey) -> Self {
+        self.object_id_key = Some(representation);
+        self
+    }
+
+    fn parse_key(&self, document: &BsonDocument) -> KeyFieldsWithErrors {
+        let representation = self.object_id_key?;
+        let key = match document.get("_id") {
+            Some(BsonValue::ObjectId(id)) => match representation {
+                BsonObjectIdKey::Bytes => Ok(Value::Bytes(id.bytes().as_slice().into())),
+                BsonObjectIdKey::HexString => Ok(Value::from(id.to_hex().as_str())),
+            },
+            Some(_) => Err(ParseError::BsonIdIsNotAnObjectId.into()),
+            None => Err(ParseError::KeyFieldMissing {
+                field_name: "_id".to_string(),
+            }
+            .into()),
+        };
+        Some(key.map(|key| vec![key]))
+    }
+
+    fn parse_document(&self, document: &BsonDocument) -> ParsedEventWithErrors {
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let schema_item = &self.schema[name];
+                match document.get(name) {
+                    Some(value) => {
+                        parse_value_from_bson(value, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromBson {
+                                field_name: name.to_string(),
+                                value: value.to_string(),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(
+            SessionType::Native,
+            DataEventType::Insert,
+            self.parse_key(document),
+            values,
+        )
+    }
+
+    fn parse_bytes(&self, mut raw_bytes: &[u8]) -> Vec<ParsedEventWithErrors> {
+        let mut events = Vec::new();
+        while !raw_bytes.is_empty() {
+            match BsonDocument::from_reader(&mut raw_bytes) {
+                Ok(document) => events.push(self.parse_document(&document)),
+                Err(e) => {
+                    let error =
+                        || -> DynError { ParseError::FailedToDecodeBson(e.to_string()).into() };
+                    let key = self.object_id_key.map(|_| Err(error()));
+                    let values = self
+                        .value_field_names
+                        .iter()
+                        .map(|_| Err(error()))
+                        .collect();
+                    events.push(ParsedEventWithErrors::new(
+                        SessionType::Native,
+                        DataEventType::Insert,
+                        key,
+                        values,
+                    ));
+                    break;
+                }
+            }
+        }
+        events
+    }
+}
+
+fn parse_value_from_bson(value: &BsonValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (Type::Optional(_) | Type::Any, BsonValue::Null) => Some(Value::None),
+        (Type::Optional(arg), value) => parse_value_from_bson(value, arg.as_ref()),
+        (Type::Bool | Type::Any, BsonValue::Boolean(b)) => Some(Value::Bool(*b)),
+        (Type::Int | Type::Any, BsonValue::Int32(i)) => Some(Value::Int((*i).into())),
+        (Type::Int | Type::Any, BsonValue::Int64(i)) => Some(Value::Int(*i)),
+        (Type::Float, BsonValue::Int32(i)) => Some(Value::Float(f64::from(*i).into())),
+        #[allow(clippy::cast_precision_loss)]
+        (Type::Float, BsonValue::Int64(i)) => Some(Value::Float((*i as f64).into())),
+        (Type::Float | Type::Any, BsonValue::Double(f)) => Some(Value::Float((*f).into())),
+        (Type::String | Type::Any, BsonValue::String(s)) => Some(Value::from(s.as_str())),
+        (Type::String, BsonValue::ObjectId(id)) => Some(Value::from(id.to_hex().as_str())),
+        (Type::Bytes, BsonValue::ObjectId(id)) => Some(Value::Bytes(id.bytes().as_slice().into())),
+        (Type::Bytes | Type::Any, BsonValue::Binary(b)) => {
+            Some(Value::Bytes(b.bytes.as_slice().into()))
+        }
+        (Type::List(arg), BsonValue::Array(values)) => values
+            .iter()
+            .map(|value| parse_value_from_bson(value, arg.as_ref()))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        (Type::Map(arg), BsonValue::Document(document)) => document
+            .iter()
+            .map(|(key, value)| Some((key.as_str().into(), parse_value_from_bson(value, arg)?)))
+            .collect::<Option<BTreeMap<_, _>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for BsonParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(_, raw_bytes) => Ok(self.parse_bytes(raw_bytes)),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(self.parse_bytes(bytes)),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Reads a stream of YAML documents separated by `---` lines and produces an
+/// insertion for each document.
+///
+/// Every document is parsed on its own, so a malformed one only results in a
+/// row of errors.
+pub struct YamlParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+}
+
+impl YamlParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<YamlParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(YamlParser {
+            value_field_names,
+            schema,
+        })
+    }
+
+    fn parse_document(&self, event: DataEventType, document: &str) -> ParsedEventWithErrors {
+        let fields = match serde_yaml::from_str(document) {
+            Ok(YamlValue::Mapping(fields)) => Ok(fields),
+            Ok(_) => Err(ParseError::YamlDocumentIsNotAMapping.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let fields = match &fields {
+                    Ok(fields) => fields,
+                    Err(e) => return Err(ParseError::FailedToParseYamlDocument(e.clone()).into()),
+                };
+                let schema_item = &self.schema[name];
+                match fields.get(name.as_str()) {
+                    Some(value) => {
+                        parse_value_from_yaml(value, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromYaml {
+                                field_name: name.to_string(),
+                                value: format!("{value:?}"),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+
+    fn parse_bytes(&self, event: DataEventType, raw_bytes: &[u8]) -> ParseResult {
+        let stream = from_utf8(raw_bytes)?;
+        let mut events = Vec::new();
+        let mut document = String::new();
+        for line in stream.lines().chain(once("---")) {
+            if line.trim_end() != "---" {
+                document.push_str(line);
+                document.push('\n');
+                continue;
+            }
+            if !document.trim().is_empty() {
+                events.push(self.parse_document(event, &document));
+            }
+            document.clear();
+        }
+        Ok(events)
+    }
+}
+
+fn parse_value_from_yaml(value: &YamlValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (_, YamlValue::Tagged(tagged)) => parse_value_from_yaml(&tagged.value, dtype),
+        (Type::Optional(_) | Type::Any, YamlValue::Null) => Some(Value::None),
+        (Type::Optional(arg), value) => parse_value_from_yaml(value, arg.as_ref()),
+        (Type::Json, value) => serde_json::to_value(value).ok().map(Value::from),
+        (Type::Bool | Type::Any, YamlValue::Bool(b)) => Some(Value::Bool(*b)),
+        (Type::Int, YamlValue::Number(n)) => n.as_i64().map(Value::Int),
+        (Type::Float, YamlValue::Number(n)) => n.as_f64().map(Value::from),
+        (Type::Any, YamlValue::Number(n)) => n
+            .as_i64()
+            .map(Value::Int)
+            .or_else(|| n.as_f64().map(Value::from)),
+        (Type::String | Type::Any, YamlValue::String(s)) => Some(Value::from(s.as_str())),
+        (Type::List(arg), YamlValue::Sequence(values)) => values
+            .iter()
+            .map(|value| parse_value_from_yaml(value, arg.as_ref()))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+impl Parser for YamlParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => self.parse_bytes(*event, raw_bytes),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => self.parse_bytes(DataEventType::Insert, bytes),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// How [`TomlParser`] finds the fields placed in nested tables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TomlTables {
+    /// Only the top-level keys are fields, a nested table becomes a JSON value
+    #[default]
+    AsJson,
+    /// A dotted field name, such as `server.port`, is a path in nested tables
+    Flatten,
+}
+
+/// Reads a TOML document from each payload and produces an insertion with the
+/// values of its keys.
+///
+/// A malformed document only results in a row of errors.
+pub struct TomlParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    tables: TomlTables,
+}
+
+impl TomlParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<TomlParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        Ok(TomlParser {
+            value_field_names,
+            schema,
+            tables: TomlTables::default(),
+        })
+    }
+
+    #[must_use]
+    pub fn with_tables(mut self, tables: TomlTables) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    fn lookup<'a>(&self, document: &'a TomlDocument, name: &str) -> Option<&'a TomlItem> {
+        match self.tables {
+            TomlTables::AsJson => document.get(name),
+            TomlTables::Flatten => name
+                .split('.')
+                .try_fold(document.as_item(), |item, key| item.get(key)),
+        }
+    }
+
+    fn parse_document(&self, event: DataEventType, raw_bytes: &[u8]) -> ParsedEventWithErrors {
+        let document = from_utf8(raw_bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|document| document.parse::<TomlDocument>().map_err(|e| e.to_string()));
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|name| {
+                let document = match &document {
+                    Ok(document) => document,
+                    Err(e) => return Err(ParseError::FailedToParseTomlDocument(e.clone()).into()),
+                };
+                let schema_item = &self.schema[name];
+                match self.lookup(document, name) {
+                    Some(item) => {
+                        parse_value_from_toml(item, &schema_item.type_).ok_or_else(|| {
+                            ParseError::FailedToParseFromToml {
+                                field_name: name.to_string(),
+                                value: item.to_string().trim().to_string(),
+                                type_: schema_item.type_.clone(),
+                            }
+                            .into()
+                        })
+                    }
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, event, None, values)
+    }
+}
+
+fn parse_value_from_toml(item: &TomlItem, dtype: &Type) -> Option<Value> {
+    match (dtype.unoptionalize(), item) {
+        (_, TomlItem::Value(value)) => parse_toml_value(value, dtype),
+        (Type::Json | Type::Any, item) => toml_item_to_json(item).map(Value::from),
+        _ => None,
+    }
+}
+
+fn parse_toml_value(value: &TomlValue, dtype: &Type) -> Option<Value> {
+    match (dtype, value) {
+        (Type::Optional(arg), value) => parse_toml_value(value, arg.as_ref()),
+        (Type::Json, value) => toml_value_to_json(value).map(Value::from),
+        (Type::Bool | Type::Any, TomlValue::Boolean(b)) => Some(Value::Bool(*b.value())),
+        (Type::Int | Type::Any, TomlValue::Integer(i)) => Some(Value::Int(*i.value())),
+        (Type::Float | Type::Any, TomlValue::Float(f)) => Some(Value::from(*f.value())),
+        (Type::String | Type::Any, TomlValue::String(s)) => Some(Value::from(s.value().as_str())),
+        (Type::List(arg), TomlValue::Array(values)) => values
+            .iter()
+            .map(|value| parse_toml_value(value, arg.as_ref()))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::from),
+        (Type::Any, TomlValue::Array(_) | TomlValue::InlineTable(_)) => {
+            toml_value_to_json(value).map(Value::from)
+        }
+        _ => None,
+    }
+}
+
+fn toml_item_to_json(item: &TomlItem) -> Option<JsonValue> {
+    match item {
+        TomlItem::None => None,
+        TomlItem::Value(value) => toml_value_to_json(value),
+        TomlItem::Table(table) => toml_table_to_json(table),
+        TomlItem::ArrayOfTables(tables) => tables
+            .iter()
+            .map(|table| toml_table_to_json(table))
+            .collect::<Option<Vec<_>>>()
+            .map(JsonValue::Array),
+    }
+}
+
+// Datetimes have no JSON counterpart, so they are kept as strings
+fn toml_value_to_json(value: &TomlValue) -> Option<JsonValue> {
+    match value {
+        TomlValue::String(s) => Some(JsonValue::String(s.value().clone())),
+        TomlValue::Integer(i) => Some(JsonValue::from(*i.value())),
+        TomlValue::Float(f) => serde_json::Number::from_f64(*f.value()).map(JsonValue::Number),
+        TomlValue::Boolean(b) => Some(JsonValue::Bool(*b.value())),
+        TomlValue::Datetime(datetime) => Some(JsonValue::String(datetime.value().to_string())),
+        TomlValue::Array(values) => values
+            .iter()
+            .map(toml_value_to_json)
+            .collect::<Option<Vec<_>>>()
+            .map(JsonValue::Array),
+        TomlValue::InlineTable(table) => toml_table_to_json(table),
+    }
+}
+
+fn toml_table_to_json(table: &dyn TableLike) -> Option<JsonValue> {
+    table
+        .iter()
+        .map(|(key, item)| Some((key.to_string(), toml_item_to_json(item)?)))
+        .collect::<Option<JsonMap<_, _>>>()
+        .map(JsonValue::Object)
+}
+
+impl Parser for TomlParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => Ok(vec![self.parse_document(*event, raw_bytes)]),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(vec![self.parse_document(DataEventType::Insert, bytes)]),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Reads a stream of XML records and produces an insertion for each top-level
+/// element.
+///
+/// The values are extracted with paths starting at the record element, such as
+/// `order/customer` for the text of a nested element or `order/customer/@id`
+/// for an attribute. If there are several matching nodes, the first one is
+/// used. A field without a path is taken from the child element of the record
+/// with the same name. A malformed record results in a row of errors, and the
+/// rest of the payload is skipped.
+pub struct XmlParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    paths: Vec<Option<String>>,
+}
+
+impl XmlParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+        mut paths: HashMap<String, String>,
+    ) -> Result<XmlParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        if let Some(name) = paths.keys().find(|name| !schema.contains_key(*name)) {
+            return Err(Error::FieldNotInSchema {
+                name: name.clone(),
+                schema_keys: schema.keys().cloned().collect(),
+            });
+        }
+        let paths = value_field_names
+            .iter()
+            .map(|name| paths.remove(name))
+            .collect();
+        Ok(XmlParser {
+            value_field_names,
+            schema,
+            paths,
+        })
+    }
+
+    fn parse_record(
+        &self,
+        record_name: &str,
+        nodes: &HashMap<String, String>,
+    ) -> ParsedEventWithErrors {
+        let values = self
+            .value_field_names
+            .iter()
+            .zip(&self.paths)
+            .map(|(name, path)| {
+                let schema_item = &self.schema[name];
+                let node = match path {
+                    Some(path) => nodes.get(path),
+                    None => nodes.get(&format!("{record_name}/{name}")),
+                };
+                match node {
+                    Some(raw_value) => parse_with_type(raw_value, schema_item, name),
+                    None => schema_item.maybe_use_default(name, None),
+                }
+            })
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, DataEventType::Insert, None, values)
+    }
+
+    fn parse_bytes(&self, raw_bytes: &[u8]) -> Vec<ParsedEventWithErrors> {
+        let mut reader = XmlReader::from_reader(raw_bytes);
+        reader.config_mut().trim_text(true);
+        let mut events = Vec::new();
+        // The path to the current element and the text collected for it
+        let mut open_elements: Vec<(String, String)> = Vec::new();
+        let mut nodes = HashMap::new();
+        loop {
+            let step = Self::read_node(&mut reader, &mut open_elements, &mut nodes);
+            match step {
+                Ok(XmlStep::Continue) => {}
+                Ok(XmlStep::RecordFinished(record_name)) => {
+                    events.push(self.parse_record(&record_name, &nodes));
+                    nodes.clear();
+                }
+                Ok(XmlStep::Eof) if open_elements.is_empty() => break,
+                Ok(XmlStep::Eof) => {
+                    events.push(self.error_row("unclosed element"));
+                    break;
+                }
+                Err(message) => {
+                    events.push(self.error_row(&message));
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    fn read_node(
+        reader: &mut XmlReader<&[u8]>,
+        open_elements: &mut Vec<(String, String)>,
+        nodes: &mut HashMap<String, String>,
+    ) -> Result<XmlStep, String> {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        let element = match &event {
+            XmlEvent::Start(element) | XmlEvent::Empty(element) => element,
+            XmlEvent::Text(text) => {
+                if let Some((_, collected)) = open_elements.last_mut() {
+                    collected.push_str(&text.unescape().map_err(|e| e.to_string())?);
+                }
+                return Ok(XmlStep::Continue);
+            }
+            XmlEvent::CData(text) => {
+                if let Some((_, collected)) = open_elements.last_mut() {
+                    collected.push_str(from_utf8(text).map_err(|e| e.to_string())?);
+                }
+                return Ok(XmlStep::Continue);
+            }
+            XmlEvent::End(_) => return Ok(Self::close_element(open_elements, nodes)),
+            XmlEvent::Eof => return Ok(XmlStep::Eof),
+            XmlEvent::Decl(_) | XmlEvent::Comment(_) | XmlEvent::PI(_) | XmlEvent::DocType(_) => {
+                return Ok(XmlStep::Continue)
+            }
+        };
+        let name = element.name();
+        let name = from_utf8(name.as_ref()).map_err(|e| e.to_string())?;
+        let path = match open_elements.last() {
+            Some((parent, _)) => format!("{parent}/{name}"),
+            None => name.to_string(),
+        };
+        for attribute in element.attributes() {
+            let attribute = attribute.map_err(|e| e.to_string())?;
+            let key = from_utf8(attribute.key.as_ref()).map_err(|e| e.to_string())?;
+            let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+            nodes
+                .entry(format!("{path}/@{key}"))
+                .or_insert_with(|| value.into_owned());
+        }
+        open_elements.push((path, String::new()));
+        if matches!(event, XmlEvent::Empty(_)) {
+            return Ok(Self::close_element(open_elements, nodes));
+        }
+        Ok(XmlStep::Continue)
+    }
+
+    fn close_element(
+        open_elements: &mut Vec<(String, String)>,
+        nodes: &mut HashMap<String, String>,
+    ) -> XmlStep {
+        let Some((path, text)) = open_elements.pop() else {
+            return XmlStep::Continue;
+        };
+        if open_elements.is_empty() {
+            nodes.entry(path.clone()).or_insert(text);
+            XmlStep::RecordFinished(path)
+        } else {
+            nodes.entry(path).or_insert(text);
+            XmlStep::Continue
+        }
+    }
+
+    fn error_row(&self, message: &str) -> ParsedEventWithErrors {
+        let values = self
+            .value_field_names
+            .iter()
+            .map(|_| Err(ParseError::FailedToParseXml(message.to_string()).into()))
+            .collect();
+        ParsedEventWithErrors::new(SessionType::Native, DataEventType::Insert, None, values)
+    }
+}
+
+enum XmlStep {
+    Continue,
+    RecordFinished(String),
+    Eof,
+}
+
+impl Parser for XmlParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(_, raw_bytes) => Ok(self.parse_bytes(raw_bytes)),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => Ok(self.parse_bytes(bytes)),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Reads a whole Parquet file from the payload and produces an insertion for
+/// each of its rows.
+///
+/// The columns of the file that are not a part of the table schema are
+/// ignored.
+pub struct ParquetParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    column_types: HashMap<String, Type>,
+}
+
+impl ParquetParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<ParquetParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        let column_types = schema
+            .iter()
+            .map(|(name, field)| (name.clone(), field.type_.clone()))
+            .collect();
+        Ok(ParquetParser {
+            value_field_names,
+            schema,
+            column_types,
+        })
+    }
+
+    fn parse_bytes(&self, event: DataEventType, raw_bytes: &[u8]) -> ParseResult {
+        let reader = ParquetFileReader::new(Bytes::copy_from_slice(raw_bytes))
+            .map_err(ParseError::Parquet)?;
+        let mut events = Vec::new();
+        for row in reader.get_row_iter(None).map_err(ParseError::Parquet)? {
+            let row = row.map_err(ParseError::Parquet)?;
+            let row_map = parquet_row_into_values_map(&row, &self.column_types);
+            let values = self
+                .value_field_names
+                .iter()
+                .map(|name| self.schema[name].maybe_use_default(name, row_map.get(name).cloned()))
+                .collect();
+            events.push(ParsedEventWithErrors::new(
+                SessionType::Native,
+                event,
+                None,
+                values,
+            ));
+        }
+        Ok(events)
+    }
+}
+
+impl Parser for ParquetParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => self.parse_bytes(*event, raw_bytes),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => self.parse_bytes(DataEventType::Insert, bytes),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Reads an Arrow IPC stream from the payload and produces an insertion for
+/// each row of its record batches.
+///
+/// The columns of the stream that are not a part of the table schema are
+/// ignored, and the fields missing in the stream are taken from the defaults.
+/// A null slot becomes `Value::None`, so it's only accepted in optional fields.
+pub struct ArrowIpcParser {
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    column_types: HashMap<String, Type>,
+}
+
+impl ArrowIpcParser {
+    pub fn new(
+        value_field_names: Vec<String>,
+        schema: HashMap<String, InnerSchemaField>,
+    ) -> Result<ArrowIpcParser> {
+        ensure_all_fields_in_schema(&None, &value_field_names, &schema)?;
+        let column_types = schema
+            .iter()
+            .map(|(name, field)| (name.clone(), field.type_.clone()))
+            .collect();
+        Ok(ArrowIpcParser {
+            value_field_names,
+            schema,
+            column_types,
+        })
+    }
+
+    fn parse_bytes(&self, event: DataEventType, raw_bytes: &[u8]) -> ParseResult {
+        let reader =
+            ArrowIpcStreamReader::try_new(raw_bytes, None).map_err(ParseError::ArrowIpc)?;
+        let mut events = Vec::new();
+        for batch in reader {
+            let batch = batch.map_err(ParseError::ArrowIpc)?;
+            for row_map in columns_into_pathway_values(&batch, &self.column_types) {
+                let values = self
+                    .value_field_names
+                    .iter()
+                    .map(|name| {
+                        self.schema[name].maybe_use_default(name, row_map.get(name).cloned())
+                    })
+                    .collect();
+                events.push(ParsedEventWithErrors::new(
+                    SessionType::Native,
+                    event,
+                    None,
+                    values,
+                ));
+            }
+        }
+        Ok(events)
+    }
+}
+
+impl Parser for ArrowIpcParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match data {
+            RawBytes(event, raw_bytes) => self.parse_bytes(*event, raw_bytes),
+            KeyValue((_key, value)) | KeyValueWithHeaders((_key, value, _)) => match value {
+                Some(bytes) => self.parse_bytes(DataEventType::Insert, bytes),
+                None => Err(ParseError::EmptyKafkaPayload.into()),
+            },
+            Empty => Ok(vec![]),
+            TokenizedEntries(..) | Diff(_) => Err(ParseError::UnsupportedReaderContext.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+}
+
+/// Collapses the events of a batch into the net state of each key, as required
+/// by `SessionType::Snapshot`. `TransparentParser` passes it the events of one
+/// `parse` call at a time.
+///
+/// The events without a key are identified by their values. The rows emitted so
+/// far are remembered, so that a deletion of a key that has never been emitted
+/// is dropped and a deletion of an emitted key carries its last values.
+///
+/// The net events are emitted in the order in which their keys first appear in
+/// the batch, so that identical batches always produce identical output.
+#[derive(Debug, Default)]
+pub struct SnapshotCoalescer {
+    emitted: HashMap<Vec<Value>, Vec<Value>>,
+}
+
+struct PendingSnapshotEntry {
+    has_explicit_key: bool,
+    values: Option<ValueFieldsWithErrors>,
+}
+
+impl SnapshotCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.emitted.clear();
+    }
+
+    pub fn coalesce(&mut self, events: Vec<ParsedEventWithErrors>) -> Vec<ParsedEventWithErrors> {
+        let mut result = Vec::new();
+        let mut pending = IndexMap::new();
+        for event in events {
+            let (is_insert, key, values) = match event {
+                ParsedEventWithErrors::Insert((key, values))
+                | ParsedEventWithErrors::Upsert((key, values)) => (true, key, values),
+                ParsedEventWithErrors::Delete((key, values)) => (false, key, values),
+                ParsedEventWithErrors::AdvanceTime => {
+                    self.flush(&mut pending, &mut result);
+                    result.push(ParsedEventWithErrors::AdvanceTime);
+                    continue;
+                }
+            };
+            let (has_explicit_key, snapshot_key) = match &key {
+                Some(Ok(key)) => (true, Some(key.clone())),
+                None => (
+                    false,
+                    values
+                        .iter()
+                        .map(|value| value.as_ref().ok().cloned())
+                        .collect(),
+                ),
+                Some(Err(_)) => (true, None),
+            };
+            let Some(snapshot_key) = snapshot_key else {
+                // The event can't be matched with the others, hence it is left for the
+                // error handling of the connector
+                result.push(if is_insert {
+                    ParsedEventWithErrors::Insert((key, values))
+                } else {
+                    ParsedEventWithErrors::Delete((key, values))
+                });
+                continue;
+            };
+            pending.insert(
+                snapshot_key,
+                PendingSnapshotEntry {
+                    has_explicit_key,
+                    values: is_insert.then_some(values),
+                },
+            );
+        }
+        self.flush(&mut pending, &mut result);
+        result
+    }
+
+    fn flush(
+        &mut self,
+        pending: &mut IndexMap<Vec<Value>, PendingSnapshotEntry>,
+        result: &mut Vec<ParsedEventWithErrors>,
+    ) {
+        for (key, entry) in pending.drain(..) {
+            let has_explicit_key = entry.has_explicit_key;
+            let event_key = || has_explicit_key.then(|| Ok(key.clone()));
+            let previous = self.emitted.remove(&key);
+            let Some(values) = entry.values else {
+                if let Some(previous) = previous {
+                    result.push(ParsedEventWithErrors::Delete((
+                        event_key(),
+                        previous.into_iter().map(Ok).collect(),
+                    )));
+                }
+                continue;
+            };
+            let row: Vec<Value> = values
+                .iter()
+                .map(|value| value.as_ref().map_or(Value::Error, Clone::clone))
+                .collect();
+            if previous.as_ref() != Some(&row) {
+                if let Some(previous) = previous {
+                    result.push(ParsedEventWithErrors::Delete((
+                        event_key(),
+                        previous.into_iter().map(Ok).collect(),
+                    )));
+                }
+                result.push(ParsedEventWithErrors::Insert((event_key(), values)));
+            }
+            self.emitted.insert(key, row);
+        }
+    }
+}
+
+/// Defines how `TransparentParser` fills the key of an insertion, when
+/// neither the reader nor the key fields provide it.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum AutoKey {
+    #[default]
+    Disabled,
+    /// The rows inserted by the parser get the keys 0, 1, 2, ... in the order of arrival.
+    /// The counter restarts from 0 when the parser is reset.
+    Autoincrement,
+}
+
+/// What [`TransparentParser`] does with a record having fields that don't
+/// correspond to any field of the schema.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownFields {
+    /// Parse the known fields as usual
+    #[default]
+    Ignore,
+    /// Emit a row where every column is an error
+    Error,
+    /// Fail the record as a whole, so that it becomes a dead letter
+    DeadLetter,
+}
+
+/// How [`TransparentParser`] masks the values of a sensitive `String` field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Masking {
+    /// Replaces the value with the given constant
+    Redact(String),
+    /// Replaces the value with the hex-encoded SHA-256 hash of its UTF-8 bytes
+    HashSha256,
+    /// Keeps only the last four characters of the value
+    PartialLast4,
+}
+
+impl Masking {
+    fn mask(&self, value: &str) -> String {
+        match self {
+            Self::Redact(replacement) => replacement.clone(),
+            Self::HashSha256 => hex::encode(Sha256::digest(value.as_bytes())),
+            Self::PartialLast4 => {
+                let skipped = value.chars().count().saturating_sub(4);
+                value.chars().skip(skipped).collect()
+            }
+        }
+    }
+}
+
+/// The way [`TransparentParser`] reads a `List` field from a string of the
+/// elements joined with a delimiter, such as `a;b;c`.
+#[derive(Clone, Debug)]
+pub struct DelimitedList {
+    pub delimiter: String,
+    // Read the empty string as an empty list rather than a single empty element
+    pub empty_is_empty_list: bool,
+}
+
+impl DelimitedList {
+    pub fn new(delimiter: impl Into<String>) -> Self {
+        Self {
+            delimiter: delimiter.into(),
+            empty_is_empty_list: true,
+        }
+    }
+
+    // Elements that fail to parse become errors
+    fn split(&self, value: &str, element_type: &Type) -> Value {
+        if value.is_empty() && self.empty_is_empty_list {
+            return Value::from(Vec::new());
+        }
+        Value::from(
+            value
+                .split(self.delimiter.as_str())
+                .map(|element| parse_str_with_type(element, element_type).unwrap_or(Value::Error))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// What [`TransparentParser`] does with a record declaring a schema version
+/// known to be incompatible with the schema of the parser.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IncompatibleVersion {
+    /// Emit a row where every column is an error
+    #[default]
+    Error,
+    /// Fail the record as a whole, so that it becomes a dead letter
+    DeadLetter,
+}
+
+/// The versions of the schemas, with which the records are written. Each
+/// record declares its version in a field, a record without it is parsed as
+/// usual.
+#[derive(Clone, Debug)]
+pub struct SchemaVersioning {
+    // The version of the schema of the parser
+    pub version: String,
+    // The field holding the version of a record, as a string or an integer
+    pub version_field: String,
+    // The versions known to be incompatible with the schema of the parser
+    pub incompatible_versions: HashSet<String>,
+    pub policy: IncompatibleVersion,
+}
+
+/// The checks of a parser schema, which maps the names of the fields to their
+/// definitions.
+pub trait Schema {
+    /// Tells if the records written with the `previous` schema can be read with
+    /// this one. The fields added since then must be optional or have defaults,
+    /// the required fields of `previous` must not be removed, and the types of
+    /// the common fields must stay the same, although they may become optional.
+    fn is_backward_compatible_with(&self, previous: &Self) -> bool;
+
+    /// Lists the fields added to this schema since `previous`, the ones removed
+    /// from it, and the ones kept with a different type or default.
+    fn diff(&self, previous: &Self) -> SchemaDiff;
+}
+
+impl<S: BuildHasher> Schema for HashMap<String, InnerSchemaField, S> {
+    fn is_backward_compatible_with(&self, previous: &Self) -> bool {
+        let fields_compatible = self.iter().all(|(name, field)| match previous.get(name) {
+            None => !field.is_required(),
+            Some(previous_field) => {
+                field.type_.unoptionalize() == previous_field.type_.unoptionalize()
+                    && (field.type_.is_optional() || !previous_field.type_.is_optional())
+            }
+        });
+        let required_kept = previous
+            .iter()
+            .all(|(name, field)| !field.is_required() || self.contains_key(name));
+        fields_compatible && required_kept
+    }
+
+    fn diff(&self, previous: &Self) -> SchemaDiff {
+        let mut diff = SchemaDiff {
+            removed: previous
+                .keys()
+                .filter(|name| !self.contains_key(*name))
+                .cloned()
+                .sorted()
+                .collect(),
+            ..SchemaDiff::default()
+        };
+        for (name, field) in self.iter().sorted_by_key(|(name, _)| *name) {
+            let Some(previous_field) = previous.get(name) else {
+                diff.added.push(name.clone());
+                continue;
+            };
+            if field.type_ != previous_field.type_ {
+                diff.type_changes.push(TypeChange {
+                    field_name: name.clone(),
+                    previous: previous_field.type_.clone(),
+                    current: field.type_.clone(),
+                });
+            }
+            if field.default != previous_field.default {
+                diff.default_changes.push(DefaultChange {
+                    field_name: name.clone(),
+                    previous: previous_field.default.clone(),
+                    current: field.default.clone(),
+                });
+            }
+        }
+        diff
+    }
+}
+
+/// A field whose type differs between two schemas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeChange {
+    pub field_name: String,
+    pub previous: Type,
+    pub current: Type,
+}
+
+/// A field whose default differs between two schemas. Computed defaults are
+/// not compared, as their results may differ between calls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultChange {
+    pub field_name: String,
+    pub previous: Option<Value>,
+    pub current: Option<Value>,
+}
+
+/// The changes between two schemas, as found by [`Schema::diff`]. Each list is
+/// sorted by the names of the fields.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub type_changes: Vec<TypeChange>,
+    pub default_changes: Vec<DefaultChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.type_changes.is_empty()
+            && self.default_changes.is_empty()
+    }
+}
+
+const RAW_PAYLOAD_FIELD_NAME: &str = "__raw";
+
+// The values of a diff are serialized with the keys sorted, so that equal
+// payloads give equal strings
+fn raw_payload(data: &ReaderContext) -> Value {
+    match data {
+        KeyValue((_, Some(value))) | KeyValueWithHeaders((_, Some(value), _)) => {
+            Value::Bytes(value.as_slice().into())
+        }
+        Diff((_, _, values)) => {
+            let object: JsonMap<_, _> = values
+                .iter()
+                .sorted_by_key(|(name, _)| *name)
+                .map(|(name, value)| {
+                    let value = value
+                        .as_ref()
+                        .ok()
+                        .and_then(|value| serialize_value_to_json(value).ok())
+                        .unwrap_or(JsonValue::Null);
+                    (name.clone(), value)
+                })
+                .collect();
+            Value::from(JsonValue::Object(object).to_string().as_str())
+        }
+        _ => Value::None,
+    }
+}
+
+// The coercion to the type of the field is left to `InnerSchemaField::coerce`
+fn header_value(raw: &[u8], type_: &Type) -> Value {
+    match (type_.unoptionalize(), from_utf8(raw)) {
+        (Type::Bytes, _) | (_, Err(_)) => Value::Bytes(raw.into()),
+        (_, Ok(s)) => Value::from(s),
+    }
+}
+
+/// The parts of a `Diff` context decoded from a key-value pair, with the
+/// names of the fields in the order of the JSON object.
+struct DecodedKeyValue {
+    data_event: DataEventType,
+    key: Option<Vec<Value>>,
+    values: ValuesMap,
+    field_order: Vec<String>,
+}
+
+/// Receives values directly from a Reader and passes them
+/// further only making adjustments according to the schema.
+///
+/// It is useful when no raw values parsing is needed.
+///
+/// The values that need no adjustments are passed through without copying, so
+/// a string column shares its buffer with the value provided by the reader.
+///
+/// Key-value contexts are accepted as well, if the value is a JSON object and
+/// the key, when present, is the text of the only key field. A context
+/// without a key is a deletion.
+#[allow(clippy::struct_excessive_bools)]
+pub struct TransparentParser {
+    key_field_names: Option<Vec<String>>,
+    value_field_names: Vec<String>,
+    schema: HashMap<String, InnerSchemaField>,
+    session_type: SessionType,
+    snapshot_coalescer: SnapshotCoalescer,
+    upsert_events: bool,
+    auto_key: AutoKey,
+    next_auto_key: i64,
+    coercion_options: CoercionOptions,
+    source_names: HashMap<String, String>,
+    case_insensitive_fields: bool,
+    unknown_fields: UnknownFields,
+    schema_versioning: Option<SchemaVersioning>,
+    json_pointers: HashMap<String, JsonPointer>,
+    // The headers of key-value contexts that fields are taken from
+    header_fields: HashMap<String, String>,
+    coercion_hooks: HashMap<String, CoercionHook>,
+    // The last fields of `value_field_names`, in the same order
+    appended_fields: Vec<(String, AppendedField)>,
+    delimited_lists: HashMap<String, DelimitedList>,
+    maskings: HashMap<String, Masking>,
+    filter: Option<RowFilter>,
+    // A key of several fields is matched as a tuple
+    key_allowlist: Option<HashSet<Value>>,
+    key_denylist: HashSet<Value>,
+    // The names of the values of each emitted event, if they follow the source
+    field_orders: Option<Vec<Vec<String>>>,
+    // The last inserted values for each key, if deletions should carry them
+    cached_rows: Option<HashMap<Vec<Value>, Vec<Value>>>,
+    // The values of the source fields merged for each key, if partial records
+    // are accumulated
+    accumulated_rows: Option<HashMap<Vec<Value>, Vec<Value>>>,
+    // The contexts that failed as a whole, if they should not fail `parse`
+    dead_letters: Option<Vec<(ReaderContext, String)>>,
+    // The positions in `value_field_names` of the emitted values, if not all
+    selection: Option<Vec<usize>>,
+    debug_logging: bool,
+    fail_on_error: bool,
+    stats: ParserStats,
+    // Indexed like `value_field_names`
+    field_stats: Vec<FieldStats>,
+    // Indexed like `value_field_names`, None for the non-numeric fields
+    numeric_stats: Vec<Option<NumericStats>>,
+}
+
+/// A custom conversion of a field value, applied after it is brought to the
+/// type from the schema. An error message turns the value into an error.
+pub type CoercionHook = Box<dyn Fn(&Value) -> Result<Value, String> + Send>;
+
+/// Computes the value of a derived field from the values parsed before it.
+/// An error message turns the value into an error.
+pub type DerivedField = Box<dyn Fn(&[Value]) -> Result<Value, String> + Send>;
+
+/// The origin of a field that `TransparentParser` appends after the parsed ones.
+enum AppendedField {
+    Derived(DerivedField),
+    RawPayload,
+}
+
+/// A predicate deciding if a parsed row is kept. The values that failed to
+/// parse are passed as `Value::Error`.
+pub type RowFilter = Box<dyn Fn(&[Value]) -> bool + Send>;
+
+/// A JSON pointer split into the name of the source field and the pointer
+/// to be applied within the JSON value of this field.
+struct JsonPointer {
+    field: String,
+    rest: String,
+    // Whether only objects can be traversed, so that arrays stay whole
+    objects_only: bool,
+}
+
+impl JsonPointer {
+    fn parse(pointer: &str) -> Result<Self> {
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            return Err(Error::InvalidJsonPointer(pointer.to_string()));
+        };
+        let (field, rest) = match tokens.split_once('/') {
+            Some((field, rest)) => (field, format!("/{rest}")),
+            None => (tokens, String::new()),
+        };
+        Ok(Self {
+            field: field.replace("~1", "/").replace("~0", "~"),
+            rest,
+            objects_only: false,
+        })
+    }
+
+    /// Points at `address.city` as at the key `city` of the object stored
+    /// under `address`.
+    fn from_dotted_name(name: &str) -> Option<Self> {
+        let (field, tokens) = name.split_once('.')?;
+        let mut rest = String::new();
+        for token in tokens.split('.') {
+            rest.push('/');
+            rest.push_str(&token.replace('~', "~0").replace('/', "~1"));
+        }
+        Some(Self {
+            field: field.to_string(),
+            rest,
+            objects_only: true,
+        })
+    }
+
+    fn find<'a>(&self, json: &'a JsonValue) -> Option<&'a JsonValue> {
+        if !self.objects_only {
+            return json.pointer(&self.rest);
+        }
+        self.rest
+            .split('/')
+            .skip(1)
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .try_fold(json, |json, token| json.as_object()?.get(&token))
+    }
+
+    fn resolve(&self, values: &ValuesMap, type_: &Type) -> Option<DynResult<Value>> {
+        let value = match values.get(&self.field)? {
+            Ok(value) => value,
+            Err(error) => return Some(Err(error.clone().into())),
+        };
+        match value {
+            Value::Json(json) => {
+                let json = self.find(json)?;
+                let value =
+                    parse_value_from_json(json, type_).unwrap_or_else(|| Value::from(json.clone()));
+                Some(Ok(value))
+            }
+            value if self.rest.is_empty() => Some(Ok(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl TransparentParser {
+    #[allow(clippy::too_many_lines)]
+    pub fn new(
+        key_field_names: Option<Vec<String>>,
+        value_field_names: Vec<String>,
+        mut schema: HashMap<String, InnerSchemaField>,
+        session_type: SessionType,
+    ) -> Result<TransparentParser> {
+        ensure_all_fields_in_schema(&key_field_names, &value_field_names, &schema)?;
+        for (name, schema_item) in &mut schema {
+            match schema_item.on_error {
+                OnError::Null if !schema_item.type_.is_optional() => {
+                    return Err(Error::NullErrorPlaceholderNotOptional(name.clone()));
+                }
+                OnError::Default
+                    if schema_item.default.is_none() && schema_item.computed_default.is_none() =>
+                {
+                    return Err(Error::DefaultErrorPlaceholderMissing(name.clone()));
+                }
+                OnError::Error | OnError::Null | OnError::Default => {}
+            }
+            match &schema_item.missing {
+                Some(_) if !schema_item.type_.is_optional() => {
+                    return Err(Error::MissingBehaviorNotOptional(name.clone()));
+                }
+                Some(MissingBehavior::Default(default))
+                    if !value_fits_type(default, &schema_item.type_) =>
+                {
+                    return Err(Error::InvalidDefault {
+                        name: name.clone(),
+                        default: default.clone(),
+                        type_: schema_item.type_.clone(),
+                    });
+                }
+                _ => {}
+            }
+            if let Some(default @ Value::String(_)) = &schema_item.default {
+                if let Some(decoded) = schema_item.decode_bytes(default) {
+                    let Ok(decoded) = decoded else {
+                        return Err(Error::InvalidDefault {
+                            name: name.clone(),
+                            default: default.clone(),
+                            type_: schema_item.type_.clone(),
+                        });
+                    };
+                    schema_item.default = Some(decoded);
+                }
+            }
+            // The result of a single call stands for all the future ones
+            if let Some(computed_default) = &schema_item.computed_default {
+                let default = computed_default();
+                if !value_fits_type(&default, &schema_item.type_) {
+                    return Err(Error::InvalidDefault {
+                        name: name.clone(),
+                        default,
+                        type_: schema_item.type_.clone(),
+                    });
+                }
+            }
+            if let (
+                Type::Enum(_)
+                | Type::Int32
+                | Type::FixedString { .. }
+                | Type::FixedArray { .. }
+                | Type::Tuple(_),
+                Some(default),
+            ) = (schema_item.type_.unoptionalize(), &schema_item.default)
+            {
+                if !value_fits_type(default, &schema_item.type_) {
+                    return Err(Error::InvalidDefault {
+                        name: name.clone(),
+                        default: default.clone(),
+                        type_: schema_item.type_.clone(),
+                    });
+                }
+            }
+            if let (Some(constraints), Some(default)) =
+                (&schema_item.constraints, &schema_item.default)
+            {
+                if constraints.check(default).is_err() {
+                    return Err(Error::DefaultViolatesConstraints {
+                        name: name.clone(),
+                        default: default.clone(),
+                    });
+                }
+            }
+        }
+        let field_stats = vec![FieldStats::default(); value_field_names.len()];
+        let numeric_stats = value_field_names
+            .iter()
+            .map(|name| NumericStats::for_type(&schema[name].type_))
+            .collect();
+        Ok(TransparentParser {
+            key_field_names,
+            value_field_names,
+            schema,
+            session_type,
+            snapshot_coalescer: SnapshotCoalescer::new(),
+            upsert_events: false,
+            auto_key: AutoKey::Disabled,
+            next_auto_key: 0,
+            coercion_options: CoercionOptions::default(),
+            source_names: HashMap::new(),
+            case_insensitive_fields: false,
+            unknown_fields: UnknownFields::Ignore,
+            schema_versioning: None,
+            json_pointers: HashMap::new(),
+            header_fields: HashMap::new(),
+            coercion_hooks: HashMap::new(),
+            appended_fields: Vec::new(),
+            delimited_lists: HashMap::new(),
+            maskings: HashMap::new(),
+            filter: None,
+            key_allowlist: None,
+            key_denylist: HashSet::new(),
+            field_orders: None,
+            cached_rows: None,
+            accumulated_rows: None,
+            dead_letters: None,
+            selection: None,
+            debug_logging: false,
+            fail_on_error: false,
+            stats: ParserStats::default(),
+            field_stats,
+            numeric_stats,
+        })
+    }
+
+    /// Takes the values of the fields from the keys of the source named
+    /// differently. The map goes from the source keys to the field names,
+    /// the keys that aren't mentioned in it are matched with the fields as is.
+    pub fn with_renames(mut self, renames: HashMap<String, String>) -> Result<Self> {
+        for (source_name, name) in renames {
+            if !self.schema.contains_key(&name) {
+                return Err(Error::FieldNotInSchema {
+                    name,
+                    schema_keys: self.schema.keys().cloned().collect(),
+                });
+            }
+            self.source_names.insert(name, source_name);
+        }
+        Ok(self)
+    }
+
+    /// Matches the source keys with the fields ignoring ASCII case, while
+    /// still preferring an exact match. Fails if the names of two fields, as
+    /// they are looked up in the source, differ only in case.
+    pub fn with_case_insensitive_fields(mut self) -> Result<Self> {
+        let mut lowercase_names: HashMap<String, &str> = HashMap::new();
+        for name in self.schema.keys() {
+            let source_name = self.source_name(name);
+            if let Some(other) =
+                lowercase_names.insert(source_name.to_ascii_lowercase(), source_name)
+            {
+                let (first, second) = if other < source_name {
+                    (other, source_name)
+                } else {
+                    (source_name, other)
+                };
+                return Err(Error::FieldNamesCollideIgnoringCase {
+                    first: first.to_string(),
+                    second: second.to_string(),
+                });
+            }
+        }
+        self.case_insensitive_fields = true;
+        Ok(self)
+    }
+
+    fn source_value<'a>(
+        &self,
+        values: &'a ValuesMap,
+        name: &str,
+    ) -> Option<&'a Result<Value, Box<ConversionError>>> {
+        let source_name = self.source_name(name);
+        if self.case_insensitive_fields {
+            values.get_ignore_ascii_case(source_name)
+        } else {
+            values.get(source_name)
+        }
+    }
+
+    /// Sets what happens to the records with fields not matching any field of
+    /// the schema, after the renames, the JSON pointers and the flattening
+    /// are taken into account. With `UnknownFields::DeadLetter` such a record
+    /// fails to parse, unless the dead letters are collected.
+    #[must_use]
+    pub fn with_unknown_fields(mut self, unknown_fields: UnknownFields) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
+
+    /// Checks the version declared by each record against the versions
+    /// known to be incompatible with the schema of the parser. The version
+    /// field doesn't count as an unknown field.
+    #[must_use]
+    pub fn with_schema_versioning(mut self, schema_versioning: SchemaVersioning) -> Self {
+        self.schema_versioning = Some(schema_versioning);
+        self
+    }
+
+    pub fn schema_version(&self) -> Option<&str> {
+        self.schema_versioning
+            .as_ref()
+            .map(|versioning| versioning.version.as_str())
+    }
+
+    fn find_incompatible_version(&self, values: &ValuesMap) -> Option<String> {
+        let versioning = self.schema_versioning.as_ref()?;
+        let version = match values.get(&versioning.version_field)? {
+            Ok(Value::String(version)) => version.to_string(),
+            Ok(Value::Int(version)) => version.to_string(),
+            _ => return None,
+        };
+        versioning
+            .incompatible_versions
+            .contains(&version)
+            .then_some(version)
+    }
+
+    fn is_known_source_key(&self, source_key: &str) -> bool {
+        if self
+            .json_pointers
+            .values()
+            .any(|pointer| pointer.field == source_key)
+            || self
+                .schema_versioning
+                .as_ref()
+                .is_some_and(|versioning| versioning.version_field == source_key)
+        {
+            return true;
+        }
+        chain!(
+            self.key_field_names.iter().flatten(),
+            &self.value_field_names
+        )
+        .filter(|name| {
+            !self.json_pointers.contains_key(*name) && !self.header_fields.contains_key(*name)
+        })
+        .map(|name| self.source_name(name))
+        .any(|name| {
+            if self.case_insensitive_fields {
+                name.eq_ignore_ascii_case(source_key)
+            } else {
+                name == source_key
+            }
+        })
+    }
+
+    fn find_unknown_fields(&self, values: &ValuesMap) -> Vec<String> {
+        values
+            .iter()
+            .map(|(source_key, _)| source_key)
+            .filter(|source_key| !self.is_known_source_key(source_key))
+            .sorted()
+            .cloned()
+            .collect()
+    }
+
+    /// Takes the values of the fields from nested JSON values of the source,
+    /// as pointed to by RFC 6901 JSON pointers. The first token of a pointer
+    /// names the source key, for instance `/data/items/0/sku` looks for the
+    /// value at `/items/0/sku` in the JSON stored under `data`.
+    pub fn with_json_pointers(mut self, pointers: HashMap<String, String>) -> Result<Self> {
+        for (name, pointer) in pointers {
+            if !self.schema.contains_key(&name) {
+                return Err(Error::FieldNotInSchema {
+                    name,
+                    schema_keys: self.schema.keys().cloned().collect(),
+                });
+            }
+            self.json_pointers
+                .insert(name, JsonPointer::parse(&pointer)?);
+        }
+        Ok(self)
+    }
+
+    /// Takes the values of the fields with dotted names, such as
+    /// `address.city`, from the nested JSON objects of the source, unless a
+    /// JSON pointer is given for the field. Only objects are flattened, an
+    /// array is taken as a whole. Fails if a flattened field is nested in
+    /// another field of the schema, as the latter would hold an object.
+    pub fn with_flatten(mut self) -> Result<Self> {
+        for name in self.schema.keys() {
+            let Some(pointer) = JsonPointer::from_dotted_name(name) else {
+                continue;
+            };
+            let mut prefix = pointer.field.clone();
+            for token in name[prefix.len() + 1..].split('.') {
+                if self.schema.contains_key(&prefix) {
+                    return Err(Error::FlattenedFieldCollides {
+                        name: name.clone(),
+                        other: prefix,
+                    });
+                }
+                prefix = format!("{prefix}.{token}");
+            }
+            self.json_pointers.entry(name.clone()).or_insert(pointer);
+        }
+        Ok(self)
+    }
+
+    /// Makes deletions in `SessionType::Native` carry the values of the row
+    /// last inserted with the same key, instead of the values provided by the
+    /// reader, which may be incomplete. The deletions of never inserted keys
+    /// keep the provided values.
+    ///
+    /// A row is kept in memory from its insertion until its deletion, so the
+    /// memory grows with the number of keys present at the same time.
+    #[must_use]
+    pub fn with_cached_delete_values(mut self) -> Self {
+        self.cached_rows = Some(HashMap::new());
+        self
+    }
+
+    /// Makes the records be taken as partial updates of the rows with their
+    /// keys. The fields absent from a record keep the values last emitted for
+    /// its key, and the ones never seen for the key take their defaults. A
+    /// deletion forgets the values of its key. Fails unless the parser has key
+    /// fields and `SessionType::Upsert`, in which each emitted row replaces the
+    /// previous one with the same key.
+    ///
+    /// The values are kept in memory from the first record of a key until its
+    /// deletion.
+    pub fn with_accumulation(mut self) -> Result<Self> {
+        if self.key_field_names.is_none() || !matches!(self.session_type, SessionType::Upsert) {
+            return Err(Error::AccumulationUnsupported);
+        }
+        self.accumulated_rows = Some(HashMap::new());
+        Ok(self)
+    }
+
+    fn accumulate(
+        &mut self,
+        data_event: DataEventType,
+        key: &KeyFieldsWithErrors,
+        absent: &[bool],
+        values: &mut ValueFieldsWithErrors,
+    ) {
+        let (Some(accumulated_rows), Some(Ok(key))) = (&mut self.accumulated_rows, key) else {
+            return;
+        };
+        match data_event {
+            DataEventType::Insert => {
+                if let Some(row) = accumulated_rows.get(key) {
+                    for ((value, absent), accumulated) in zip(zip(values.iter_mut(), absent), row) {
+                        if *absent {
+                            *value = Ok(accumulated.clone());
+                        }
+                    }
+                }
+                let row = values
+                    .iter()
+                    .map(|value| value.as_ref().map_or(Value::Error, Clone::clone))
+                    .collect();
+                accumulated_rows.insert(key.clone(), row);
+            }
+            DataEventType::Delete => {
+                accumulated_rows.remove(key);
+            }
+        }
+    }
+
+    /// Makes `parse` succeed when a context can't be parsed at all. Such a
+    /// context produces no events and is kept, together with the error
+    /// message, until it is collected with `take_dead_letters`.
+    #[must_use]
+    pub fn with_dead_letters(mut self) -> Self {
+        self.dead_letters = Some(Vec::new());
+        self
+    }
+
+    /// Removes and returns the contexts which failed since the last call.
+    pub fn take_dead_letters(&mut self) -> Vec<(ReaderContext, String)> {
+        self.dead_letters.as_mut().map(take).unwrap_or_default()
+    }
+
+    /// Restricts the emitted values to the given fields, in the given order.
+    /// The other fields are still parsed, so they still count in the stats
+    /// and are seen by the filter, and the key fields are extracted whether
+    /// they are selected or not.
+    pub fn with_select(mut self, names: Vec<String>) -> Result<Self> {
+        let mut selection = Vec::with_capacity(names.len());
+        for name in names {
+            let Some(index) = self
+                .value_field_names
+                .iter()
+                .position(|field| *field == name)
+            else {
+                return Err(Error::FieldNotInSchema {
+                    name,
+                    schema_keys: self.value_field_names.clone(),
+                });
+            };
+            if selection.contains(&index) {
+                return Err(Error::FieldSelectedTwice(name));
+            }
+            selection.push(index);
+        }
+        self.selection = Some(selection);
+        Ok(self)
+    }
+
+    fn select_values(&self, event: &mut ParsedEventWithErrors) {
+        let Some(selection) = &self.selection else {
+            return;
+        };
+        match event {
+            ParsedEventWithErrors::Insert((_, values))
+            | ParsedEventWithErrors::Delete((_, values))
+            | ParsedEventWithErrors::Upsert((_, values))
+                if !values.is_empty() =>
+            {
+                let mut all_values: Vec<_> = take(values).into_iter().map(Some).collect();
+                *values = selection
+                    .iter()
+                    .map(|index| {
+                        all_values[*index]
+                            .take()
+                            .expect("the selected fields are distinct")
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// Makes the values of each row be emitted in the order in which their fields
+    /// appear in the JSON object of a key-value context, followed by the ones
+    /// missing from it in the order of the schema. Diff contexts carry no order,
+    /// so their rows keep the order of the schema. The names of the emitted
+    /// values are returned by `take_field_orders`.
+    ///
+    /// It is not available for `SessionType::Snapshot`, which may retract a row
+    /// emitted in a different order.
+    pub fn with_source_field_order(mut self) -> Result<Self> {
+        if matches!(self.session_type, SessionType::Snapshot) {
+            return Err(Error::SourceFieldOrderInSnapshot);
+        }
+        self.field_orders = Some(Vec::new());
+        Ok(self)
+    }
+
+    /// Returns the names of the values of each event emitted since the last
+    /// call, if the order of the source fields is kept.
+    pub fn take_field_orders(&mut self) -> Vec<Vec<String>> {
+        self.field_orders.as_mut().map(take).unwrap_or_default()
+    }
+
+    fn order_like_source(
+        &self,
+        event: &mut ParsedEventWithErrors,
+        source_field_order: &[String],
+    ) -> Vec<String> {
+        let emitted: Vec<&String> = match &self.selection {
+            Some(selection) => selection
+                .iter()
+                .map(|index| &self.value_field_names[*index])
+                .collect(),
+            None => self.value_field_names.iter().collect(),
+        };
+        let source_position = |name: &str| {
+            let name = self.source_name(name);
+            source_field_order
+                .iter()
+                .position(|source_name| {
+                    if self.case_insensitive_fields {
+                        source_name.eq_ignore_ascii_case(name)
+                    } else {
+                        source_name == name
+                    }
+                })
+                .unwrap_or(source_field_order.len())
+        };
+        let order: Vec<usize> = (0..emitted.len())
+            .sorted_by_key(|index| source_position(emitted[*index]))
+            .collect();
+        if let ParsedEventWithErrors::Insert((_, values))
+        | ParsedEventWithErrors::Delete((_, values))
+        | ParsedEventWithErrors::Upsert((_, values)) = event
+        {
+            if !values.is_empty() {
+                let mut all_values: Vec<_> = take(values).into_iter().map(Some).collect();
+                *values = order
+                    .iter()
+                    .map(|index| {
+                        all_values[*index]
+                            .take()
+                            .expect("the order is a permutation")
+                    })
+                    .collect();
+            }
+        }
+        order.iter().map(|index| emitted[*index].clone()).collect()
+    }
+
+    fn use_cached_row(
+        &mut self,
+        data_event: DataEventType,
+        key: &KeyFieldsWithErrors,
+        values: &mut ValueFieldsWithErrors,
+    ) {
+        let (Some(cached_rows), Some(Ok(key)), SessionType::Native) =
+            (&mut self.cached_rows, key, self.session_type)
+        else {
+            return;
+        };
+        match data_event {
+            DataEventType::Insert => {
+                let row = values
+                    .iter()
+                    .map(|value| value.as_ref().map_or(Value::Error, Clone::clone))
+                    .collect();
+                cached_rows.insert(key.clone(), row);
+            }
+            DataEventType::Delete => {
+                if let Some(row) = cached_rows.remove(key) {
+                    *values = row.into_iter().map(Ok).collect();
+                }
+            }
+        }
+    }
+
+    /// Makes the parser log the rows with errors at the debug level, naming
+    /// the failed fields together with their raw values, types and the
+    /// reasons of the failures.
+    #[must_use]
+    pub fn with_debug_logging(mut self) -> Self {
+        self.debug_logging = true;
+        self
+    }
+
+    fn log_rejected_fields(&self, raw_values: &ValuesMap, values: &ValueFieldsWithErrors) {
+        let rejected = self
+            .value_field_names
+            .iter()
+            .zip(values)
+            .filter_map(|(name, value)| {
+                let error = value.as_ref().err()?;
+                let raw_value = self.describe_raw_value(raw_values, name);
+                let type_ = &self.schema[name].type_;
+                Some(format!(
+                    "field {name:?} of type {type_} with raw value {raw_value}: {error}"
+                ))
+            })
+            .join("; ");
+        if !rejected.is_empty() {
+            debug!("rejected row fields: {rejected}");
+        }
+    }
+
+    fn describe_raw_value(&self, raw_values: &ValuesMap, name: &str) -> String {
+        let raw_value = match self.json_pointers.get(name) {
+            Some(pointer) => raw_values.get(&pointer.field),
+            None => self.source_value(raw_values, name),
+        };
+        match raw_value {
+            Some(Ok(value)) => format!("{value:?}"),
+            Some(Err(error)) => format!("error ({error})"),
+            None => "missing".to_string(),
+        }
+    }
+
+    /// Makes the parsing of a record fail, instead of emitting a row with an
+    /// error in some of its fields. With dead letters enabled, such a record
+    /// becomes a dead letter.
+    #[must_use]
+    pub fn with_fail_on_error(mut self) -> Self {
+        self.fail_on_error = true;
+        self
+    }
+
+    fn ensure_no_errors(
+        &self,
+        raw_values: &ValuesMap,
+        values: &ValueFieldsWithErrors,
+    ) -> Result<(), ParseError> {
+        let failed = zip(&self.value_field_names, values)
+            .find_map(|(name, value)| Some((name, value.as_ref().err()?)));
+        match failed {
+            Some((name, error)) => Err(ParseError::FieldFailedInStrictMode {
+                field_name: name.clone(),
+                raw_value: self.describe_raw_value(raw_values, name),
+                error: error.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Drops the insertions and the upserts of the rows, for which the
+    /// predicate returns false. The deletions are always passed further.
+    #[must_use]
+    pub fn with_filter(mut self, predicate: impl Fn(&[Value]) -> bool + Send + 'static) -> Self {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+
+    fn passes_filter(&self, event: &ParsedEventWithErrors) -> bool {
+        let Some(filter) = &self.filter else {
+            return true;
+        };
+        match event {
+            ParsedEventWithErrors::Insert((_, values))
+            | ParsedEventWithErrors::Upsert((_, values)) => {
+                let row: Vec<_> = values
+                    .iter()
+                    .map(|value| value.as_ref().map_or(Value::Error, Clone::clone))
+                    .collect();
+                filter(&row)
+            }
+            ParsedEventWithErrors::Delete(_) | ParsedEventWithErrors::AdvanceTime => true,
+        }
+    }
+
+    /// Makes only the rows with the given keys be emitted, or all of them for
+    /// `None`. The rows without a valid key are dropped while the list is set.
+    /// The key of several fields is given as a tuple of their values.
+    pub fn set_key_allowlist(&mut self, keys: Option<HashSet<Value>>) {
+        self.key_allowlist = keys;
+    }
+
+    /// Makes the rows with the given keys be dropped, deletions included. The
+    /// key of several fields is given as a tuple of their values.
+    pub fn set_key_denylist(&mut self, keys: HashSet<Value>) {
+        self.key_denylist = keys;
+    }
+
+    fn passes_key_lists(&self, key: Option<&DynResult<Vec<Value>>>) -> bool {
+        let key = match key {
+            Some(Ok(key)) if key.len() == 1 => Cow::Borrowed(&key[0]),
+            Some(Ok(key)) => Cow::Owned(Value::from(key.as_slice())),
+            _ => return self.key_allowlist.is_none(),
+        };
+        self.key_allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.contains(key.as_ref()))
+            && !self.key_denylist.contains(key.as_ref())
+    }
+
+    /// Registers a hook run on the successfully coerced values of the field.
+    /// A field can have only one hook, a later one replaces the earlier.
+    pub fn with_coercion_hook(
+        mut self,
+        name: &str,
+        hook: impl Fn(&Value) -> Result<Value, String> + Send + 'static,
+    ) -> Result<Self> {
+        if !self.schema.contains_key(name) {
+            return Err(Error::FieldNotInSchema {
+                name: name.to_string(),
+                schema_keys: self.schema.keys().cloned().collect(),
+            });
+        }
+        self.coercion_hooks.insert(name.to_string(), Box::new(hook));
+        Ok(self)
+    }
+
+    /// Appends a field computed from the values parsed in the row: the ones of
+    /// `value_field_names` and of the derived fields registered earlier.
+    /// A selection made before doesn't include the field.
+    pub fn with_derived_field(
+        self,
+        name: &str,
+        type_: Type,
+        derive: impl Fn(&[Value]) -> Result<Value, String> + Send + 'static,
+    ) -> Result<Self> {
+        self.with_appended_field(name, type_, AppendedField::Derived(Box::new(derive)))
+    }
+
+    /// Appends the `__raw` field holding the payload of the parsed context: the
+    /// bytes of a key-value context or the JSON text of the values of a diff.
+    /// It stays intact when the other fields fail. A selection made before
+    /// doesn't include the field.
+    pub fn with_raw_payload(self) -> Result<Self> {
+        self.with_appended_field(RAW_PAYLOAD_FIELD_NAME, Type::Any, AppendedField::RawPayload)
+    }
+
+    fn with_appended_field(
+        mut self,
+        name: &str,
+        type_: Type,
+        appended: AppendedField,
+    ) -> Result<Self> {
+        if self.schema.contains_key(name) {
+            return Err(Error::DerivedFieldCollides(name.to_string()));
+        }
+        self.numeric_stats.push(NumericStats::for_type(&type_));
+        self.field_stats.push(FieldStats::default());
+        self.schema
+            .insert(name.to_string(), InnerSchemaField::new(type_, None));
+        self.value_field_names.push(name.to_string());
+        self.appended_fields.push((name.to_string(), appended));
+        Ok(self)
+    }
+
+    fn appended_value(
+        &self,
+        name: &str,
+        appended: &AppendedField,
+        data: &ReaderContext,
+        values: &ValueFieldsWithErrors,
+    ) -> DynResult<Value> {
+        match appended {
+            AppendedField::Derived(derive) => self.derive_value(name, derive, values),
+            AppendedField::RawPayload => Ok(raw_payload(data)),
+        }
+    }
+
+    fn derive_value(
+        &self,
+        name: &str,
+        derive: &DerivedField,
+        values: &ValueFieldsWithErrors,
+    ) -> DynResult<Value> {
+        let row: Vec<_> = values
+            .iter()
+            .map(|value| value.as_ref().map_or(Value::Error, Clone::clone))
+            .collect();
+        let value = derive(&row).map_err(|message| ParseError::DerivedFieldFailed {
+            field_name: name.to_string(),
+            message,
+        })?;
+        let type_ = &self.schema[name].type_;
+        if !value_fits_type(&value, type_) {
+            return Err(CoerceError::UnexpectedValueType {
+                expected: type_.clone(),
+                got: format!("{:?}", value.kind()),
+            }
+            .into());
+        }
+        Ok(value)
+    }
+
+    // The raw payload is kept even if the whole row is replaced with errors
+    fn keep_raw_payload(&self, data: &ReaderContext, values: &mut ValueFieldsWithErrors) {
+        let source_field_count = self.value_field_names.len() - self.appended_fields.len();
+        for ((_, appended), value) in zip(&self.appended_fields, &mut values[source_field_count..])
+        {
+            if matches!(appended, AppendedField::RawPayload) {
+                *value = Ok(raw_payload(data));
+            }
+        }
+    }
+
+    fn apply_coercion_hook(&self, name: &str, value: Value) -> DynResult<Value> {
+        match self.coercion_hooks.get(name) {
+            Some(hook) => hook(&value).map_err(|message| {
+                ParseError::CoercionHookFailed {
+                    field_name: name.to_string(),
+                    message,
+                }
+                .into()
+            }),
+            None => Ok(value),
+        }
+    }
+
+    /// Makes the values of the `String` field be masked once they are parsed.
+    /// The errors and the `None` values are kept as they are, and so are the
+    /// values of the field in the keys.
+    pub fn with_masking(mut self, name: &str, masking: Masking) -> Result<Self> {
+        let Some(schema_item) = self.schema.get(name) else {
+            return Err(Error::FieldNotInSchema {
+                name: name.to_string(),
+                schema_keys: self.schema.keys().cloned().collect(),
+            });
+        };
+        if !matches!(schema_item.type_.unoptionalize(), Type::String) {
+            return Err(Error::MaskedFieldNotAString(name.to_string()));
+        }
+        self.maskings.insert(name.to_string(), masking);
+        Ok(self)
+    }
+
+    fn mask(&self, name: &str, value: Value) -> Value {
+        match (self.maskings.get(name), &value) {
+            (Some(masking), Value::String(s)) => Value::from(masking.mask(s).as_str()),
+            _ => value,
+        }
+    }
+
+    /// Makes the string values of the `List` field be split into elements,
+    /// each of them parsed as the type of the elements.
+    pub fn with_delimited_list(mut self, name: &str, delimited: DelimitedList) -> Result<Self> {
+        let Some(schema_item) = self.schema.get(name) else {
+            return Err(Error::FieldNotInSchema {
+                name: name.to_string(),
+                schema_keys: self.schema.keys().cloned().collect(),
+            });
+        };
+        if !matches!(schema_item.type_.unoptionalize(), Type::List(_)) {
+            return Err(Error::DelimitedFieldNotAList(name.to_string()));
+        }
+        self.delimited_lists.insert(name.to_string(), delimited);
+        Ok(self)
+    }
+
+    /// Takes the values of the field from the header with the given name,
+    /// instead of the payload. Only `KeyValueWithHeaders` contexts carry
+    /// headers, and the field is taken as missing from the others. A header
+    /// that is not valid UTF-8 is kept as bytes, so it only fits `Bytes` fields.
+    pub fn with_header_field(mut self, name: &str, header: &str) -> Result<Self> {
+        if !self.schema.contains_key(name) {
+            return Err(Error::FieldNotInSchema {
+                name: name.to_string(),
+                schema_keys: self.schema.keys().cloned().collect(),
+            });
+        }
+        self.header_fields
+            .insert(name.to_string(), header.to_string());
+        Ok(self)
+    }
+
+    fn split_delimited_list(&self, name: &str, value: Value) -> Value {
+        match (self.delimited_lists.get(name), &value) {
+            (Some(delimited), Value::String(s)) => {
+                let Type::List(element_type) = self.schema[name].type_.unoptionalize() else {
+                    unreachable!("with_delimited_list only accepts List fields");
+                };
+                delimited.split(s, element_type)
+            }
+            _ => value,
+        }
+    }
+
+    /// The outcomes of the values parsed for each field since the creation or
+    /// the last reset of the parser. The fields that weren't parsed yet are
+    /// omitted.
+    pub fn coercion_report(&self) -> HashMap<String, FieldStats> {
+        zip(&self.value_field_names, &self.field_stats)
+            .filter(|(_, stats)| !stats.is_empty())
+            .map(|(name, stats)| (name.clone(), *stats))
+            .collect()
+    }
+
+    /// Fails on the first `String` field holding invalid UTF-8, if such
+    /// records are to be rejected as a whole.
+    fn reject_invalid_utf8(&self, values: &ValuesMap) -> DynResult<()> {
+        if self.coercion_options.utf8_mode != Utf8Mode::Reject {
+            return Ok(());
+        }
+        for name in &self.value_field_names {
+            let schema_item = &self.schema[name];
+            let (Type::String, Some(Ok(value @ Value::Bytes(bytes)))) = (
+                schema_item.type_.unoptionalize(),
+                self.source_value(values, name),
+            ) else {
+                continue;
+            };
+            if let Err(error) = from_utf8(bytes) {
+                return Err(ParseError::SchemaNotSatisfied {
+                    value: value.to_string(),
+                    field_name: name.clone(),
+                    type_: schema_item.type_.clone(),
+                    error: ParseError::Utf8DecodeFailed(error).into(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// The summaries of the values parsed for each numeric field since the
+    /// creation or the last reset of the parser. The fields without any
+    /// numeric value parsed yet are omitted.
+    pub fn numeric_profile(&self) -> HashMap<String, NumericStats> {
+        zip(&self.value_field_names, &self.numeric_stats)
+            .filter_map(|(name, stats)| {
+                let stats = stats.filter(|stats| stats.count > 0)?;
+                Some((name.clone(), stats))
+            })
+            .collect()
+    }
+
+    fn source_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.source_names.get(name).map_or(name, String::as_str)
+    }
+
+    #[must_use]
+    pub fn with_coercion_options(mut self, coercion_options: CoercionOptions) -> Self {
+        self.coercion_options = coercion_options;
+        self
+    }
+
+    #[must_use]
+    pub fn with_auto_key(mut self, auto_key: AutoKey) -> Self {
+        self.auto_key = auto_key;
+        self
+    }
+
+    /// Makes the parser emit `ParsedEventWithErrors::Upsert` instead of
+    /// insertions when `SessionType::Upsert` is used, so that the new row is
+    /// delivered together with its key.
+    #[must_use]
+    pub fn with_upsert_events(mut self) -> Self {
+        self.upsert_events = true;
+        self
+    }
+
+    /// Turns a key-value pair, as delivered by message queues, into the parts
+    /// of a `Diff` context. The key bytes are the text of the only key field,
+    /// parsed according to its type, and the value bytes are a JSON object
+    /// with the fields of the row. A message without a key is a deletion.
+    fn decode_key_value(
+        &self,
+        raw_key: Option<&[u8]>,
+        raw_value: Option<&[u8]>,
+    ) -> DynResult<DecodedKeyValue> {
+        let Some(raw_value) = raw_value else {
+            return Err(ParseError::EmptyKafkaPayload.into());
+        };
+        let payload = prepare_plaintext_string(raw_value)?;
+        // Read into an `IndexMap`, as a JSON object doesn't keep the order of its keys
+        let Ok(fields) = serde_json::from_str::<IndexMap<String, JsonValue>>(&payload) else {
+            return Err(ParseError::FailedToParseJson(payload).into());
+        };
+        let field_order = fields.keys().cloned().collect();
+        let values: HashMap<String, Result<Value, Box<ConversionError>>> = fields
+            .into_iter()
+            .map(|(name, json)| {
+                let value = parse_value_from_json(&json, &Type::Optional(Type::Any.into()))
+                    .unwrap_or_else(|| Value::from(json));
+                (name, Ok(value))
+            })
+            .collect();
+        let Some(raw_key) = raw_key else {
+            return Ok(DecodedKeyValue {
+                data_event: DataEventType::Delete,
+                key: None,
+                values: values.into(),
+                field_order,
+            });
+        };
+        let key_field_name = match self.key_field_names.as_deref() {
+            Some([key_field_name]) => key_field_name,
+            key_field_names => {
+                return Err(ParseError::KeyBytesWithoutSingleKeyField(
+                    key_field_names.map_or(0, <[String]>::len),
+                )
+                .into())
+            }
+        };
+        let raw_key = prepare_plaintext_string(raw_key)?;
+        let key = parse_str_with_type(&raw_key, &self.schema[key_field_name].type_)?;
+        Ok(DecodedKeyValue {
+            data_event: DataEventType::Insert,
+            key: Some(vec![key]),
+            values: values.into(),
+            field_order,
+        })
+    }
+
+    /// Appends the events parsed from the context to `output`, so that a batch
+    /// of contexts can share a single buffer.
+    fn parse_into(
+        &mut self,
+        data: &ReaderContext,
+        output: &mut Vec<ParsedEventWithErrors>,
+    ) -> DynResult<()> {
+        if let Some(len) = data.payload_len() {
+            self.stats.bytes_consumed += len as u64;
+        }
+        match (
+            self.parse_context_into(data, output),
+            &mut self.dead_letters,
+        ) {
+            (Err(error), Some(dead_letters)) => {
+                dead_letters.push((data.clone(), error.to_string()));
+                Ok(())
+            }
+            (result, _) => result,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn parse_context_into(
+        &mut self,
+        data: &ReaderContext,
+        output: &mut Vec<ParsedEventWithErrors>,
+    ) -> DynResult<()> {
+        let decoded;
+        let (data_event, key, values, source_field_order, headers) = match data {
+            Empty => return Ok(()),
+            Diff((data_event, key, values)) => (data_event, key, values, &[][..], None),
+            KeyValue((raw_key, raw_value)) | KeyValueWithHeaders((raw_key, raw_value, _)) => {
+                decoded = self.decode_key_value(raw_key.as_deref(), raw_value.as_deref())?;
+                (
+                    &decoded.data_event,
+                    &decoded.key,
+                    &decoded.values,
+                    decoded.field_order.as_slice(),
+                    data.headers(),
+                )
+            }
+            _ => return Err(ParseError::UnsupportedReaderContext.into()),
+        };
+        if values.get_special() == Some(SpecialEvent::Commit) {
+            output.push(ParsedEventWithErrors::AdvanceTime);
+            return Ok(());
+        }
+        let unknown_fields = match self.unknown_fields {
+            UnknownFields::Ignore => Vec::new(),
+            UnknownFields::Error | UnknownFields::DeadLetter => self.find_unknown_fields(values),
+        };
+        if !unknown_fields.is_empty() && self.unknown_fields == UnknownFields::DeadLetter {
+            return Err(ParseError::UnknownFields(unknown_fields).into());
+        }
+        let incompatible_version = self.find_incompatible_version(values);
+        if let (Some(version), Some(versioning)) = (&incompatible_version, &self.schema_versioning)
+        {
+            if versioning.policy == IncompatibleVersion::DeadLetter {
+                return Err(ParseError::IncompatibleSchemaVersion(version.clone()).into());
+            }
+        }
+        // A default can't stand in for an absent key field, since the row
+        // would then be matched with a wrong key
+        let mut key = key.clone().map(Ok).or_else(|| {
+            self.key_field_names.as_ref().map(|key_field_names| {
+                key_field_names
+                    .iter()
+                    .map(|name| match self.source_value(values, name) {
+                        // ensure_all_fields_in_schema in new() makes sure that all keys are in the schema
+                        Some(value) => {
+                            self.schema[name].maybe_use_default(name, Some(value.clone()))
+                        }
+                        None => Err(ParseError::KeyFieldMissing {
+                            field_name: name.clone(),
+                        }
+                        .into()),
+                    })
+                    .collect()
+            })
+        });
+        if key.is_none()
+            && matches!(self.auto_key, AutoKey::Autoincrement)
+            && matches!(data_event, DataEventType::Insert)
+        {
+            key = Some(Ok(vec![Value::Int(self.next_auto_key)]));
+            self.next_auto_key += 1;
+        }
+        if !self.passes_key_lists(key.as_ref()) {
+            return Ok(());
+        }
+
+        self.reject_invalid_utf8(values)?;
+
+        let mut field_stats = take(&mut self.field_stats);
+        let source_field_count = self.value_field_names.len() - self.appended_fields.len();
+        let mut absent = Vec::with_capacity(source_field_count);
+        let mut parsed_values: Vec<_> = zip(&self.value_field_names, &mut field_stats)
+            .take(source_field_count)
+            .map(|(name, field_stats)| {
+                let schema_item = &self.schema[name]; // ensure_all_fields_in_schema in new() makes sure that all keys are in the schema
+                let (value, defaulted) = if let Some(header) = self.header_fields.get(name) {
+                    match headers.and_then(|headers| headers.get(header)) {
+                        Some(raw) => (Ok(header_value(raw, &schema_item.type_)), false),
+                        None => (schema_item.value_if_missing(name), true),
+                    }
+                } else if let Some(pointer) = self.json_pointers.get(name) {
+                    match pointer.resolve(values, &schema_item.type_) {
+                        Some(value) => (value, false),
+                        None => (schema_item.value_if_missing(name), true),
+                    }
+                } else {
+                    match self.source_value(values, name) {
+                        Some(raw) => (
+                            schema_item.maybe_use_default(name, Some(raw.clone())),
+                            false,
+                        ),
+                        None => (schema_item.value_if_missing(name), true),
+                    }
+                };
+                let value = value
+                    .map(|value| self.split_delimited_list(name, value))
+                    .and_then(|value| schema_item.coerce(name, &value, &self.coercion_options))
+                    .and_then(|value| self.apply_coercion_hook(name, value))
+                    .map(|value| self.mask(name, value));
+                field_stats.record(&value, defaulted);
+                absent.push(defaulted);
+                schema_item.replace_error(name, value)
+            })
+            .collect();
+        self.accumulate(*data_event, &key, &absent, &mut parsed_values);
+        for ((name, appended), field_stats) in zip(
+            &self.appended_fields,
+            &mut field_stats[source_field_count..],
+        ) {
+            let value = self.appended_value(name, appended, data, &parsed_values);
+            field_stats.record(&value, false);
+            parsed_values.push(value);
+        }
+        self.field_stats = field_stats;
+        for (stats, value) in zip(&mut self.numeric_stats, &parsed_values) {
+            if let (Some(stats), Ok(value)) = (stats, value) {
+                stats.record(value);
+            }
+        }
+        let mut parsed_values = if let Some(version) = incompatible_version {
+            self.value_field_names
+                .iter()
+                .map(|_| Err(ParseError::IncompatibleSchemaVersion(version.clone()).into()))
+                .collect()
+        } else if unknown_fields.is_empty() {
+            parsed_values
+        } else {
+            self.value_field_names
+                .iter()
+                .map(|_| Err(ParseError::UnknownFields(unknown_fields.clone()).into()))
+                .collect()
+        };
+        self.keep_raw_payload(data, &mut parsed_values);
+
+        if self.debug_logging && log_enabled!(LogLevel::Debug) {
+            self.log_rejected_fields(values, &parsed_values);
+        }
+        self.use_cached_row(*data_event, &key, &mut parsed_values);
+        if self.fail_on_error {
+            self.ensure_no_errors(values, &parsed_values)?;
+        }
+        let values = parsed_values;
+
+        let mut event = match (self.session_type, data_event) {
+            (SessionType::Upsert, DataEventType::Insert) if self.upsert_events => {
+                ParsedEventWithErrors::Upsert((key, values))
+            }
+            _ => ParsedEventWithErrors::new(self.session_type, *data_event, key, values),
+        };
+        if !self.passes_filter(&event) {
+            return Ok(());
+        }
+        self.select_values(&mut event);
+        if self.field_orders.is_some() {
+            let field_order = self.order_like_source(&mut event, source_field_order);
+            self.field_orders
+                .as_mut()
+                .expect("field orders are kept")
+                .push(field_order);
+        }
+
+        output.push(event);
+        Ok(())
+    }
+
+    /// Coalesces the events of a single call in a snapshot session. The
+    /// connectors parse one message per call, so the changes of a key spread
+    /// over several messages are not collapsed. Across calls, the coalescer
+    /// only remembers the emitted rows, so that the deletions of keys never
+    /// emitted and the re-insertions of unchanged rows are dropped.
+    ///
+    /// Only the events from `start` on are taken, so that the contexts of a
+    /// batch are finished one by one in a shared buffer.
+    fn finish_events(&mut self, events: &mut Vec<ParsedEventWithErrors>, start: usize) {
+        if matches!(self.session_type, SessionType::Snapshot) {
+            let coalesced = self.snapshot_coalescer.coalesce(events.split_off(start));
+            events.extend(coalesced);
+        }
+        self.stats.record(&events[start..]);
+    }
+}
+
+impl Parser for TransparentParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut events = Vec::with_capacity(1);
+        self.parse_into(data, &mut events)?;
+        self.finish_events(&mut events, 0);
+        Ok(events)
+    }
+
+    fn parse_batch(&mut self, contexts: &[ReaderContext]) -> ParseResult {
+        let mut events = Vec::with_capacity(contexts.len());
+        for (index, context) in contexts.iter().enumerate() {
+            let start = events.len();
+            self.parse_into(context, &mut events)
+                .map_err(|error| ParseError::BatchContextFailed { index, error })?;
+            self.finish_events(&mut events, start);
+        }
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        self.selection
+            .as_ref()
+            .map_or(self.value_field_names.len(), Vec::len)
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.session_type
+    }
+
+    fn reset(&mut self) {
+        // The stats are kept, as promised by `Parser::stats`. So are the dead
+        // letters, which are left from before the rewind and still wait to be
+        // collected with `take_dead_letters`.
+        self.snapshot_coalescer.clear();
+        self.next_auto_key = 0;
+        if let Some(cached_rows) = &mut self.cached_rows {
+            cached_rows.clear();
+        }
+        if let Some(accumulated_rows) = &mut self.accumulated_rows {
+            accumulated_rows.clear();
+        }
+        self.field_stats.fill(FieldStats::default());
+        for stats in self.numeric_stats.iter_mut().flatten() {
+            *stats = NumericStats::default();
+        }
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.stats
+    }
+}
+
+/// Guesses the schema of the records provided by a reader from the first
+/// `sample_limit` of them. A field gets the narrowest type fitting all its
+/// values, integers mixed with floats become floats and other mixed types
+/// become strings. A field that is absent or null in some records becomes
+/// optional, with `None` as the default.
+pub fn infer_schema(
+    samples: &[ReaderContext],
+    sample_limit: usize,
+) -> HashMap<String, InnerSchemaField> {
+    // For each field: the type of its non-null values and whether it can be null
+    let mut fields: HashMap<&str, (Option<Type>, bool)> = HashMap::new();
+    let records = samples
+        .iter()
+        .filter_map(|sample| match sample {
+            Diff((_, _, values)) if values.get_special().is_none() => Some(values),
+            _ => None,
+        })
+        .take(sample_limit);
+    for (index, values) in records.enumerate() {
+        for (name, value) in values.iter() {
+            // A field first seen after the first record was absent before
+            let (type_, nullable) = fields.entry(name.as_str()).or_insert((None, index > 0));
+            match value.as_ref().map(inferred_type) {
+                Ok(None) => *nullable = true,
+                Ok(Some(value_type)) => {
+                    *type_ = Some(match type_.take() {
+                        Some(previous) => widen_inferred_type(previous, value_type),
+                        None => value_type,
+                    });
+                }
+                // A value that failed to convert tells nothing about the type
+                Err(_) => {}
+            }
+        }
+        for (name, (_, nullable)) in &mut fields {
+            if values.get(name).is_none() {
+                *nullable = true;
+            }
+        }
+    }
+    fields
+        .into_iter()
+        .map(|(name, (type_, nullable))| {
+            let field = match (type_, nullable) {
+                (None, _) => InnerSchemaField::new(Type::Any, Some(Value::None)),
+                (Some(type_), true) => {
+                    InnerSchemaField::new(Type::Optional(type_.into()), Some(Value::None))
+                }
+                (Some(type_), false) => InnerSchemaField::new(type_, None),
+            };
+            (name.to_string(), field)
+        })
+        .collect()
+}
+
+fn inferred_type(value: &Value) -> Option<Type> {
+    let type_ = match value {
+        Value::None => return None,
+        Value::Bool(_) => Type::Bool,
+        Value::Int(_) => Type::Int,
+        Value::Float(_) => Type::Float,
+        Value::Pointer(_) => Type::Pointer,
+        Value::String(_) => Type::String,
+        Value::Bytes(_) => Type::Bytes,
+        Value::DateTimeNaive(_) => Type::DateTimeNaive,
+        Value::DateTimeUtc(_) => Type::DateTimeUtc,
+        Value::Duration(_) => Type::Duration,
+        Value::Json(_) => Type::Json,
+        Value::Decimal(_) => Type::Decimal,
+        Value::Uuid(_) => Type::Uuid,
+        Value::Date(_) => Type::Date,
+        Value::Time(_) => Type::Time,
+        Value::Int128(_) => Type::Int128,
+        Value::Tuple(_)
+        | Value::IntArray(_)
+        | Value::FloatArray(_)
+        | Value::Map(_)
+        | Value::PyObjectWrapper(_)
+        | Value::Error
+        | Value::Pending => Type::Any,
+    };
+    Some(type_)
+}
+
+fn widen_inferred_type(first: Type, second: Type) -> Type {
+    match (first, second) {
+        (first, second) if first == second => first,
+        (Type::Int | Type::Float, Type::Int | Type::Float) => Type::Float,
+        _ => Type::String,
+    }
+}
+
+/// How long [`DedupParser`] remembers a key after letting its insertion through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DedupTtl {
+    /// The number of insertions and upserts seen by the parser
+    Events(u64),
+    /// The wall-clock time
+    Time(Duration),
+}
+
+/// Wraps a parser and suppresses repeated insertions of the same key until the
+/// key is deleted. The events without a key are identified by their values.
+///
+/// The set of seen keys is kept across `parse` calls and grows with the number
+/// of distinct keys present at the same time, unless a TTL is set. With a TTL,
+/// a key is forgotten once it expires, so that its next insertion passes again.
+pub struct DedupParser {
+    inner: Box<dyn Parser>,
+    // The values are the sequence numbers of the insertions that let the keys in
+    seen_keys: HashMap<Vec<Value>, u64>,
+    ttl: Option<DedupTtl>,
+    // Keys in the order of their insertions, only filled when there is a TTL
+    expiry_queue: VecDeque<(u64, Instant, Vec<Value>)>,
+    insertions: u64,
+}
+
+impl DedupParser {
+    pub fn new(inner: Box<dyn Parser>) -> DedupParser {
+        DedupParser {
+            inner,
+            seen_keys: HashMap::new(),
+            ttl: None,
+            expiry_queue: VecDeque::new(),
+            insertions: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: DedupTtl) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The number of keys currently remembered.
+    pub fn seen_keys_count(&self) -> usize {
+        self.seen_keys.len()
+    }
+
+    fn dedup_key(key: &KeyFieldsWithErrors, values: &ValueFieldsWithErrors) -> Option<Vec<Value>> {
+        match key {
+            Some(Ok(key)) => Some(key.clone()),
+            Some(Err(_)) => None,
+            None => values
+                .iter()
+                .map(|value| value.as_ref().ok().cloned())
+                .collect(),
+        }
+    }
+
+    // Each key is pushed to the queue once per insertion, so every entry is
+    // popped at most once. The entries of deleted or re-inserted keys are stale
+    // and only dropped from the queue.
+    fn evict_expired(&mut self, now: Instant) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        while let Some((sequence_number, inserted_at, _)) = self.expiry_queue.front() {
+            let expired = match ttl {
+                DedupTtl::Events(events) => self.insertions - sequence_number >= events,
+                DedupTtl::Time(duration) => now.duration_since(*inserted_at) >= duration,
+            };
+            if !expired {
+                break;
+            }
+            let (sequence_number, _, key) = self
+                .expiry_queue
+                .pop_front()
+                .expect("the queue is not empty");
+            if self.seen_keys.get(&key) == Some(&sequence_number) {
+                self.seen_keys.remove(&key);
+            }
+        }
+    }
+
+    // Returns false if the key is already present
+    fn insert(&mut self, key: Vec<Value>, now: Instant) -> bool {
+        if self.seen_keys.contains_key(&key) {
+            return false;
+        }
+        if self.ttl.is_some() {
+            self.expiry_queue
+                .push_back((self.insertions, now, key.clone()));
+        }
+        self.seen_keys.insert(key, self.insertions);
+        true
+    }
+
+    fn keep(&mut self, event: &ParsedEventWithErrors, now: Instant) -> bool {
+        match event {
+            ParsedEventWithErrors::Insert((key, values)) => {
+                self.insertions += 1;
+                self.evict_expired(now);
+                Self::dedup_key(key, values).map_or(true, |dedup_key| self.insert(dedup_key, now))
+            }
+            ParsedEventWithErrors::Delete((key, values)) => {
+                if let Some(dedup_key) = Self::dedup_key(key, values) {
+                    self.seen_keys.remove(&dedup_key);
+                }
+                true
+            }
+            ParsedEventWithErrors::Upsert((key, values)) => {
+                self.insertions += 1;
+                self.evict_expired(now);
+                if let Some(dedup_key) = Self::dedup_key(key, values) {
+                    self.insert(dedup_key, now);
+                }
+                true
+            }
+            ParsedEventWithErrors::AdvanceTime => true,
+        }
+    }
+}
+
+impl Parser for DedupParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut events = self.inner.parse(data)?;
+        let now = Instant::now();
+        events.retain(|event| self.keep(event, now));
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Dedup({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.seen_keys.clear();
+        self.expiry_queue.clear();
+        self.insertions = 0;
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Wraps a parser and passes through a fixed fraction of the rows, chosen by
+/// the hash of the given field, of the key or, if there is none, of the whole
+/// row. The choice only depends on the hashed values, so it is the same in
+/// every run, and the deletions of the sampled rows pass as well.
+///
+/// The rows, in which the hashed values contain errors, are dropped.
+pub struct SampleParser {
+    inner: Box<dyn Parser>,
+    // The rows with the bucket below it are passed
+    threshold: u32,
+    field_index: Option<usize>,
+}
+
+const SAMPLE_BUCKETS: u32 = 1 << 16;
+
+impl SampleParser {
+    pub fn new(
+        inner: Box<dyn Parser>,
+        rate: f64,
+        value_field_names: &[String],
+        field_name: Option<&str>,
+    ) -> Result<SampleParser> {
+        assert!(
+            (0.0..=1.0).contains(&rate),
+            "the sample rate must be between 0 and 1"
+        );
+        let field_index = field_name
+            .map(|field_name| {
+                value_field_names
+                    .iter()
+                    .position(|name| name == field_name)
+                    .ok_or_else(|| Error::FieldNotInSchema {
+                        name: field_name.to_string(),
+                        schema_keys: value_field_names.to_vec(),
+                    })
+            })
+            .transpose()?;
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let threshold = (rate * f64::from(SAMPLE_BUCKETS)).round() as u32;
+        Ok(SampleParser {
+            inner,
+            threshold,
+            field_index,
+        })
+    }
+
+    fn is_sampled(&self, key: &KeyFieldsWithErrors, values: &ValueFieldsWithErrors) -> bool {
+        let hashed: Option<Vec<Value>> = match (self.field_index, key) {
+            (Some(index), _) => values
+                .get(index)
+                .and_then(|value| value.as_ref().ok())
+                .map(|value| vec![value.clone()]),
+            (None, Some(key)) => key.as_ref().ok().cloned(),
+            (None, None) => values
+                .iter()
+                .map(|value| value.as_ref().ok().cloned())
+                .collect(),
+        };
+        let Some(hashed) = hashed else {
+            return false;
+        };
+        if hashed
+            .iter()
+            .any(|value| matches!(value, Value::Error | Value::Pending))
+        {
+            return false;
+        }
+        let bucket = Key::for_values(&hashed).0 % KeyImpl::from(SAMPLE_BUCKETS);
+        bucket < KeyImpl::from(self.threshold)
+    }
+}
+
+impl Parser for SampleParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut events = self.inner.parse(data)?;
+        events.retain(|event| match event {
+            ParsedEventWithErrors::AdvanceTime => true,
+            ParsedEventWithErrors::Insert((key, values))
+            | ParsedEventWithErrors::Delete((key, values))
+            | ParsedEventWithErrors::Upsert((key, values)) => self.is_sampled(key, values),
+        });
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Sample({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Wraps a parser and repeats `parse` if it fails, waiting for an exponentially
+/// growing delay between the attempts. The errors in individual values are not
+/// retried.
+pub struct RetryingParser {
+    inner: Box<dyn Parser>,
+    max_attempts: usize,
+    base_delay: Duration,
+    retries: usize,
+    last_error: Option<String>,
+}
+
+impl RetryingParser {
+    pub fn new(inner: Box<dyn Parser>, max_attempts: usize, base_delay: Duration) -> Self {
+        Self {
+            inner,
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            retries: 0,
+            last_error: None,
+        }
+    }
+
+    /// The total number of retries done by the parser.
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// The message of the last error returned by the wrapped parser.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+impl Parser for RetryingParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut delay = self.base_delay;
+        let mut attempt = 1;
+        loop {
+            match self.inner.parse(data) {
+                Ok(events) => return Ok(events),
+                Err(e) => {
+                    self.last_error = Some(e.to_string());
+                    if attempt >= self.max_attempts {
+                        return Err(e);
+                    }
+                }
+            }
+            sleep(delay);
+            delay = delay.saturating_mul(2);
+            attempt += 1;
+            self.retries += 1;
+        }
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Retrying({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Wraps a parser and passes at most the given number of events on each call,
+/// keeping the rest in order until the next calls. The kept events can be
+/// drained by parsing `ReaderContext::Empty`.
+pub struct ThrottleParser {
+    inner: Box<dyn Parser>,
+    max_events: usize,
+    buffer: VecDeque<ParsedEventWithErrors>,
+}
+
+impl ThrottleParser {
+    pub fn new(inner: Box<dyn Parser>, max_events: usize) -> Self {
+        Self {
+            inner,
+            max_events: max_events.max(1),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// The number of events waiting to be passed.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn release(&mut self, events: Vec<ParsedEventWithErrors>) -> Vec<ParsedEventWithErrors> {
+        self.buffer.extend(events);
+        let count = self.max_events.min(self.buffer.len());
+        self.buffer.drain(..count).collect()
+    }
+}
+
+impl Parser for ThrottleParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let events = self.inner.parse(data)?;
+        Ok(self.release(events))
+    }
+
+    fn parse_batch(&mut self, contexts: &[ReaderContext]) -> ParseResult {
+        let events = self.inner.parse_batch(contexts)?;
+        Ok(self.release(events))
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Throttle({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Tries the parsers in order and returns the events of the first one that
+/// succeeds, so that a source can switch between formats. A parser succeeds if
+/// it doesn't fail as a whole, even if some of the values are errors.
+///
+/// If all the parsers fail, the error of the last one is returned, or, if the
+/// dead letters are collected, the context is kept until `take_dead_letters`.
+pub struct FirstOfParser {
+    parsers: Vec<Box<dyn Parser>>,
+    last_success: Option<usize>,
+    dead_letters: Option<Vec<(ReaderContext, String)>>,
+}
+
+impl FirstOfParser {
+    pub fn new(parsers: Vec<Box<dyn Parser>>) -> Self {
+        assert!(!parsers.is_empty(), "at least one parser is needed");
+        Self {
+            parsers,
+            last_success: None,
+            dead_letters: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_dead_letters(mut self) -> Self {
+        self.dead_letters = Some(Vec::new());
+        self
+    }
+
+    /// Removes and returns the contexts which failed since the last call.
+    pub fn take_dead_letters(&mut self) -> Vec<(ReaderContext, String)> {
+        self.dead_letters.as_mut().map(take).unwrap_or_default()
+    }
+
+    /// The index of the parser that succeeded on the last parsed context.
+    pub fn last_success(&self) -> Option<usize> {
+        self.last_success
+    }
+}
+
+impl Parser for FirstOfParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut last_error = None;
+        for (index, parser) in self.parsers.iter_mut().enumerate() {
+            match parser.parse(data) {
+                Ok(events) => {
+                    self.last_success = Some(index);
+                    return Ok(events);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        self.last_success = None;
+        let error = last_error.expect("there is at least one parser");
+        match &mut self.dead_letters {
+            Some(dead_letters) => {
+                dead_letters.push((data.clone(), error.to_string()));
+                Ok(vec![])
+            }
+            None => Err(error),
+        }
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        for parser in &mut self.parsers {
+            parser.on_new_source_started(metadata);
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        self.parsers[0].column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        let descriptions = self.parsers.iter().map(|parser| parser.short_description());
+        format!("FirstOf({})", descriptions.format(", ")).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.parsers[0].session_type()
+    }
+
+    fn reset(&mut self) {
+        self.last_success = None;
+        for parser in &mut self.parsers {
+            parser.reset();
+        }
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.parsers.iter().map(|parser| parser.stats()).fold(
+            ParserStats::default(),
+            |total, stats| ParserStats {
+                events: total.events + stats.events,
+                rows_with_errors: total.rows_with_errors + stats.rows_with_errors,
+                errors: total.errors + stats.errors,
+                bytes_consumed: total.bytes_consumed + stats.bytes_consumed,
+            },
+        )
+    }
+}
+
+/// Wraps a parser and turns each event, where the given field holds a list or
+/// a JSON array, into a separate event for every element. The other fields are
+/// repeated in all resulting events.
+///
+/// When the event has a key, the position of the element is appended to it, so
+/// that the resulting rows don't collide.
+pub struct ExplodeParser {
+    inner: Box<dyn Parser>,
+    field_index: usize,
+}
+
+impl ExplodeParser {
+    pub fn new(
+        inner: Box<dyn Parser>,
+        value_field_names: &[String],
+        field_name: &str,
+    ) -> Result<ExplodeParser> {
+        let field_index = value_field_names
+            .iter()
+            .position(|name| name == field_name)
+            .ok_or_else(|| Error::FieldNotInSchema {
+                name: field_name.to_string(),
+                schema_keys: value_field_names.to_vec(),
+            })?;
+        Ok(ExplodeParser { inner, field_index })
+    }
+
+    // Gives the event back if it can't be exploded
+    fn explode(
+        &self,
+        key: KeyFieldsWithErrors,
+        values: ValueFieldsWithErrors,
+    ) -> Result<Vec<ExplodedRow>, ExplodedRow> {
+        let elements: Vec<_> = match values.get(self.field_index) {
+            Some(Ok(Value::Tuple(elements))) => elements.iter().cloned().collect(),
+            Some(Ok(Value::Json(json))) if json.is_array() => json
+                .as_array()
+                .expect("json is an array")
+                .iter()
+                .cloned()
+                .map(Value::from)
+                .collect(),
+            _ => return Err((key, values)),
+        };
+        let key = match key {
+            Some(Ok(key)) => Some(key),
+            None => None,
+            Some(Err(e)) => return Err((Some(Err(e)), values)),
+        };
+        Ok(elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| {
+                let key = key.as_ref().map(|key| {
+                    let mut key = key.clone();
+                    key.push(Value::Int(
+                        index.try_into().expect("index should fit in i64"),
+                    ));
+                    Ok(key)
+                });
+                let values = values
+                    .iter()
+                    .enumerate()
+                    .map(|(position, value)| {
+                        if position == self.field_index {
+                            match element {
+                                Value::Error => Err(ParseError::ErrorInExplodedElement.into()),
+                                ref element => Ok(element.clone()),
+                            }
+                        } else {
+                            duplicate_value_with_error(value)
+                        }
+                    })
+                    .collect();
+                (key, values)
+            })
+            .collect())
+    }
+}
+
+type ExplodedRow = (KeyFieldsWithErrors, ValueFieldsWithErrors);
+
+fn duplicate_value_with_error(value: &DynResult<Value>) -> DynResult<Value> {
+    match value {
+        Ok(value) => Ok(value.clone()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+impl Parser for ExplodeParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut result = Vec::new();
+        for event in self.inner.parse(data)? {
+            let (make_event, key, values): (fn(_) -> _, _, _) = match event {
+                ParsedEventWithErrors::Insert((key, values)) => {
+                    (ParsedEventWithErrors::Insert, key, values)
+                }
+                ParsedEventWithErrors::Delete((key, values)) => {
+                    (ParsedEventWithErrors::Delete, key, values)
+                }
+                ParsedEventWithErrors::Upsert((key, values)) => {
+                    (ParsedEventWithErrors::Upsert, key, values)
+                }
+                ParsedEventWithErrors::AdvanceTime => {
+                    result.push(ParsedEventWithErrors::AdvanceTime);
+                    continue;
+                }
+            };
+            match self.explode(key, values) {
+                Ok(rows) => result.extend(rows.into_iter().map(make_event)),
+                Err(row) => result.push(make_event(row)),
+            }
+        }
+        Ok(result)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Explode({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Wraps a parser and stamps its inserted and upserted rows with constant
+/// columns, such as the name of the source. A column already produced by the
+/// inner parser is overridden in place, the other ones are appended in the
+/// given order.
+///
+/// Deletions are passed through unchanged, unless [`EnrichParser::with_deletes`]
+/// is set.
+pub struct EnrichParser {
+    inner: Box<dyn Parser>,
+    value_field_names: Vec<String>,
+    // Position in the output for each of the constant columns
+    metadata: Vec<(usize, Value)>,
+    enrich_deletes: bool,
+}
+
+impl EnrichParser {
+    pub fn new(
+        inner: Box<dyn Parser>,
+        value_field_names: &[String],
+        metadata: Vec<(String, Value)>,
+    ) -> EnrichParser {
+        let mut value_field_names = value_field_names.to_vec();
+        let metadata = metadata
+            .into_iter()
+            .map(|(name, value)| {
+                let position = value_field_names
+                    .iter()
+                    .position(|other| *other == name)
+                    .unwrap_or_else(|| {
+                        value_field_names.push(name);
+                        value_field_names.len() - 1
+                    });
+                (position, value)
+            })
+            .collect();
+        EnrichParser {
+            inner,
+            value_field_names,
+            metadata,
+            enrich_deletes: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_deletes(mut self) -> Self {
+        self.enrich_deletes = true;
+        self
+    }
+
+    /// The names of the columns in the emitted rows, including the constant ones.
+    pub fn value_field_names(&self) -> &[String] {
+        &self.value_field_names
+    }
+
+    fn enrich(&self, values: &mut ValueFieldsWithErrors) {
+        // Deletions in upsert sessions carry no values
+        if values.is_empty() {
+            return;
+        }
+        values.resize_with(self.value_field_names.len(), || Ok(Value::None));
+        for (position, value) in &self.metadata {
+            values[*position] = Ok(value.clone());
+        }
+    }
+}
+
+impl Parser for EnrichParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut events = self.inner.parse(data)?;
+        for event in &mut events {
+            match event {
+                ParsedEventWithErrors::Insert((_, values))
+                | ParsedEventWithErrors::Upsert((_, values)) => self.enrich(values),
+                ParsedEventWithErrors::Delete((_, values)) if self.enrich_deletes => {
+                    self.enrich(values);
+                }
+                ParsedEventWithErrors::Delete(_) | ParsedEventWithErrors::AdvanceTime => {}
+            }
+        }
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Enrich({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// Wraps a parser and appends to its inserted and upserted rows a column with
+/// the number of rows stamped before, starting from zero. The counter is kept
+/// across the calls of `parse` and restarts from zero on `reset`. Unlike an
+/// autoincremented key, the column is only data.
+///
+/// Deletions are passed through unchanged, unless [`SequenceParser::with_deletes`]
+/// is set, in which case they are stamped and counted too.
+pub struct SequenceParser {
+    inner: Box<dyn Parser>,
+    value_field_names: Vec<String>,
+    type_: Type,
+    next_sequence_number: i64,
+    stamp_deletes: bool,
+}
+
+impl SequenceParser {
+    /// Creates a parser appending an `Int` column named `column_name` to the
+    /// columns of `inner`, named `value_field_names`.
+    pub fn new(
+        inner: Box<dyn Parser>,
+        value_field_names: &[String],
+        column_name: String,
+    ) -> Result<SequenceParser> {
+        if value_field_names.contains(&column_name) {
+            return Err(Error::DerivedFieldCollides(column_name));
+        }
+        let mut value_field_names = value_field_names.to_vec();
+        value_field_names.push(column_name);
+        Ok(SequenceParser {
+            inner,
+            value_field_names,
+            type_: Type::Int,
+            next_sequence_number: 0,
+            stamp_deletes: false,
+        })
+    }
+
+    /// Sets the type of the column, which can be `Int`, `Float` or `String`.
+    pub fn with_type(mut self, type_: Type) -> Result<Self> {
+        if !matches!(type_, Type::Int | Type::Float | Type::String) {
+            return Err(Error::SequenceTypeUnsupported(type_));
+        }
+        self.type_ = type_;
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn with_deletes(mut self) -> Self {
+        self.stamp_deletes = true;
+        self
+    }
+
+    /// The names of the columns in the emitted rows, including the sequence one.
+    pub fn value_field_names(&self) -> &[String] {
+        &self.value_field_names
+    }
+
+    fn stamp(&mut self, values: &mut ValueFieldsWithErrors) {
+        // Deletions in upsert sessions carry no values
+        if values.is_empty() {
+            return;
+        }
+        let sequence_number = self.next_sequence_number;
+        self.next_sequence_number += 1;
+        #[allow(clippy::cast_precision_loss)]
+        let value = match self.type_ {
+            Type::Float => Value::from(sequence_number as f64),
+            Type::String => Value::from(sequence_number.to_string().as_str()),
+            _ => Value::Int(sequence_number),
+        };
+        values.push(Ok(value));
+    }
+}
+
+impl Parser for SequenceParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let mut events = self.inner.parse(data)?;
+        for event in &mut events {
+            match event {
+                ParsedEventWithErrors::Insert((_, values))
+                | ParsedEventWithErrors::Upsert((_, values)) => self.stamp(values),
+                ParsedEventWithErrors::Delete((_, values)) if self.stamp_deletes => {
+                    self.stamp(values);
+                }
+                ParsedEventWithErrors::Delete(_) | ParsedEventWithErrors::AdvanceTime => {}
+            }
+        }
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.value_field_names.len()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Sequence({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.next_sequence_number = 0;
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+/// What [`MultiSchemaParser`] does with a record whose discriminator value has
+/// no schema registered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownDiscriminator {
+    /// Emit a row where every column is an error
+    #[default]
+    Error,
+    /// Drop the record
+    Skip,
+}
+
+/// Dispatches each record to one of several schemas, chosen by the string
+/// value of the discriminator field. Every schema must have the same number of
+/// columns, since they all feed the same table.
+///
+/// When the discriminator is included in the output, it goes before the
+/// columns of the chosen schema.
+pub struct MultiSchemaParser {
+    discriminator_field: String,
+    parsers: HashMap<String, TransparentParser>,
+    width: usize,
+    include_discriminator: bool,
+    on_unknown: UnknownDiscriminator,
+    session_type: SessionType,
+    stats: ParserStats,
+}
+
+impl MultiSchemaParser {
+    pub fn new(
+        discriminator_field: String,
+        schemas: HashMap<String, (Vec<String>, HashMap<String, InnerSchemaField>)>,
+        session_type: SessionType,
+    ) -> Result<MultiSchemaParser> {
+        let width = schemas
+            .values()
+            .map(|(value_field_names, _)| value_field_names.len())
+            .max()
+            .unwrap_or(0);
+        let mut parsers = HashMap::with_capacity(schemas.len());
+        for (discriminator, (value_field_names, schema)) in schemas {
+            if value_field_names.len() != width {
+                return Err(Error::MultiSchemaWidthMismatch {
+                    discriminator,
+                    width: value_field_names.len(),
+                    expected: width,
+                });
+            }
+            let parser = TransparentParser::new(None, value_field_names, schema, session_type)?;
+            parsers.insert(discriminator, parser);
+        }
+        Ok(MultiSchemaParser {
+            discriminator_field,
+            parsers,
+            width,
+            include_discriminator: false,
+            on_unknown: UnknownDiscriminator::default(),
+            session_type,
+            stats: ParserStats::default(),
+        })
+    }
+
+    #[must_use]
+    pub fn with_discriminator_in_output(mut self, include_discriminator: bool) -> Self {
+        self.include_discriminator = include_discriminator;
+        self
+    }
+
+    #[must_use]
+    pub fn with_unknown_discriminator(mut self, on_unknown: UnknownDiscriminator) -> Self {
+        self.on_unknown = on_unknown;
+        self
+    }
+
+    fn discriminator(&self, values: &ValuesMap) -> Result<String, ParseError> {
+        match values.get(&self.discriminator_field) {
+            Some(Ok(Value::String(discriminator))) => Ok(discriminator.to_string()),
+            Some(Ok(value)) => Err(ParseError::UnknownDiscriminator(value.to_string())),
+            Some(Err(_)) | None => Err(ParseError::DiscriminatorMissing(
+                self.discriminator_field.clone(),
+            )),
+        }
+    }
+
+    fn error_row(
+        &self,
+        data_event: DataEventType,
+        key: KeyFieldsWithErrors,
+        error: &ParseError,
+    ) -> ParsedEventWithErrors {
+        let values = (0..self.column_count())
+            .map(|_| Err(error.to_string().into()))
+            .collect();
+        ParsedEventWithErrors::new(self.session_type, data_event, key, values)
+    }
+}
+
+impl Parser for MultiSchemaParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let (data_event, key, values) = match data {
+            Empty => return Ok(vec![]),
+            Diff((data_event, key, values)) => (data_event, key, values),
+            _ => return Err(ParseError::UnsupportedReaderContext.into()),
+        };
+        if values.get_special() == Some(SpecialEvent::Commit) {
+            return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
+        }
+        let discriminator = match self.discriminator(values) {
+            Ok(discriminator) if self.parsers.contains_key(&discriminator) => discriminator,
+            result => {
+                if self.on_unknown == UnknownDiscriminator::Skip {
+                    return Ok(vec![]);
+                }
+                let error = result.map_or_else(|error| error, ParseError::UnknownDiscriminator);
+                let events = vec![self.error_row(*data_event, key.clone().map(Ok), &error)];
+                self.stats.record(&events);
+                return Ok(events);
+            }
+        };
+        let parser = self
+            .parsers
+            .get_mut(&discriminator)
+            .expect("the discriminator has a schema");
+        let mut events = parser.parse(data)?;
+        if self.include_discriminator {
+            let discriminator = Value::from(discriminator.as_str());
+            for event in &mut events {
+                match event {
+                    ParsedEventWithErrors::Insert((_, values))
+                    | ParsedEventWithErrors::Delete((_, values))
+                    | ParsedEventWithErrors::Upsert((_, values)) => {
+                        // Deletions in upsert sessions carry no values
+                        if !values.is_empty() {
+                            values.insert(0, Ok(discriminator.clone()));
+                        }
+                    }
+                    ParsedEventWithErrors::AdvanceTime => {}
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        for parser in self.parsers.values_mut() {
+            parser.on_new_source_started(metadata);
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        self.width + usize::from(self.include_discriminator)
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("MultiSchema({})", self.discriminator_field).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.session_type
+    }
+
+    fn reset(&mut self) {
+        for parser in self.parsers.values_mut() {
+            parser.reset();
+        }
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.parsers
+            .values()
+            .map(Parser::stats)
+            .fold(self.stats, |total, stats| ParserStats {
+                events: total.events + stats.events,
+                rows_with_errors: total.rows_with_errors + stats.rows_with_errors,
+                errors: total.errors + stats.errors,
+                bytes_consumed: total.bytes_consumed + stats.bytes_consumed,
+            })
+    }
+}
+
+/// The compression of the bytes handed to [`DecompressingParser`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionCodec {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+    /// Gzip or Zstd, recognized by the magic bytes, or none if neither matches
+    Auto,
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl CompressionCodec {
+    fn detect(data: &[u8]) -> Self {
+        if data.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if data.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    fn decompress(self, data: &[u8]) -> Result<Vec<u8>, ParseError> {
+        let codec = match self {
+            Self::Auto => Self::detect(data),
+            codec => codec,
+        };
+        let decompressed = match codec {
+            Self::None | Self::Auto => return Ok(data.to_vec()),
+            Self::Gzip => {
+                let mut decompressed = Vec::new();
+                MultiGzDecoder::new(data)
+                    .read_to_end(&mut decompressed)
+                    .map(|_| decompressed)
+            }
+            Self::Zstd => zstd::decode_all(data),
+        };
+        decompressed.map_err(|error| ParseError::DecompressionFailed { codec, error })
+    }
+}
+
+/// Wraps a parser and decompresses the raw bytes of each context before they
+/// are parsed, including the values of the key-value contexts. The other
+/// contexts are passed as they are.
+///
+/// The data that fails to decompress fails the parsing, unless the dead
+/// letters are kept, in which case the context becomes one of them.
+pub struct DecompressingParser {
+    inner: Box<dyn Parser>,
+    codec: CompressionCodec,
+    dead_letters: Option<Vec<(ReaderContext, String)>>,
+}
+
+impl DecompressingParser {
+    pub fn new(inner: Box<dyn Parser>, codec: CompressionCodec) -> DecompressingParser {
+        DecompressingParser {
+            inner,
+            codec,
+            dead_letters: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_dead_letters(mut self) -> Self {
+        self.dead_letters = Some(Vec::new());
+        self
+    }
+
+    /// Returns the contexts that failed to decompress since the last call,
+    /// each with the reason of the failure.
+    pub fn take_dead_letters(&mut self) -> Vec<(ReaderContext, String)> {
+        self.dead_letters.as_mut().map(take).unwrap_or_default()
+    }
+
+    fn decompress(&self, data: &ReaderContext) -> Result<Option<ReaderContext>, ParseError> {
+        let decompressed = match data {
+            RawBytes(event, bytes) => RawBytes(*event, self.codec.decompress(bytes)?),
+            KeyValue((key, Some(value))) => {
+                KeyValue((key.clone(), Some(self.codec.decompress(value)?)))
+            }
+            KeyValueWithHeaders((key, Some(value), headers)) => KeyValueWithHeaders((
+                key.clone(),
+                Some(self.codec.decompress(value)?),
+                headers.clone(),
+            )),
+            _ => return Ok(None),
+        };
+        Ok(Some(decompressed))
+    }
+}
+
+impl Parser for DecompressingParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        match (self.decompress(data), &mut self.dead_letters) {
+            (Ok(Some(decompressed)), _) => self.inner.parse(&decompressed),
+            (Ok(None), _) => self.inner.parse(data),
+            (Err(error), Some(dead_letters)) => {
+                dead_letters.push((data.clone(), error.to_string()));
+                Ok(vec![])
+            }
+            (Err(error), None) => Err(error.into()),
+        }
+    }
+
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.inner.on_new_source_started(metadata);
+    }
+
+    fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Decompressing({})", self.inner.short_description()).into()
+    }
+
+    fn session_type(&self) -> SessionType {
+        self.inner.session_type()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+}
+
+const LENGTH_PREFIX_SIZE: usize = 4;
 
-                let line = prepare_plaintext_string(raw_bytes)?;
+/// Splits a stream of raw bytes into records, each preceded by its length as a
+/// 4-byte big-endian integer, and parses each record with the wrapped parser as
+/// a separate context. The bytes of an incomplete record are kept until the
+/// rest of it arrives in the next contexts. The other contexts are passed as
+/// they are.
+///
+/// A record longer than `max_frame_size` fails the parsing and the buffered
+/// bytes are dropped, since the stream can't be followed any further. If the
+/// dead letters are kept, the context declaring such a record becomes one of
+/// them instead.
+pub struct LengthPrefixedParser {
+    inner: Box<dyn Parser>,
+    max_frame_size: usize,
+    buffer: Vec<u8>,
+    dead_letters: Option<Vec<(ReaderContext, String)>>,
+}
 
-                let key_and_value: Vec<&str> = line.trim().split(&self.separator).collect();
-                if key_and_value.len() != 2 {
-                    return Err(ParseError::KeyValueTokensIncorrect(key_and_value.len()).into());
-                }
-                (key_and_value[0].to_string(), key_and_value[1].to_string())
-            }
-            KeyValue((k, v)) => {
-                let key = if let Some(bytes) = k {
-                    prepare_plaintext_string(bytes)?
-                } else {
-                    if self.key_field_names.is_some() {
-                        return Err(ParseError::EmptyKafkaPayload.into());
-                    }
-                    DEBEZIUM_EMPTY_KEY_PAYLOAD.to_string()
-                };
-                let value = match v {
-                    Some(bytes) => prepare_plaintext_string(bytes)?,
-                    None => return Err(ParseError::EmptyKafkaPayload.into()),
-                };
-                (key, value)
-            }
-            Diff(_) | TokenizedEntries(_, _) | Empty => {
-                return Err(ParseError::UnsupportedReaderContext.into());
-            }
-        };
+impl LengthPrefixedParser {
+    pub fn new(inner: Box<dyn Parser>, max_frame_size: usize) -> LengthPrefixedParser {
+        LengthPrefixedParser {
+            inner,
+            max_frame_size,
+            buffer: Vec::new(),
+            dead_letters: None,
+        }
+    }
 
-        let Ok(value_change) = serde_json::from_str(&raw_value_change) else {
-            return Err(ParseError::FailedToParseJson(raw_value_change).into());
-        };
+    #[must_use]
+    pub fn with_dead_letters(mut self) -> Self {
+        self.dead_letters = Some(Vec::new());
+        self
+    }
 
-        let change_payload = match value_change {
-            JsonValue::Object(payload_value) => payload_value,
-            JsonValue::Null => return Ok(Vec::new()), // tombstone event for kafka: nothing to do for us
-            _ => {
-                return Err(ParseError::DebeziumFormatViolated(
-                    DebeziumFormatError::IncorrectJsonRoot,
-                )
-                .into())
+    /// Returns the contexts that declared an oversized record since the last
+    /// call, each with the reason of the failure.
+    pub fn take_dead_letters(&mut self) -> Vec<(ReaderContext, String)> {
+        self.dead_letters.as_mut().map(take).unwrap_or_default()
+    }
+
+    // The complete frames are removed from the buffer, the ones before an
+    // oversized frame are returned together with the error
+    fn take_frames(&mut self) -> (Vec<Vec<u8>>, Option<ParseError>) {
+        let mut frames = Vec::new();
+        let mut start = 0;
+        while let Some(prefix) = self.buffer.get(start..start + LENGTH_PREFIX_SIZE) {
+            let prefix = prefix.try_into().expect("the prefix has the declared size");
+            let length = usize::try_from(u32::from_be_bytes(prefix)).unwrap_or(usize::MAX);
+            if length > self.max_frame_size {
+                self.buffer.clear();
+                let error = ParseError::FrameTooLarge {
+                    length,
+                    max_frame_size: self.max_frame_size,
+                };
+                return (frames, Some(error));
             }
-        };
+            let end = start + LENGTH_PREFIX_SIZE + length;
+            let Some(frame) = self.buffer.get(start + LENGTH_PREFIX_SIZE..end) else {
+                break;
+            };
+            frames.push(frame.to_vec());
+            start = end;
+        }
+        self.buffer.drain(..start);
+        (frames, None)
+    }
+}
 
-        let Ok(change_key) = serde_json::from_str::<JsonValue>(&raw_key_change) else {
-            return Err(ParseError::FailedToParseJson(raw_key_change).into());
+impl Parser for LengthPrefixedParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let RawBytes(event, bytes) = data else {
+            return self.inner.parse(data);
         };
-
-        if !change_payload.contains_key("payload") {
-            return Err(ParseError::DebeziumFormatViolated(
-                DebeziumFormatError::NoPayloadAtTopLevel,
-            )
-            .into());
+        self.buffer.extend_from_slice(bytes);
+        let (frames, error) = self.take_frames();
+        let mut events = Vec::new();
+        for frame in frames {
+            events.extend(self.inner.parse(&RawBytes(*event, frame))?);
         }
-
-        match &change_payload["payload"]["op"] {
-            JsonValue::String(op) => match op.as_ref() {
-                "r" | "c" => {
-                    self.parse_read_or_create(&change_key["payload"], &change_payload["payload"])
-                }
-                "u" => self.parse_update(&change_key["payload"], &change_payload["payload"]),
-                "d" => self.parse_delete(&change_key["payload"], &change_payload["payload"]),
-                _ => Err(ParseError::UnsupportedDebeziumOperation(op.to_string()).into()),
-            },
-            _ => Err(ParseError::DebeziumFormatViolated(
-                DebeziumFormatError::OperationFieldMissing,
-            )
-            .into()),
+        match (error, &mut self.dead_letters) {
+            (None, _) => {}
+            (Some(error), Some(dead_letters)) => {
+                dead_letters.push((data.clone(), error.to_string()));
+            }
+            (Some(error), None) => return Err(error.into()),
         }
+        Ok(events)
     }
 
-    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
+        self.buffer.clear();
+        self.inner.on_new_source_started(metadata);
+    }
 
     fn column_count(&self) -> usize {
-        self.value_field_names.len()
+        self.inner.column_count()
+    }
+
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("LengthPrefixed({})", self.inner.short_description()).into()
     }
 
     fn session_type(&self) -> SessionType {
-        match self.db_type {
-            DebeziumDBType::Postgres => SessionType::Native,
+        self.inner.session_type()
+    }
 
-            // MongoDB events don't contain the previous state of the record
-            // therefore we can only do the upsert with the same key and the
-            // new value
-            DebeziumDBType::MongoDB => SessionType::Upsert,
-        }
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.inner.reset();
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.inner.stats()
     }
 }
 
-pub struct JsonLinesParser {
+/// Parses the changes delivered as the images of the row before and after the
+/// change, stored as JSON objects in two fields of the context. An update
+/// becomes the deletion of the old row followed by the insertion of the new
+/// one, while a missing or null image means that the row was inserted or
+/// deleted. Both images are read with the same schema.
+pub struct CdcImageParser {
     key_field_names: Option<Vec<String>>,
     value_field_names: Vec<String>,
-    column_paths: HashMap<String, String>,
-    field_absence_is_error: bool,
     schema: HashMap<String, InnerSchemaField>,
-    metadata_column_value: Value,
-    session_type: SessionType,
+    before_field: String,
+    after_field: String,
+    stats: ParserStats,
 }
 
-impl JsonLinesParser {
+impl CdcImageParser {
     pub fn new(
         key_field_names: Option<Vec<String>>,
         value_field_names: Vec<String>,
-        column_paths: HashMap<String, String>,
-        field_absence_is_error: bool,
         schema: HashMap<String, InnerSchemaField>,
-        session_type: SessionType,
-    ) -> Result<JsonLinesParser> {
+        before_field: String,
+        after_field: String,
+    ) -> Result<Self> {
         ensure_all_fields_in_schema(&key_field_names, &value_field_names, &schema)?;
-        Ok(JsonLinesParser {
+        Ok(Self {
             key_field_names,
             value_field_names,
-            column_paths,
-            field_absence_is_error,
             schema,
-            metadata_column_value: Value::None,
-            session_type,
+            before_field,
+            after_field,
+            stats: ParserStats::default(),
         })
     }
-}
 
-impl Parser for JsonLinesParser {
-    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
-        let (data_event, key, line) = match data {
-            RawBytes(event, line) => {
-                let line = prepare_plaintext_string(line)?;
-                (*event, None, line)
-            }
-            KeyValue((_key, value)) => {
-                if let Some(line) = value {
-                    let line = prepare_plaintext_string(line)?;
-                    (DataEventType::Insert, None, line)
-                } else {
-                    return Err(ParseError::EmptyKafkaPayload.into());
-                }
-            }
-            Diff(_) | TokenizedEntries(..) => {
-                return Err(ParseError::UnsupportedReaderContext.into());
+    fn image<'a>(values: &'a ValuesMap, field_name: &str) -> DynResult<Option<&'a JsonValue>> {
+        match values.get(field_name) {
+            None | Some(Ok(Value::None)) => Ok(None),
+            Some(Ok(Value::Json(json))) if json.is_null() => Ok(None),
+            Some(Ok(Value::Json(json))) if json.is_object() => Ok(Some(&**json)),
+            Some(Ok(value)) => Err(ParseError::CdcImageNotAnObject {
+                field_name: field_name.to_string(),
+                value: value.clone(),
             }
-            Empty => return Ok(vec![]),
-        };
-
-        if line.is_empty() {
-            return Ok(vec![]);
-        }
-
-        if line == COMMIT_LITERAL {
-            return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
+            .into()),
+            Some(Err(error)) => Err(error.clone().into()),
         }
+    }
 
-        let payload: JsonValue = match serde_json::from_str(&line) {
-            Ok(json_value) => json_value,
-            Err(_) => return Err(ParseError::FailedToParseJson(line).into()),
-        };
-
-        let key = key.or(match &self.key_field_names {
-            Some(key_field_names) => Some(
-                values_by_names_from_json(
-                    &payload,
-                    key_field_names,
-                    &self.column_paths,
-                    self.field_absence_is_error,
-                    &self.schema,
-                    &self.metadata_column_value,
-                )
-                .into_iter()
-                .collect(),
-            ),
-            None => None, // use method from the different PR
+    fn parse_image(&self, image: &JsonValue, event: DataEventType) -> ParsedEventWithErrors {
+        let key = self.key_field_names.as_ref().map(|names| {
+            values_by_names_from_json(
+                image,
+                names,
+                &HashMap::new(),
+                true,
+                &self.schema,
+                &Value::None,
+            )
+            .into_iter()
+            .collect()
         });
-
         let values = values_by_names_from_json(
-            &payload,
+            image,
             &self.value_field_names,
-            &self.column_paths,
-            self.field_absence_is_error,
+            &HashMap::new(),
+            true,
             &self.schema,
-            &self.metadata_column_value,
+            &Value::None,
         );
-
-        let event = ParsedEventWithErrors::new(self.session_type, data_event, key, values);
-
-        Ok(vec![event])
+        ParsedEventWithErrors::new(SessionType::Native, event, key, values)
     }
+}
 
-    fn on_new_source_started(&mut self, metadata: &SourceMetadata) {
-        let metadata_serialized: JsonValue = metadata.serialize();
-        self.metadata_column_value = metadata_serialized.into();
+impl Parser for CdcImageParser {
+    fn parse(&mut self, data: &ReaderContext) -> ParseResult {
+        let values = match data {
+            Empty => return Ok(vec![]),
+            Diff((_, _, values)) => values,
+            _ => return Err(ParseError::UnsupportedReaderContext.into()),
+        };
+        if values.get_special() == Some(SpecialEvent::Commit) {
+            return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
+        }
+        let before = Self::image(values, &self.before_field)?;
+        let after = Self::image(values, &self.after_field)?;
+        let events: Vec<_> = chain!(
+            before.map(|image| self.parse_image(image, DataEventType::Delete)),
+            after.map(|image| self.parse_image(image, DataEventType::Insert)),
+        )
+        .collect();
+        self.stats.record(&events);
+        Ok(events)
     }
 
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
     fn column_count(&self) -> usize {
         self.value_field_names.len()
     }
 
-    fn session_type(&self) -> SessionType {
-        self.session_type
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("CdcImage({}, {})", self.before_field, self.after_field).into()
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.stats
     }
 }
 
-/// Receives values directly from a Reader and passes them
-/// further only making adjustments according to the schema.
+/// Which of the sub-records merged by [`MergeParser`] provides the value of a
+/// field present in both of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeWinner {
+    Header,
+    #[default]
+    Body,
+}
+
+/// Parses the records made of two JSON objects, a header and a body, stored in
+/// two fields of the context. Their fields are merged into one flat record,
+/// which is then read with the schema. A missing or null sub-record is treated
+/// as empty, so its fields take their defaults.
 ///
-/// It is useful when no raw values parsing is needed.
-pub struct TransparentParser {
+/// A field present in both sub-records with values of different JSON types
+/// becomes an error, whichever side wins.
+pub struct MergeParser {
     key_field_names: Option<Vec<String>>,
     value_field_names: Vec<String>,
     schema: HashMap<String, InnerSchemaField>,
-    session_type: SessionType,
+    header_field: String,
+    body_field: String,
+    winner: MergeWinner,
+    stats: ParserStats,
 }
 
-impl TransparentParser {
+impl MergeParser {
     pub fn new(
         key_field_names: Option<Vec<String>>,
         value_field_names: Vec<String>,
         schema: HashMap<String, InnerSchemaField>,
-        session_type: SessionType,
-    ) -> Result<TransparentParser> {
+        header_field: String,
+        body_field: String,
+    ) -> Result<Self> {
         ensure_all_fields_in_schema(&key_field_names, &value_field_names, &schema)?;
-        Ok(TransparentParser {
+        Ok(Self {
             key_field_names,
             value_field_names,
             schema,
-            session_type,
+            header_field,
+            body_field,
+            winner: MergeWinner::default(),
+            stats: ParserStats::default(),
         })
     }
+
+    #[must_use]
+    pub fn with_winner(mut self, winner: MergeWinner) -> Self {
+        self.winner = winner;
+        self
+    }
+
+    fn sub_record<'a>(
+        values: &'a ValuesMap,
+        field_name: &str,
+    ) -> DynResult<Option<&'a JsonMap<String, JsonValue>>> {
+        match values.get(field_name) {
+            None | Some(Ok(Value::None)) => Ok(None),
+            Some(Ok(Value::Json(json))) if json.is_null() => Ok(None),
+            Some(Ok(Value::Json(json))) if json.is_object() => Ok(json.as_object()),
+            Some(Ok(value)) => Err(ParseError::SubRecordNotAnObject {
+                field_name: field_name.to_string(),
+                value: value.clone(),
+            }
+            .into()),
+            Some(Err(error)) => Err(error.clone().into()),
+        }
+    }
+
+    // Gives the merged record and the names of the fields with conflicting types
+    fn merge(
+        &self,
+        header: Option<&JsonMap<String, JsonValue>>,
+        body: Option<&JsonMap<String, JsonValue>>,
+    ) -> (JsonValue, Vec<String>) {
+        let (loser, winner) = match self.winner {
+            MergeWinner::Header => (body, header),
+            MergeWinner::Body => (header, body),
+        };
+        let mut merged = loser.cloned().unwrap_or_default();
+        let mut conflicts = Vec::new();
+        for (name, value) in winner.into_iter().flatten() {
+            if let Some(other) = merged.insert(name.clone(), value.clone()) {
+                let compatible = other.is_null()
+                    || value.is_null()
+                    || discriminant(&other) == discriminant(value);
+                if !compatible {
+                    conflicts.push(name.clone());
+                }
+            }
+        }
+        (JsonValue::Object(merged), conflicts)
+    }
+
+    fn project(
+        &self,
+        record: &JsonValue,
+        names: &[String],
+        conflicts: &[String],
+    ) -> ValueFieldsWithErrors {
+        let mut values = values_by_names_from_json(
+            record,
+            names,
+            &HashMap::new(),
+            true,
+            &self.schema,
+            &Value::None,
+        );
+        for (name, value) in zip(names, &mut values) {
+            if conflicts.contains(name) {
+                *value = Err(ParseError::MergedFieldTypesConflict {
+                    field_name: name.clone(),
+                }
+                .into());
+            }
+        }
+        values
+    }
 }
 
-impl Parser for TransparentParser {
+impl Parser for MergeParser {
     fn parse(&mut self, data: &ReaderContext) -> ParseResult {
-        let (data_event, key, values) = match data {
+        let (data_event, values) = match data {
             Empty => return Ok(vec![]),
-            Diff((data_event, key, values)) => (data_event, key, values),
+            Diff((data_event, _, values)) => (data_event, values),
             _ => return Err(ParseError::UnsupportedReaderContext.into()),
         };
         if values.get_special() == Some(SpecialEvent::Commit) {
             return Ok(vec![ParsedEventWithErrors::AdvanceTime]);
         }
-        let key = key.clone().map(Ok).or_else(|| {
-            self.key_field_names.as_ref().map(|key_field_names| {
-                key_field_names
-                    .iter()
-                    .map(|name| {
-                        self.schema[name] // ensure_all_fields_in_schema in new() makes sure that all keys are in the schema
-                        .maybe_use_default(name, values.get(name).cloned())
-                    })
-                    .collect()
-            })
+        let header = Self::sub_record(values, &self.header_field)?;
+        let body = Self::sub_record(values, &self.body_field)?;
+        let (record, conflicts) = self.merge(header, body);
+        let key = self.key_field_names.as_ref().map(|names| {
+            self.project(&record, names, &conflicts)
+                .into_iter()
+                .collect()
         });
-
-        let values: Vec<_> = self
-            .value_field_names
-            .iter()
-            .map(|name| {
-                self.schema[name] // ensure_all_fields_in_schema in new() makes sure that all keys are in the schema
-                    .maybe_use_default(name, values.get(name).cloned())
-            })
-            .collect();
-
-        let event = ParsedEventWithErrors::new(self.session_type, *data_event, key, values);
-
-        Ok(vec![event])
+        let values = self.project(&record, &self.value_field_names, &conflicts);
+        let events = vec![ParsedEventWithErrors::new(
+            SessionType::Native,
+            *data_event,
+            key,
+            values,
+        )];
+        self.stats.record(&events);
+        Ok(events)
     }
 
     fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
@@ -1781,8 +7295,12 @@ impl Parser for TransparentParser {
         self.value_field_names.len()
     }
 
-    fn session_type(&self) -> SessionType {
-        self.session_type
+    fn short_description(&self) -> Cow<'static, str> {
+        format!("Merge({}, {})", self.header_field, self.body_field).into()
+    }
+
+    fn stats(&self) -> ParserStats {
+        self.stats
     }
 }
 
@@ -2135,6 +7653,18 @@ fn serialize_value_to_bson(value: &Value) -> Result<BsonValue, FormatterError> {
         // We use milliseconds in durations to be consistent with the granularity
         // of the BSON DateTime type
         Value::Duration(d) => Ok(bson!(d.milliseconds())),
+        Value::Decimal(d) => Ok(BsonValue::String(d.to_string())),
+        Value::Uuid(u) => Ok(BsonValue::String(u.to_string())),
+        Value::Date(d) => Ok(BsonValue::String(d.to_string())),
+        Value::Time(t) => Ok(BsonValue::String(t.to_string())),
+        Value::Int128(i) => Ok(BsonValue::String(i.to_string())),
+        Value::Map(entries) => {
+            let mut document = BsonDocument::new();
+            for (key, value) in entries.iter() {
+                document.insert(key.to_string(), serialize_value_to_bson(value)?);
+            }
+            Ok(BsonValue::Document(document))
+        }
         Value::Json(j) => Ok(bson!(j.to_string())),
         Value::Error => Err(FormatterError::ErrorValueNonBsonSerializable),
         Value::PyObjectWrapper(_) => Err(FormatterError::TypeNonBsonSerializable {
@@ -269,7 +269,7 @@ impl DeltaBatchWriter {
         let delta_type = match type_ {
             Type::Bool => DeltaTableKernelType::Primitive(DeltaTablePrimitiveType::Boolean),
             Type::Float => DeltaTableKernelType::Primitive(DeltaTablePrimitiveType::Double),
-            Type::String | Type::Json | Type::Pointer => {
+            Type::String | Type::Enum(_) | Type::Json | Type::Pointer => {
                 DeltaTableKernelType::Primitive(DeltaTablePrimitiveType::String)
             }
             Type::PyObjectWrapper | Type::Bytes => {
@@ -329,7 +329,17 @@ impl DeltaBatchWriter {
                 DeltaTableKernelType::Struct(struct_descriptor.into())
             }
             Type::Optional(wrapped) => return Self::delta_table_type(wrapped),
-            Type::Any | Type::Future(_) => return Err(WriteError::UnsupportedType(type_.clone())),
+            Type::Any
+            | Type::Decimal
+            | Type::Uuid
+            | Type::Date
+            | Type::Time
+            | Type::Int32
+            | Type::Int128
+            | Type::FixedString { .. }
+            | Type::FixedArray { .. }
+            | Type::Map(_)
+            | Type::Future(_) => return Err(WriteError::UnsupportedType(type_.clone())),
         };
         Ok(delta_type)
     }
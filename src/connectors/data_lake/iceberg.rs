@@ -174,7 +174,7 @@ impl IcebergTableParams {
         let iceberg_type = match type_ {
             Type::Bool => IcebergType::Primitive(IcebergPrimitiveType::Boolean),
             Type::Float => IcebergType::Primitive(IcebergPrimitiveType::Double),
-            Type::String | Type::Json | Type::Pointer => {
+            Type::String | Type::Enum(_) | Type::Json | Type::Pointer => {
                 IcebergType::Primitive(IcebergPrimitiveType::String)
             }
             Type::Bytes | Type::PyObjectWrapper => {
@@ -196,9 +196,19 @@ impl IcebergTableParams {
                 let array_type = IcebergListType::new(nested_type.into());
                 IcebergType::List(array_type)
             }
-            Type::Any | Type::Array(_, _) | Type::Tuple(_) | Type::Future(_) => {
-                return Err(WriteError::UnsupportedType(type_.clone()))
-            }
+            Type::Any
+            | Type::Decimal
+            | Type::Uuid
+            | Type::Date
+            | Type::Time
+            | Type::Int32
+            | Type::Int128
+            | Type::FixedString { .. }
+            | Type::FixedArray { .. }
+            | Type::Map(_)
+            | Type::Array(_, _)
+            | Type::Tuple(_)
+            | Type::Future(_) => return Err(WriteError::UnsupportedType(type_.clone())),
         };
         Ok(iceberg_type)
     }
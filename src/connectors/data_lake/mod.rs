@@ -101,7 +101,9 @@ pub fn parquet_value_into_pathway_value(
             EngineDuration::new_with_unit(*i, "us").unwrap(),
         )),
         (ParquetValue::Double(f), Type::Float | Type::Any) => Some(Value::Float((*f).into())),
-        (ParquetValue::Str(s), Type::String | Type::Any) => Some(Value::String(s.into())),
+        (ParquetValue::Str(s), Type::String | Type::Enum(_) | Type::Any) => {
+            Some(Value::String(s.into()))
+        }
         (ParquetValue::Str(s), Type::Pointer) => parse_pathway_pointer(s).ok(),
         (ParquetValue::Str(s), Type::Json) => serde_json::from_str::<serde_json::Value>(s)
             .ok()
@@ -306,12 +308,14 @@ fn column_into_pathway_values(
             })
         }
         (ArrowDataType::Boolean, Type::Bool | Type::Any) => convert_arrow_boolean_array(column),
-        (ArrowDataType::Utf8, Type::String | Type::Json | Type::Pointer | Type::Any) => {
-            convert_arrow_string_array::<i32>(column, column_name, expected_type_unopt)
-        }
-        (ArrowDataType::LargeUtf8, Type::String | Type::Json | Type::Pointer | Type::Any) => {
-            convert_arrow_string_array::<i64>(column, column_name, expected_type_unopt)
-        }
+        (
+            ArrowDataType::Utf8,
+            Type::String | Type::Enum(_) | Type::Json | Type::Pointer | Type::Any,
+        ) => convert_arrow_string_array::<i32>(column, column_name, expected_type_unopt),
+        (
+            ArrowDataType::LargeUtf8,
+            Type::String | Type::Enum(_) | Type::Json | Type::Pointer | Type::Any,
+        ) => convert_arrow_string_array::<i64>(column, column_name, expected_type_unopt),
         (ArrowDataType::Binary, Type::Bytes | Type::PyObjectWrapper | Type::Any) => {
             convert_arrow_bytes_array::<i32>(column, column_name, expected_type_unopt)
         }
@@ -430,7 +434,7 @@ fn convert_arrow_string_array<OffsetType: OffsetSizeTrait>(
         .into_iter()
         .map(|v| match v {
             Some(v) => match expected_type {
-                Type::String | Type::Any => Ok(Value::String(v.into())),
+                Type::String | Type::Enum(_) | Type::Any => Ok(Value::String(v.into())),
                 Type::Json => serde_json::from_str::<serde_json::Value>(v)
                     .map(Value::from)
                     .map_err(|_| {
@@ -293,7 +293,7 @@ impl LakeWriter {
             Type::Bool => ArrowDataType::Boolean,
             Type::Int | Type::Duration => ArrowDataType::Int64,
             Type::Float => ArrowDataType::Float64,
-            Type::String | Type::Json | Type::Pointer => ArrowDataType::Utf8,
+            Type::String | Type::Enum(_) | Type::Json | Type::Pointer => ArrowDataType::Utf8,
             Type::Bytes | Type::PyObjectWrapper => {
                 if settings.use_64bit_size_type {
                     ArrowDataType::LargeBinary
@@ -356,7 +356,17 @@ impl LakeWriter {
                 let struct_descriptor = ArrowFields::from(struct_fields);
                 ArrowDataType::Struct(struct_descriptor)
             }
-            Type::Any | Type::Future(_) => return Err(WriteError::UnsupportedType(type_.clone())),
+            Type::Any
+            | Type::Decimal
+            | Type::Uuid
+            | Type::Date
+            | Type::Time
+            | Type::Int32
+            | Type::Int128
+            | Type::FixedString { .. }
+            | Type::FixedArray { .. }
+            | Type::Map(_)
+            | Type::Future(_) => return Err(WriteError::UnsupportedType(type_.clone())),
         })
     }
 
@@ -72,7 +72,7 @@ use postgres::Client as PsqlClient;
 use pyo3::prelude::*;
 use rdkafka::consumer::{BaseConsumer, Consumer, DefaultConsumerContext};
 use rdkafka::error::{KafkaError, RDKafkaErrorCode};
-use rdkafka::message::BorrowedMessage;
+use rdkafka::message::{BorrowedMessage, Headers};
 use rdkafka::producer::{BaseRecord, DefaultProducerContext, Producer, ThreadedProducer};
 use rdkafka::topic_partition_list::Offset as KafkaOffset;
 use rdkafka::Message;
@@ -146,10 +146,26 @@ impl ValuesMap {
         self.map.get(key)
     }
 
+    /// Looks up the key ignoring ASCII case, an exact match takes precedence.
+    /// If several keys differ only in case, the smallest one is used.
+    pub fn get_ignore_ascii_case(&self, key: &str) -> Option<&Result<Value, Box<ConversionError>>> {
+        self.map.get(key).or_else(|| {
+            self.map
+                .iter()
+                .filter(|(candidate, _)| candidate.eq_ignore_ascii_case(key))
+                .min_by_key(|(candidate, _)| candidate.as_str())
+                .map(|(_, value)| value)
+        })
+    }
+
     pub fn remove(&mut self, key: &str) {
         self.map.remove(key);
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Result<Value, Box<ConversionError>>)> {
+        self.map.iter()
+    }
+
     pub fn to_pure_hashmap(self) -> DynResult<HashMap<String, Value>> {
         self.map
             .into_iter()
@@ -194,11 +210,15 @@ impl Display for MessageQueueTopic {
     }
 }
 
-#[derive(Debug)]
+/// The headers of a message, such as the ones of a Kafka record.
+pub type MessageHeaders = HashMap<String, Vec<u8>>;
+
+#[derive(Clone, Debug)]
 pub enum ReaderContext {
     RawBytes(DataEventType, Vec<u8>),
     TokenizedEntries(DataEventType, Vec<String>),
     KeyValue((Option<Vec<u8>>, Option<Vec<u8>>)),
+    KeyValueWithHeaders((Option<Vec<u8>>, Option<Vec<u8>>, MessageHeaders)),
     Diff((DataEventType, Option<Vec<Value>>, ValuesMap)),
     Empty,
 }
@@ -226,6 +246,37 @@ impl ReaderContext {
     pub fn from_key_value(key: Option<Vec<u8>>, value: Option<Vec<u8>>) -> ReaderContext {
         ReaderContext::KeyValue((key, value))
     }
+
+    pub fn from_key_value_with_headers(
+        key: Option<Vec<u8>>,
+        value: Option<Vec<u8>>,
+        headers: MessageHeaders,
+    ) -> ReaderContext {
+        ReaderContext::KeyValueWithHeaders((key, value, headers))
+    }
+
+    /// The headers of the message, if the context carries them.
+    pub fn headers(&self) -> Option<&MessageHeaders> {
+        match self {
+            ReaderContext::KeyValueWithHeaders((_, _, headers)) => Some(headers),
+            _ => None,
+        }
+    }
+
+    /// The number of bytes of the raw payload, that is the bytes of a raw-bytes
+    /// context or the value of a key-value one. The contexts holding parsed
+    /// data have no raw payload. `TransparentParser` adds it to
+    /// `ParserStats::bytes_consumed`.
+    pub fn payload_len(&self) -> Option<usize> {
+        match self {
+            ReaderContext::RawBytes(_, bytes) => Some(bytes.len()),
+            ReaderContext::KeyValue((_, value))
+            | ReaderContext::KeyValueWithHeaders((_, value, _)) => value.as_ref().map(Vec::len),
+            ReaderContext::TokenizedEntries(..) | ReaderContext::Diff(_) | ReaderContext::Empty => {
+                None
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -777,6 +828,13 @@ impl Reader for KafkaReader {
             };
             let message_key = kafka_message.key().map(<[u8]>::to_vec);
             let message_payload = kafka_message.payload().map(<[u8]>::to_vec);
+            // The headers without a value are left out, as if they were absent
+            let message_headers = kafka_message.headers().map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|header| Some((header.key.to_string(), header.value?.to_vec())))
+                    .collect()
+            });
 
             if let Some(lazy_seek_offset) = self.positions_for_seek.get(&kafka_message.partition())
             {
@@ -811,7 +869,14 @@ impl Reader for KafkaReader {
                 (offset_key, offset_value)
             };
             let metadata = KafkaMetadata::from_rdkafka_message(&kafka_message);
-            let message = ReaderContext::from_key_value(message_key, message_payload);
+            let message = match message_headers {
+                Some(headers) => ReaderContext::from_key_value_with_headers(
+                    message_key,
+                    message_payload,
+                    headers,
+                ),
+                None => ReaderContext::from_key_value(message_key, message_payload),
+            };
             self.deferred_read_result = Some(ReadResult::Data(message, offset));
 
             return Ok(ReadResult::NewSource(metadata.into()));
@@ -1336,12 +1401,23 @@ impl PsqlWriter {
             Type::Bool => "BOOLEAN".to_string(),
             Type::Int | Type::Duration => "BIGINT".to_string(),
             Type::Float => "DOUBLE PRECISION".to_string(),
-            Type::Pointer | Type::String => "TEXT".to_string(),
+            Type::Decimal | Type::Int128 => "NUMERIC".to_string(),
+            Type::Uuid => "UUID".to_string(),
+            Type::Date => "DATE".to_string(),
+            Type::Time => "TIME".to_string(),
+            Type::Int32 => "INTEGER".to_string(),
+            Type::Pointer | Type::String | Type::FixedString { .. } | Type::Enum(_) => {
+                "TEXT".to_string()
+            }
             Type::Bytes | Type::PyObjectWrapper => "BYTEA".to_string(),
-            Type::Json => "JSONB".to_string(),
+            Type::Json | Type::Map(_) => "JSONB".to_string(),
             Type::DateTimeNaive => "TIMESTAMP".to_string(),
             Type::DateTimeUtc => "TIMESTAMPTZ".to_string(),
-            Type::Optional(wrapped) | Type::List(wrapped) => {
+            Type::Optional(wrapped)
+            | Type::List(wrapped)
+            | Type::FixedArray {
+                element: wrapped, ..
+            } => {
                 if let Type::Any = **wrapped {
                     return Err(WriteError::UnsupportedType(type_.clone()));
                 }
@@ -1378,10 +1454,13 @@ mod to_sql {
     use std::error::Error;
 
     use bytes::BytesMut;
-    use chrono::{DateTime, NaiveDateTime, Utc};
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
     use ordered_float::OrderedFloat;
     use postgres::types::{to_sql_checked, Format, IsNull, ToSql, Type};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
 
+    use crate::engine::coerce::serialize_value_to_json;
     use crate::engine::time::DateTime as _;
     use crate::engine::Value;
 
@@ -1393,6 +1472,7 @@ mod to_sql {
     }
 
     impl ToSql for Value {
+        #[allow(clippy::too_many_lines)]
         fn to_sql(
             &self,
             ty: &Type,
@@ -1475,6 +1555,36 @@ mod to_sql {
                     "python object"
                 }
                 Self::Pending => "pending",
+                Self::Decimal(d) => {
+                    try_forward!(Decimal, *d);
+                    try_forward!(String, d.to_string());
+                    "decimal"
+                }
+                Self::Map(_) => {
+                    try_forward!(serde_json::Value, serialize_value_to_json(self)?);
+                    "map"
+                }
+                Self::Uuid(u) => {
+                    try_forward!(Uuid, *u);
+                    try_forward!(String, u.to_string());
+                    "UUID"
+                }
+                Self::Date(d) => {
+                    try_forward!(NaiveDate, *d);
+                    try_forward!(String, d.to_string());
+                    "date"
+                }
+                Self::Time(t) => {
+                    try_forward!(NaiveTime, *t);
+                    try_forward!(String, t.to_string());
+                    "time"
+                }
+                Self::Int128(i) => {
+                    try_forward!(i64, *i);
+                    try_forward!(Decimal, Decimal::try_from_i128_with_scale(*i, 0)?);
+                    try_forward!(String, i.to_string());
+                    "int128"
+                }
             };
             Err(Box::new(WrongPathwayType {
                 pathway_type: pathway_type.to_owned(),
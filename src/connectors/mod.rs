@@ -850,7 +850,7 @@ impl Connector {
             }
 
             match entry {
-                ParsedEvent::Insert((_, values)) => {
+                ParsedEvent::Insert((_, values)) | ParsedEvent::Upsert((_, values)) => {
                     if values.len() != self.num_columns {
                         error!("There are {} tokens in the entry, but the expected number of tokens was {}", values.len(), self.num_columns);
                         continue;
@@ -858,7 +858,7 @@ impl Connector {
                     Self::on_insert(key.expect("No key"), values, input_session);
                 }
                 ParsedEvent::Delete((_, values)) => {
-                    if matches!(session_type, SessionType::Native)
+                    if matches!(session_type, SessionType::Native | SessionType::Snapshot)
                         && values.len() != self.num_columns
                     {
                         error!("There are {} tokens in the entry, but the expected number of tokens was {}", values.len(), self.num_columns);
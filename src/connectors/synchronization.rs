@@ -422,7 +422,8 @@ impl ConnectorGroupAccessor {
                 return EntryCheckResponse::Approved(EntrySendApproval::new(None, Value::None))
             }
             ParsedEventWithErrors::Insert((_, values))
-            | ParsedEventWithErrors::Delete((_, values)) => self.extract_target_value(values),
+            | ParsedEventWithErrors::Delete((_, values))
+            | ParsedEventWithErrors::Upsert((_, values)) => self.extract_target_value(values),
         };
         if Some(target_value) <= self.max_possible_value.as_ref() {
             let approval = EntrySendApproval::new(Some(self.source_id), target_value.clone());
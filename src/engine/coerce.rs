@@ -0,0 +1,864 @@
+// Copyright © 2024 Pathway
+
+use std::collections::BTreeMap;
+use std::iter::{once, zip};
+use std::str::from_utf8;
+use std::time::Duration;
+
+use super::error::DynResult;
+use super::time::{DateTimeNaive, DateTimeUtc, Duration as EngineDuration};
+use super::value::parse_pathway_pointer;
+use super::{FixedStringAlign, Type, Value};
+
+use arcstr::ArcStr;
+use base64::engine::general_purpose::STANDARD as base64encoder;
+use base64::Engine;
+use bincode::ErrorKind as BincodeError;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use itertools::Itertools;
+use ndarray::ArrayD;
+use rust_decimal::Decimal;
+use serde_json::json;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use uuid::Uuid;
+
+/// A value that breaks one of the rules by which values are brought to the
+/// types of columns.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("{0:?} is not a valid UUID")]
+    InvalidUuid(String),
+
+    #[error("{0:?} is not a valid date")]
+    InvalidDate(String),
+
+    #[error("{0:?} is not a valid time of day")]
+    InvalidTime(String),
+
+    #[error("{0:?} is not a valid duration")]
+    InvalidDuration(String),
+
+    #[error("value {0} is out of the allowed range")]
+    ValueOutOfRange(String),
+
+    #[error("string length {0} is out of the allowed range")]
+    StringLengthOutOfRange(usize),
+
+    #[error("{0:?} is not a recognized boolean token")]
+    UnknownBoolToken(String),
+
+    #[error("the value is not a list")]
+    NotAList,
+
+    #[error("the array has {got} elements, expected {expected}")]
+    ArrayLengthMismatch { expected: usize, got: usize },
+
+    #[error("the value is not a tuple")]
+    NotATuple,
+
+    #[error("the tuple has {got} elements, expected {expected}")]
+    TupleArityMismatch { expected: usize, got: usize },
+
+    #[error("the value is not a map")]
+    NotAMap,
+
+    #[error("{0} is not a finite float")]
+    NonFiniteFloat(f64),
+
+    #[error("{value:?} is not one of the allowed variants: {variants:?}")]
+    UnknownEnumVariant {
+        value: String,
+        variants: Vec<String>,
+    },
+
+    #[error("expected {expected}, got {got}")]
+    UnexpectedValueType { expected: Type, got: String },
+
+    #[error("Error value is not json-serializable")]
+    ErrorValueNonJsonSerializable,
+
+    #[error("Pending value is not json-serializable")]
+    PendingValueNonJsonSerializable,
+
+    #[error(transparent)]
+    Bincode(#[from] BincodeError),
+
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+}
+
+/// Optional conversions used when the values provided by a reader are brought
+/// to the types declared in the schema.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Default)]
+pub struct CoercionOptions {
+    // Decode strings in `Bytes` columns as base64
+    pub base64_bytes: bool,
+    // Parse strings in `Int` and `Float` columns as numbers
+    pub numeric_strings: bool,
+    // Treat infinities and NaNs in `Float` columns as errors
+    pub finite_floats: bool,
+    // Strings denoting a missing value, such as `NULL` or `\N`
+    pub null_sentinels: Vec<String>,
+    // Ignore the surrounding whitespace when comparing with `null_sentinels`
+    pub trim_null_sentinels: bool,
+    // Parse strings in `Bool` columns with the given tokens
+    pub bool_tokens: Option<BoolTokens>,
+    // `strftime`-style formats tried after `YYYY-MM-DD` in `Date` columns
+    pub date_formats: Vec<String>,
+    // The notation of strings in `Duration` columns
+    pub duration_format: DurationFormat,
+    // The handling of bytes that are not valid UTF-8 in `String` columns
+    pub utf8_mode: Utf8Mode,
+    // The time zone of the strings without an offset in `DateTimeUtc` columns,
+    // such as `America/New_York` or `+02:00`
+    pub default_timezone: Option<String>,
+}
+
+impl CoercionOptions {
+    /// The conversions of [`Value::try_coerce`]: numbers and booleans are read
+    /// from strings as well.
+    pub fn lenient() -> Self {
+        Self {
+            numeric_strings: true,
+            bool_tokens: Some(BoolTokens::default()),
+            ..Default::default()
+        }
+    }
+}
+
+/// The strings accepted as boolean values, compared case-insensitively.
+#[derive(Clone, Debug)]
+pub struct BoolTokens {
+    pub truthy: Vec<String>,
+    pub falsy: Vec<String>,
+}
+
+impl Default for BoolTokens {
+    fn default() -> Self {
+        Self {
+            truthy: ["true", "1", "yes", "t"].map(String::from).into(),
+            falsy: ["false", "0", "no", "f"].map(String::from).into(),
+        }
+    }
+}
+
+impl BoolTokens {
+    fn parse(&self, value: &str) -> Result<bool, Error> {
+        let value = value.trim();
+        let matches = |tokens: &[String]| tokens.iter().any(|t| t.eq_ignore_ascii_case(value));
+        if matches(&self.truthy) {
+            Ok(true)
+        } else if matches(&self.falsy) {
+            Ok(false)
+        } else {
+            Err(Error::UnknownBoolToken(value.to_string()))
+        }
+    }
+}
+
+/// Defines what happens to the bytes that are not valid UTF-8, when they are
+/// provided for a `String` column. The valid bytes are always decoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Utf8Mode {
+    /// The value becomes an error
+    #[default]
+    Strict,
+    /// The invalid sequences are replaced with U+FFFD
+    Lossy,
+    /// The whole record fails to parse
+    Reject,
+}
+
+/// The string notations accepted in `Duration` columns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DurationFormat {
+    /// Either of the notations below
+    #[default]
+    Any,
+    /// ISO-8601, such as `PT1H30M`
+    Iso8601,
+    /// Numbers with unit suffixes, such as `1h30m` or `2days 5min`
+    Humantime,
+}
+
+impl CoercionOptions {
+    pub(crate) fn is_null_sentinel(&self, value: &str) -> bool {
+        let value = if self.trim_null_sentinels {
+            value.trim()
+        } else {
+            value
+        };
+        self.null_sentinels.iter().any(|sentinel| sentinel == value)
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+pub(crate) fn coerce_to_type(
+    value: &Value,
+    type_: &Type,
+    options: &CoercionOptions,
+) -> DynResult<Value> {
+    match (type_.unoptionalize(), value) {
+        (Type::Decimal, Value::String(s)) => Ok(Value::Decimal(s.trim().parse()?)),
+        (Type::Decimal, Value::Int(i)) => Ok(Value::Decimal((*i).into())),
+        (Type::Decimal, Value::Float(f)) => Ok(Value::Decimal(Decimal::try_from(f.0)?)),
+        (Type::Int, Value::String(s)) if options.numeric_strings => {
+            let s = s.trim();
+            if s.is_empty() && type_.is_optional() {
+                Ok(Value::None)
+            } else {
+                Ok(Value::Int(s.parse()?))
+            }
+        }
+        (Type::Int32, Value::String(s)) if options.numeric_strings => {
+            let s = s.trim();
+            if s.is_empty() && type_.is_optional() {
+                Ok(Value::None)
+            } else {
+                ensure_int32(s.parse()?).map(Value::Int)
+            }
+        }
+        (Type::Int32, Value::Int(i)) => ensure_int32(*i).map(Value::Int),
+        // Numbers beyond the 64-bit range can only be delivered as strings
+        (Type::Int128, Value::String(s)) => {
+            let s = s.trim();
+            if s.is_empty() && type_.is_optional() {
+                Ok(Value::None)
+            } else {
+                Ok(Value::Int128(s.parse()?))
+            }
+        }
+        (Type::Int128, Value::Int(i)) => Ok(Value::Int128((*i).into())),
+        (Type::FixedString { len, pad, align }, Value::String(s)) => {
+            Ok(Value::from(trim_fixed_string(s, *len, *pad, *align)?))
+        }
+        (Type::Float, Value::String(s)) if options.numeric_strings => {
+            let s = s.trim();
+            if s.is_empty() && type_.is_optional() {
+                Ok(Value::None)
+            } else {
+                ensure_finite_float(s.parse()?, options).map(Value::from)
+            }
+        }
+        (Type::Float, Value::Float(f)) => ensure_finite_float(f.0, options).map(Value::from),
+        (Type::Enum(variants), Value::String(s)) => {
+            ensure_enum_variant(s, variants)?;
+            Ok(value.clone())
+        }
+        (Type::Uuid, Value::String(s)) => Ok(Value::Uuid(parse_uuid(s.trim())?)),
+        (Type::Date, Value::String(s)) => {
+            Ok(Value::Date(parse_date(s.trim(), &options.date_formats)?))
+        }
+        (Type::Time, Value::String(s)) => Ok(Value::Time(parse_time(s.trim())?)),
+        (Type::Duration, Value::String(s)) => Ok(Value::Duration(parse_duration(
+            s.trim(),
+            options.duration_format,
+        )?)),
+        (Type::String, Value::Bytes(bytes)) => match options.utf8_mode {
+            Utf8Mode::Lossy => Ok(Value::from(String::from_utf8_lossy(bytes).as_ref())),
+            Utf8Mode::Strict | Utf8Mode::Reject => Ok(Value::from(from_utf8(bytes)?)),
+        },
+        (Type::Bool, Value::String(s)) => match &options.bool_tokens {
+            Some(tokens) => Ok(Value::Bool(tokens.parse(s)?)),
+            None => Ok(value.clone()),
+        },
+        #[allow(clippy::cast_precision_loss)]
+        (Type::Float, Value::Int(i)) => Ok(Value::Float((*i as f64).into())),
+        (Type::DateTimeUtc, Value::String(s)) => Ok(Value::DateTimeUtc(parse_datetime_utc(
+            s.trim(),
+            options.default_timezone.as_deref(),
+        )?)),
+        (
+            Type::List(_) | Type::FixedArray { .. } | Type::Tuple(_) | Type::Map(_) | Type::Json,
+            Value::None,
+        ) if type_.is_optional() => Ok(Value::None),
+        // An element that can't be converted only spoils its own position in the list
+        (Type::List(arg), Value::Tuple(elements)) => Ok(Value::from(
+            elements
+                .iter()
+                .map(|element| {
+                    coerce_to_type(element, arg, options)
+                        .ok()
+                        .filter(|element| value_fits_type(element, arg))
+                        .unwrap_or(Value::Error)
+                })
+                .collect::<Vec<_>>(),
+        )),
+        (Type::FixedArray { element, len }, Value::Tuple(elements)) => {
+            if elements.len() != *len {
+                return Err(Error::ArrayLengthMismatch {
+                    expected: *len,
+                    got: elements.len(),
+                }
+                .into());
+            }
+            coerce_to_type(value, &Type::List(element.clone()), options)
+        }
+        // Like in lists, an element that can't be converted becomes an error
+        (Type::Tuple(args), Value::Tuple(elements)) => {
+            ensure_tuple_arity(args, elements.len())?;
+            Ok(Value::from(
+                zip(args.iter(), elements.iter())
+                    .map(|(arg, element)| {
+                        coerce_to_type(element, arg, options)
+                            .ok()
+                            .filter(|element| value_fits_type(element, arg))
+                            .unwrap_or(Value::Error)
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        }
+        (Type::Tuple(args), Value::Json(json)) if json.is_array() => {
+            let elements = json.as_array().expect("json is an array");
+            ensure_tuple_arity(args, elements.len())?;
+            Ok(Value::from(
+                zip(args.iter(), elements)
+                    .map(|(arg, element)| {
+                        parse_value_from_json(element, arg).unwrap_or(Value::Error)
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        }
+        (Type::Bytes, Value::String(s)) if options.base64_bytes => {
+            Ok(Value::Bytes(base64encoder.decode(s.as_bytes())?.into()))
+        }
+        (Type::Json, Value::Json(_)) => Ok(value.clone()),
+        // Any value is a valid JSON column, it is stored as it is
+        (Type::Json, value) => Ok(Value::from(serialize_value_to_json(value)?)),
+        (Type::Map(arg), Value::Map(entries)) => Ok(Value::from(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let value = coerce_to_type(value, arg, options)
+                        .ok()
+                        .filter(|value| value_fits_type(value, arg))
+                        .unwrap_or(Value::Error);
+                    (key.clone(), value)
+                })
+                .collect::<BTreeMap<_, _>>(),
+        )),
+        (Type::Map(arg), Value::Json(json)) if json.is_object() => {
+            Ok(parse_value_from_json(json, &Type::Map(arg.clone()))
+                .expect("a JSON object can always be parsed as a map"))
+        }
+        (Type::List(_) | Type::FixedArray { .. }, _) => Err(Error::NotAList.into()),
+        (Type::Tuple(_), _) => Err(Error::NotATuple.into()),
+        (Type::Map(_), _) => Err(Error::NotAMap.into()),
+        _ => Ok(value.clone()),
+    }
+}
+
+fn ensure_tuple_arity(args: &[Type], len: usize) -> Result<(), Error> {
+    if args.len() == len {
+        Ok(())
+    } else {
+        Err(Error::TupleArityMismatch {
+            expected: args.len(),
+            got: len,
+        })
+    }
+}
+
+pub(crate) fn ensure_int32(value: i64) -> DynResult<i64> {
+    if i32::try_from(value).is_ok() {
+        Ok(value)
+    } else {
+        Err(Error::ValueOutOfRange(value.to_string()).into())
+    }
+}
+
+// The length is checked before the padding is removed
+pub(crate) fn trim_fixed_string(
+    raw_value: &str,
+    len: usize,
+    pad: char,
+    align: FixedStringAlign,
+) -> Result<&str, Error> {
+    let raw_len = raw_value.chars().count();
+    if raw_len > len {
+        return Err(Error::StringLengthOutOfRange(raw_len));
+    }
+    // A value made of padding only keeps a single character, so that zero
+    // padded with zeros stays "0"
+    Ok(match align {
+        FixedStringAlign::Left => match raw_value.trim_end_matches(pad) {
+            "" => &raw_value[..raw_value.len().min(pad.len_utf8())],
+            trimmed => trimmed,
+        },
+        FixedStringAlign::Right => match raw_value.trim_start_matches(pad) {
+            "" => &raw_value[raw_value.len().saturating_sub(pad.len_utf8())..],
+            trimmed => trimmed,
+        },
+    })
+}
+
+/// Parses an RFC 3339 string, converting its offset to UTC. A string without an
+/// offset is read as the local time in `default_timezone`, if it is provided.
+fn parse_datetime_utc(value: &str, default_timezone: Option<&str>) -> DynResult<DateTimeUtc> {
+    match (DateTimeUtc::from_rfc3339(value), default_timezone) {
+        (Ok(datetime), _) => Ok(datetime),
+        (Err(_), Some(timezone)) => {
+            let local = DateTimeNaive::strptime(value, "%Y-%m-%dT%H:%M:%S%.f")?;
+            Ok(local.to_utc_from_timezone(timezone)?)
+        }
+        (Err(error), None) => Err(error.into()),
+    }
+}
+
+fn ensure_finite_float(value: f64, options: &CoercionOptions) -> DynResult<f64> {
+    if options.finite_floats && !value.is_finite() {
+        Err(Error::NonFiniteFloat(value).into())
+    } else {
+        Ok(value)
+    }
+}
+
+// Only the hyphenated and the simple (32 hex digits) forms are accepted
+pub(crate) fn parse_uuid(raw_value: &str) -> Result<Uuid, Error> {
+    match raw_value.len() {
+        32 | 36 => Uuid::try_parse(raw_value).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| Error::InvalidUuid(raw_value.to_string()))
+}
+
+// `YYYY-MM-DD` is always accepted, the other formats are tried after it
+pub(crate) fn parse_date(raw_value: &str, formats: &[String]) -> Result<NaiveDate, Error> {
+    once("%Y-%m-%d")
+        .chain(formats.iter().map(String::as_str))
+        .find_map(|format| NaiveDate::parse_from_str(raw_value, format).ok())
+        .ok_or_else(|| Error::InvalidDate(raw_value.to_string()))
+}
+
+// `HH:MM:SS` with optional fractional seconds, leap seconds are rejected
+pub(crate) fn parse_time(raw_value: &str) -> Result<NaiveTime, Error> {
+    NaiveTime::parse_from_str(raw_value, "%H:%M:%S%.f")
+        .ok()
+        .filter(|time| time.nanosecond() < 1_000_000_000)
+        .ok_or_else(|| Error::InvalidTime(raw_value.to_string()))
+}
+
+// Neither notation can express a negative duration
+fn parse_duration(raw_value: &str, format: DurationFormat) -> Result<EngineDuration, Error> {
+    match format {
+        DurationFormat::Any => {
+            parse_iso8601_duration(raw_value).or_else(|| humantime::parse_duration(raw_value).ok())
+        }
+        DurationFormat::Iso8601 => parse_iso8601_duration(raw_value),
+        DurationFormat::Humantime => humantime::parse_duration(raw_value).ok(),
+    }
+    .and_then(|duration| i64::try_from(duration.as_nanos()).ok())
+    .and_then(|duration_ns| EngineDuration::new_with_unit(duration_ns, "ns").ok())
+    .ok_or_else(|| Error::InvalidDuration(raw_value.to_string()))
+}
+
+// `PnW` or `PnDTnHnMnS`, years and months are rejected as having no fixed length
+fn parse_iso8601_duration(raw_value: &str) -> Option<Duration> {
+    let rest = raw_value.strip_prefix('P')?;
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date_part, time_part)) => (date_part, time_part),
+        None => (rest, ""),
+    };
+    if date_part.is_empty() && time_part.is_empty() {
+        return None;
+    }
+    let mut total = Duration::ZERO;
+    for (mut part, units) in [(date_part, "WD"), (time_part, "HMS")] {
+        // Each unit may appear once and only in the order above
+        let mut units = units.chars();
+        while !part.is_empty() {
+            let end = part.find(|c: char| c.is_ascii_alphabetic())?;
+            let unit = units.find(|unit| part[end..].starts_with(*unit))?;
+            let seconds_per_unit = match unit {
+                'W' => 604_800,
+                'D' => 86_400,
+                'H' => 3_600,
+                'M' => 60,
+                _ => 1,
+            };
+            let component = parse_iso8601_number(&part[..end], unit == 'S')?;
+            total = total.checked_add(component.checked_mul(seconds_per_unit)?)?;
+            part = &part[end + 1..];
+        }
+    }
+    Some(total)
+}
+
+// The number of seconds in a component, only seconds may be fractional
+fn parse_iso8601_number(number: &str, allow_fraction: bool) -> Option<Duration> {
+    let (whole, fraction) = match number.split_once(['.', ',']) {
+        Some(_) if !allow_fraction => return None,
+        Some((whole, fraction)) => (whole, fraction),
+        None => (number, ""),
+    };
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) || fraction.len() > 9 {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<9}").parse().ok()?
+    };
+    Some(Duration::new(whole.parse().ok()?, nanos))
+}
+
+pub(crate) fn ensure_enum_variant(value: &str, variants: &[String]) -> Result<(), Error> {
+    if variants.iter().any(|variant| variant == value) {
+        Ok(())
+    } else {
+        Err(Error::UnknownEnumVariant {
+            value: value.to_string(),
+            variants: variants.to_vec(),
+        })
+    }
+}
+
+pub(crate) fn value_fits_type(value: &Value, type_: &Type) -> bool {
+    match (type_, value) {
+        (Type::Optional(_), Value::None)
+        | (Type::Any | Type::Array(..) | Type::PyObjectWrapper | Type::Future(_), _)
+        | (Type::Bool, Value::Bool(_))
+        | (Type::Int, Value::Int(_))
+        | (Type::Float, Value::Float(_))
+        | (Type::Pointer, Value::Pointer(_))
+        | (Type::String, Value::String(_))
+        | (Type::Bytes, Value::Bytes(_))
+        | (Type::DateTimeNaive, Value::DateTimeNaive(_))
+        | (Type::DateTimeUtc, Value::DateTimeUtc(_))
+        | (Type::Duration, Value::Duration(_))
+        | (Type::Decimal, Value::Decimal(_))
+        | (Type::Uuid, Value::Uuid(_))
+        | (Type::Date, Value::Date(_))
+        | (Type::Time, Value::Time(_))
+        | (Type::Int128, Value::Int128(_))
+        | (Type::Json, Value::Json(_)) => true,
+        (Type::Optional(arg), value) => value_fits_type(value, arg),
+        (Type::Int32, Value::Int(i)) => i32::try_from(*i).is_ok(),
+        (Type::FixedString { len, .. }, Value::String(s)) => s.chars().count() <= *len,
+        (Type::Enum(variants), Value::String(s)) => variants.iter().any(|v| v == s.as_str()),
+        // Elements that failed to convert are already marked as errors
+        (Type::List(arg), Value::Tuple(elements)) => elements
+            .iter()
+            .all(|element| matches!(element, Value::Error) || value_fits_type(element, arg)),
+        (Type::Map(arg), Value::Map(entries)) => entries
+            .values()
+            .all(|value| matches!(value, Value::Error) || value_fits_type(value, arg)),
+        (Type::FixedArray { element, len }, Value::Tuple(elements)) => {
+            elements.len() == *len
+                && elements
+                    .iter()
+                    .all(|value| matches!(value, Value::Error) || value_fits_type(value, element))
+        }
+        (Type::Tuple(args), Value::Tuple(elements)) => {
+            args.len() == elements.len()
+                && zip(args.iter(), elements.iter()).all(|(arg, element)| {
+                    matches!(element, Value::Error) || value_fits_type(element, arg)
+                })
+        }
+        (
+            Type::Bool
+            | Type::Int
+            | Type::Float
+            | Type::Pointer
+            | Type::String
+            | Type::Bytes
+            | Type::DateTimeNaive
+            | Type::DateTimeUtc
+            | Type::Duration
+            | Type::Decimal
+            | Type::Uuid
+            | Type::Date
+            | Type::Time
+            | Type::Int32
+            | Type::Int128
+            | Type::FixedString { .. }
+            | Type::Enum(_)
+            | Type::Json
+            | Type::List(_)
+            | Type::FixedArray { .. }
+            | Type::Map(_)
+            | Type::Tuple(_),
+            _,
+        ) => false,
+    }
+}
+
+fn parse_list_from_json(values: &[JsonValue], dtype: &Type) -> Option<Value> {
+    let mut list = Vec::with_capacity(values.len());
+    for value in values {
+        list.push(parse_value_from_json(value, dtype)?);
+    }
+    Some(Value::from(list))
+}
+
+fn parse_tuple_from_json(values: &[JsonValue], dtypes: &[Type]) -> Option<Value> {
+    if values.len() != dtypes.len() {
+        return None;
+    }
+    let mut tuple = Vec::with_capacity(values.len());
+    for (value, dtype) in values.iter().zip_eq(dtypes.iter()) {
+        tuple.push(parse_value_from_json(value, dtype)?);
+    }
+    Some(Value::from(tuple))
+}
+
+fn parse_ndarray_from_json(value: &JsonMap<String, JsonValue>, dtype: &Type) -> Option<Value> {
+    let JsonValue::Array(ref elements) = value["elements"] else {
+        return None;
+    };
+    let JsonValue::Array(ref json_field_shape) = value["shape"] else {
+        return None;
+    };
+    let mut shape = Vec::new();
+    for shape_element in json_field_shape {
+        let JsonValue::Number(shape_element) = shape_element else {
+            return None;
+        };
+        let shape_element = shape_element.as_u64()?;
+        let Ok(shape_element) = TryInto::<usize>::try_into(shape_element) else {
+            return None;
+        };
+        shape.push(shape_element);
+    }
+
+    match dtype {
+        Type::Int => {
+            let mut flat_elements = Vec::new();
+            for element in elements {
+                let JsonValue::Number(number_element) = element else {
+                    return None;
+                };
+                let int_element = number_element.as_i64()?;
+                flat_elements.push(int_element);
+            }
+            let array_impl = ArrayD::<i64>::from_shape_vec(shape, flat_elements);
+            if let Ok(array_impl) = array_impl {
+                Some(Value::from(array_impl))
+            } else {
+                None
+            }
+        }
+        Type::Float => {
+            let mut flat_elements = Vec::new();
+            for element in elements {
+                let JsonValue::Number(number_element) = element else {
+                    return None;
+                };
+                let float_element = number_element.as_f64()?;
+                flat_elements.push(float_element);
+            }
+            let array_impl = ArrayD::<f64>::from_shape_vec(shape, flat_elements);
+            if let Ok(array_impl) = array_impl {
+                Some(Value::from(array_impl))
+            } else {
+                None
+            }
+        }
+        type_ => {
+            unreachable!("ndarray elements can only be either int or float, but {type_} was used")
+        }
+    }
+}
+
+pub(crate) fn create_bincoded_value(value: &Value) -> Result<String, Error> {
+    let raw_bytes = bincode::serialize(value).map_err(|e| *e)?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(raw_bytes);
+    Ok(encoded)
+}
+
+pub(crate) fn parse_bincoded_value(s: &str) -> Result<Value, Error> {
+    let raw_bytes = base64::engine::general_purpose::STANDARD.decode(s)?;
+    Ok(bincode::deserialize::<Value>(&raw_bytes).map_err(|e| *e)?)
+}
+
+/// Parses a value of type `dtype` from JSON variable `value`.
+/// If the field is not present or has incorrect format in the
+/// given payload, returns `None`. This `None` is further converted
+/// into `Error::FailedToParseFromJson` containing verbose
+/// information about parsing problem.
+#[allow(clippy::too_many_lines)]
+pub(crate) fn parse_value_from_json(value: &JsonValue, dtype: &Type) -> Option<Value> {
+    if value.is_null() {
+        if dtype.is_optional() {
+            return Some(Value::None);
+        }
+        return None;
+    }
+    match (dtype.unoptionalize(), value) {
+        (Type::Bool | Type::Any, JsonValue::Bool(v)) => Some(Value::Bool(*v)),
+        // Numbers parsing
+        (Type::Int, JsonValue::Number(v)) => {
+            let i64_field = v.as_i64()?;
+            Some(Value::from(i64_field))
+        }
+        (Type::Int32, JsonValue::Number(v)) => {
+            let i32_field = i32::try_from(v.as_i64()?).ok()?;
+            Some(Value::from(i64::from(i32_field)))
+        }
+        (Type::Int128, JsonValue::Number(v)) => {
+            let i128_field = v
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| v.as_u64().map(i128::from))?;
+            Some(Value::Int128(i128_field))
+        }
+        (Type::Int128, JsonValue::String(s)) => s.parse().ok().map(Value::Int128),
+        (Type::Float, JsonValue::Number(v)) => {
+            let f64_field = v.as_f64()?;
+            Some(Value::from(f64_field))
+        }
+        (Type::Duration, JsonValue::Number(v)) => {
+            let duration_ns = v.as_i64()?;
+            let engine_duration = EngineDuration::new_with_unit(duration_ns, "ns")
+                .expect("new_with_unit can't fail when 'ns' is used as a unit");
+            Some(Value::Duration(engine_duration))
+        }
+        (Type::Decimal, JsonValue::Number(v)) => {
+            let serialized = v.to_string();
+            serialized
+                .parse()
+                .or_else(|_| Decimal::from_scientific(&serialized))
+                .ok()
+                .map(Value::Decimal)
+        }
+        (Type::Any, JsonValue::Number(v)) => {
+            if let Some(parsed_i64) = v.as_i64() {
+                Some(Value::from(parsed_i64))
+            } else {
+                v.as_f64().map(Value::from)
+            }
+        }
+        // Strings parsing
+        (Type::String | Type::Any, JsonValue::String(s)) => Some(Value::from(s.as_str())),
+        (Type::Enum(variants), JsonValue::String(s)) => ensure_enum_variant(s, variants)
+            .ok()
+            .map(|()| Value::from(s.as_str())),
+        (Type::Bytes, JsonValue::String(s)) => {
+            let decoded = base64::engine::general_purpose::STANDARD.decode(s);
+            if let Ok(decoded) = decoded {
+                Some(Value::Bytes(decoded.into()))
+            } else {
+                None
+            }
+        }
+        (Type::Decimal, JsonValue::String(s)) => s.parse().ok().map(Value::Decimal),
+        (Type::Uuid, JsonValue::String(s)) => parse_uuid(s).ok().map(Value::Uuid),
+        (Type::Date, JsonValue::String(s)) => parse_date(s, &[]).ok().map(Value::Date),
+        (Type::Time, JsonValue::String(s)) => parse_time(s).ok().map(Value::Time),
+        (Type::FixedString { len, pad, align }, JsonValue::String(s)) => {
+            trim_fixed_string(s, *len, *pad, *align)
+                .ok()
+                .map(Value::from)
+        }
+        (Type::PyObjectWrapper, JsonValue::String(s)) => parse_bincoded_value(s).ok(),
+        (Type::Pointer, JsonValue::String(s)) => parse_pathway_pointer(s).ok(),
+        (Type::DateTimeUtc, JsonValue::String(s)) => {
+            let engine_datetime = DateTimeUtc::strptime(s, "%Y-%m-%dT%H:%M:%S%.f%z");
+            if let Ok(engine_datetime) = engine_datetime {
+                Some(Value::DateTimeUtc(engine_datetime))
+            } else {
+                None
+            }
+        }
+        (Type::DateTimeNaive, JsonValue::String(s)) => {
+            let engine_datetime = DateTimeNaive::strptime(s, "%Y-%m-%dT%H:%M:%S%.f");
+            if let Ok(engine_datetime) = engine_datetime {
+                Some(Value::DateTimeNaive(engine_datetime))
+            } else {
+                None
+            }
+        }
+        (Type::Json, value) => Some(Value::from(value.clone())),
+        (Type::Tuple(dtypes), JsonValue::Array(v)) => parse_tuple_from_json(v, dtypes),
+        (Type::List(arg), JsonValue::Array(v)) => parse_list_from_json(v, arg),
+        (Type::FixedArray { element, len }, JsonValue::Array(v)) if v.len() == *len => {
+            parse_list_from_json(v, element)
+        }
+        // A value that doesn't match the type only spoils its own entry
+        (Type::Map(arg), JsonValue::Object(entries)) => Some(Value::from(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let value = parse_value_from_json(value, arg).unwrap_or(Value::Error);
+                    (ArcStr::from(key.as_str()), value)
+                })
+                .collect::<BTreeMap<_, _>>(),
+        )),
+        (Type::Array(_, nested_type), JsonValue::Object(v)) => {
+            parse_ndarray_from_json(v, nested_type.as_ref())
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn serialize_value_to_json(value: &Value) -> Result<JsonValue, Error> {
+    match value {
+        Value::None => Ok(JsonValue::Null),
+        Value::Int(i) => Ok(json!(i)),
+        Value::Float(f) => Ok(json!(f)),
+        Value::Bool(b) => Ok(json!(b)),
+        Value::String(s) => Ok(json!(s)),
+        Value::Pointer(p) => Ok(json!(p.to_string())),
+        Value::Tuple(t) => {
+            let mut items = Vec::with_capacity(t.len());
+            for item in t.iter() {
+                items.push(serialize_value_to_json(item)?);
+            }
+            Ok(JsonValue::Array(items))
+        }
+        Value::Bytes(b) => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(b);
+            Ok(json!(encoded))
+        }
+        Value::IntArray(a) => {
+            let mut flat_elements = Vec::with_capacity(a.len());
+            for item in a.iter() {
+                flat_elements.push(json!(item));
+            }
+            let serialized_values = json!({
+                "shape": a.shape(),
+                "elements": flat_elements,
+            });
+            Ok(serialized_values)
+        }
+        Value::FloatArray(a) => {
+            let mut flat_elements = Vec::with_capacity(a.len());
+            for item in a.iter() {
+                flat_elements.push(json!(item));
+            }
+            let serialized_values = json!({
+                "shape": a.shape(),
+                "elements": flat_elements,
+            });
+            Ok(serialized_values)
+        }
+        Value::DateTimeNaive(dt) => Ok(json!(dt.to_string())),
+        Value::DateTimeUtc(dt) => Ok(json!(dt.to_string())),
+        Value::Duration(d) => Ok(json!(d.nanoseconds())),
+        // Decimals are serialized as strings so that no precision is lost
+        Value::Decimal(d) => Ok(json!(d.to_string())),
+        Value::Uuid(u) => Ok(json!(u.to_string())),
+        Value::Date(d) => Ok(json!(d.to_string())),
+        Value::Time(t) => Ok(json!(t.to_string())),
+        // Like decimals, so that the numbers beyond the 64-bit range are kept intact
+        Value::Int128(i) => Ok(json!(i.to_string())),
+        Value::Map(entries) => {
+            let mut items = JsonMap::with_capacity(entries.len());
+            for (key, value) in entries.iter() {
+                items.insert(key.to_string(), serialize_value_to_json(value)?);
+            }
+            Ok(JsonValue::Object(items))
+        }
+        Value::Json(j) => Ok((**j).clone()),
+        Value::PyObjectWrapper(_) => {
+            let encoded = create_bincoded_value(value)?;
+            Ok(json!(encoded))
+        }
+        Value::Error => Err(Error::ErrorValueNonJsonSerializable),
+        Value::Pending => Err(Error::PendingValueNonJsonSerializable),
+    }
+}
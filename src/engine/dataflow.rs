@@ -3738,7 +3738,7 @@ impl<S: MaybeTotalScope<MaybeTotalTimestamp = Timestamp>> DataflowGraphInner<S>
         Collection<S, (Key, Value)>,
     )> {
         match session_type {
-            SessionType::Native => {
+            SessionType::Native | SessionType::Snapshot => {
                 let mut input_session = InputSession::new();
                 let collection = input_session.to_collection(&mut self.scope);
                 Ok((Box::new(input_session), collection))
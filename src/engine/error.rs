@@ -6,7 +6,7 @@ use std::fmt;
 use std::result;
 
 use super::ColumnPath;
-use super::{Key, Value};
+use super::{Key, Type, Value};
 use crate::connectors::synchronization::Error as InputSynchronizationError;
 use crate::persistence::Error as PersistenceBackendError;
 
@@ -140,6 +140,65 @@ pub enum Error {
         schema_keys: Vec<String>,
     },
 
+    #[error("default value {default} of column {name} doesn't match its type {type_}")]
+    InvalidDefault {
+        name: String,
+        default: Value,
+        type_: Type,
+    },
+
+    #[error("invalid JSON pointer {0:?}")]
+    InvalidJsonPointer(String),
+
+    #[error("columns {first} and {second} differ only in case")]
+    FieldNamesCollideIgnoringCase { first: String, second: String },
+
+    #[error("flattened column {name} collides with column {other}")]
+    FlattenedFieldCollides { name: String, other: String },
+
+    #[error("derived column {0} collides with a column of the schema")]
+    DerivedFieldCollides(String),
+
+    #[error("sequence column can't be of type {0}")]
+    SequenceTypeUnsupported(Type),
+
+    #[error("column {0} has no range of bytes in the fixed-width record")]
+    FixedWidthRangeMissing(String),
+
+    #[error("column {0} is masked, but it is not a string")]
+    MaskedFieldNotAString(String),
+
+    #[error("column {0} is split on a delimiter, but it is not a list")]
+    DelimitedFieldNotAList(String),
+
+    #[error("partial records can only be accumulated with key columns in upsert sessions")]
+    AccumulationUnsupported,
+
+    #[error("the order of the source fields can't be kept in snapshot sessions")]
+    SourceFieldOrderInSnapshot,
+
+    #[error("column {0} is selected more than once")]
+    FieldSelectedTwice(String),
+
+    #[error("column {0} sets what fills it when missing, but it is not optional")]
+    MissingBehaviorNotOptional(String),
+
+    #[error("column {0} replaces errors with None, but it is not optional")]
+    NullErrorPlaceholderNotOptional(String),
+
+    #[error("column {0} replaces errors with its default, but it has no default")]
+    DefaultErrorPlaceholderMissing(String),
+
+    #[error("default value {default} of column {name} violates its constraints")]
+    DefaultViolatesConstraints { name: String, default: Value },
+
+    #[error("schema for discriminator {discriminator:?} has {width} columns, expected {expected}")]
+    MultiSchemaWidthMismatch {
+        discriminator: String,
+        width: usize,
+        expected: usize,
+    },
+
     #[error("input synchronization failed: {0}")]
     InputSynchronization(#[from] InputSynchronizationError),
 }
@@ -9,8 +9,9 @@ pub use self::error::{DataError, Error, Result};
 
 pub mod report_error;
 
+pub mod coerce;
 pub mod value;
-pub use self::value::{Key, KeyImpl, ShardPolicy, Type, Value};
+pub use self::value::{FixedStringAlign, Key, KeyImpl, ShardPolicy, Type, Value};
 
 pub mod reduce;
 pub use reduce::Reducer;
@@ -271,6 +271,20 @@ impl DateTimeUtc {
         }
     }
 
+    pub fn from_rfc3339(date_string: &str) -> DataResult<Self> {
+        let datetime = chrono::DateTime::parse_from_rfc3339(date_string).map_err(|e| {
+            DataError::ParseError(format!(
+                "cannot parse date {date_string:?} as RFC 3339: {e}"
+            ))
+        })?;
+        let timestamp = datetime.timestamp_nanos_opt().ok_or_else(|| {
+            DataError::ParseError(format!(
+                "date {date_string:?} is out of the supported range"
+            ))
+        })?;
+        Ok(Self::new(timestamp))
+    }
+
     pub fn to_naive_in_timezone(&self, timezone: &str) -> DataResult<DateTimeNaive> {
         let naive_utc = self.as_chrono_datetime();
         to_naive_in_timezone::<FixedOffset>(naive_utc, timezone)
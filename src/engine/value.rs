@@ -2,29 +2,39 @@
 
 #![allow(clippy::non_canonical_partial_ord_impl)] // False positive with Derivative
 
+use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Display};
 use std::mem::{align_of, size_of};
 use std::ops::Deref;
 use std::sync::Arc;
 
+use super::coerce::{coerce_to_type, value_fits_type, CoercionOptions, Error as CoerceError};
 use super::error::{DataError, DynError, DynResult};
 use super::time::{DateTime, DateTimeNaive, DateTimeUtc, Duration};
 use super::PyObjectWrapper;
 
 use arcstr::ArcStr;
 use cfg_if::cfg_if;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
 use derivative::Derivative;
 use itertools::Itertools as _;
 use ndarray::ArrayD;
 use ordered_float::OrderedFloat;
 use rand::Rng;
+use rust_decimal::Decimal;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
+use uuid::Uuid;
 use xxhash_rust::xxh3::Xxh3 as Hasher;
 
 pub const BASE32_ALPHABET: base32::Alphabet = base32::Alphabet::Crockford;
 
+/// The version of [`Value::stable_hash`]. The hashes stay the same as long as
+/// the version does, it is increased whenever the hash of any value changes.
+pub const STABLE_HASH_VERSION: u32 = 1;
+const STABLE_HASH_SEED: u64 = 0x7061_7468_7761_7921;
+
 cfg_if! {
     if #[cfg(feature="yolo-id32")] {
         pub type KeyImpl = u32;
@@ -226,6 +236,12 @@ pub enum Value {
     Error,
     PyObjectWrapper(Handle<PyObjectWrapper>),
     Pending,
+    Decimal(Decimal),
+    Uuid(Uuid),
+    Map(Arc<BTreeMap<ArcStr, Self>>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Int128(i128),
 }
 
 const _: () = assert!(align_of::<Value>() <= 16);
@@ -336,6 +352,14 @@ impl Value {
         }
     }
 
+    pub fn as_decimal(&self) -> DynResult<Decimal> {
+        if let Self::Decimal(d) = self {
+            Ok(*d)
+        } else {
+            Err(self.type_mismatch("Decimal"))
+        }
+    }
+
     pub fn into_result(self) -> DynResult<Self> {
         match self {
             Self::Error => Err(DataError::ErrorInValue.into()),
@@ -364,6 +388,72 @@ impl Display for Value {
             Self::Error => write!(fmt, "Error"),
             Self::PyObjectWrapper(ob) => write!(fmt, "{ob}"),
             Self::Pending => write!(fmt, "Pending"),
+            Self::Decimal(d) => write!(fmt, "{d}"),
+            Self::Uuid(u) => write!(fmt, "{u}"),
+            Self::Map(entries) => write!(
+                fmt,
+                "{{{}}}",
+                entries
+                    .iter()
+                    .format_with(", ", |(key, value), f| f(&format_args!("{key:?}: {value}")))
+            ),
+            Self::Date(d) => write!(fmt, "{d}"),
+            Self::Time(t) => write!(fmt, "{t}"),
+            Self::Int128(i) => write!(fmt, "{i}"),
+        }
+    }
+}
+
+fn json_size_bytes(json: &JsonValue) -> usize {
+    let heap = match json {
+        JsonValue::String(s) => s.len(),
+        JsonValue::Array(values) => values.iter().map(json_size_bytes).sum(),
+        JsonValue::Object(entries) => entries
+            .iter()
+            .map(|(key, value)| size_of::<String>() + key.len() + json_size_bytes(value))
+            .sum(),
+        JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => 0,
+    };
+    size_of::<JsonValue>() + heap
+}
+
+struct Annotated<'a>(&'a Value);
+
+impl Display for Annotated<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::None => write!(fmt, "None"),
+            Value::Bool(b) => write!(fmt, "Bool({b})"),
+            Value::Int(i) => write!(fmt, "Int({i})"),
+            Value::Float(OrderedFloat(f)) => write!(fmt, "Float({f:?})"),
+            Value::Pointer(p) => write!(fmt, "Pointer({p})"),
+            Value::String(s) => write!(fmt, "Str({s:?})"),
+            Value::Bytes(b) => write!(fmt, "Bytes({b:?})"),
+            Value::Tuple(vals) => write!(fmt, "Tuple[{}]", vals.iter().map(Annotated).format(", ")),
+            Value::IntArray(array) => write!(fmt, "IntArray({array})"),
+            Value::FloatArray(array) => write!(fmt, "FloatArray({array})"),
+            Value::DateTimeNaive(date_time) => write!(fmt, "DateTimeNaive({date_time})"),
+            Value::DateTimeUtc(date_time) => write!(fmt, "DateTimeUtc({date_time})"),
+            Value::Duration(duration) => write!(fmt, "Duration({duration})"),
+            Value::Json(json) => write!(fmt, "Json({json})"),
+            Value::Error => write!(fmt, "Err"),
+            Value::PyObjectWrapper(ob) => write!(fmt, "PyObject({ob})"),
+            Value::Pending => write!(fmt, "Pending"),
+            Value::Decimal(d) => write!(fmt, "Decimal({d})"),
+            Value::Uuid(u) => write!(fmt, "Uuid({u})"),
+            Value::Map(entries) => write!(
+                fmt,
+                "Map{{{}}}",
+                entries
+                    .iter()
+                    .format_with(", ", |(key, value), f| f(&format_args!(
+                        "{key:?}: {}",
+                        Annotated(value)
+                    )))
+            ),
+            Value::Date(d) => write!(fmt, "Date({d})"),
+            Value::Time(t) => write!(fmt, "Time({t})"),
+            Value::Int128(i) => write!(fmt, "Int128({i})"),
         }
     }
 }
@@ -470,6 +560,36 @@ impl From<Duration> for Value {
     }
 }
 
+impl From<NaiveDate> for Value {
+    fn from(d: NaiveDate) -> Self {
+        Self::Date(d)
+    }
+}
+
+impl From<NaiveTime> for Value {
+    fn from(t: NaiveTime) -> Self {
+        Self::Time(t)
+    }
+}
+
+impl From<BTreeMap<ArcStr, Value>> for Value {
+    fn from(entries: BTreeMap<ArcStr, Value>) -> Self {
+        Self::Map(Arc::new(entries))
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(u: Uuid) -> Self {
+        Self::Uuid(u)
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(d: Decimal) -> Self {
+        Self::Decimal(d)
+    }
+}
+
 impl From<JsonValue> for Value {
     fn from(json: JsonValue) -> Self {
         Self::Json(Handle::new(json))
@@ -504,6 +624,20 @@ pub enum Kind {
     Error,
     PyObjectWrapper,
     Pending,
+    Decimal,
+    Uuid,
+    Map,
+    Date,
+    Time,
+    Int128,
+}
+
+/// The side of a `Type::FixedString` field its content is aligned to, the
+/// other side is filled with the padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedStringAlign {
+    Left,
+    Right,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -518,10 +652,30 @@ pub enum Type {
     DateTimeNaive,
     DateTimeUtc,
     Duration,
+    Decimal,
+    Uuid,
+    Date,
+    Time,
+    Int128,
+    // Stored as `Value::Int`, limited to the 32-bit range
+    Int32,
+    // Stored as `Value::String` with the padding removed
+    FixedString {
+        len: usize,
+        pad: char,
+        align: FixedStringAlign,
+    },
+    Enum(Arc<[String]>),
     Array(Option<usize>, Arc<Type>),
+    // Stored as `Value::Tuple` with exactly `len` elements
+    FixedArray {
+        element: Arc<Type>,
+        len: usize,
+    },
     Json,
     Tuple(Arc<[Type]>),
     List(Arc<Type>),
+    Map(Arc<Type>),
     PyObjectWrapper,
     Optional(Arc<Type>),
     Future(Arc<Type>),
@@ -542,6 +696,16 @@ impl Type {
     pub fn is_optional(&self) -> bool {
         matches!(self, Self::Optional(_))
     }
+
+    /// Collapses directly nested optionals, so that `Optional(Optional(T))`
+    /// becomes `Optional(T)`.
+    #[must_use]
+    pub fn flatten_optional(self) -> Self {
+        match self {
+            Self::Optional(arg) if arg.is_optional() => arg.as_ref().clone().flatten_optional(),
+            type_ => type_,
+        }
+    }
 }
 
 impl Display for Type {
@@ -557,6 +721,14 @@ impl Display for Type {
             Type::DateTimeNaive => write!(f, "DateTimeNaive"),
             Type::DateTimeUtc => write!(f, "DateTimeUtc"),
             Type::Duration => write!(f, "Duration"),
+            Type::Decimal => write!(f, "Decimal"),
+            Type::Uuid => write!(f, "UUID"),
+            Type::Date => write!(f, "Date"),
+            Type::Time => write!(f, "Time"),
+            Type::Int128 => write!(f, "int128"),
+            Type::Int32 => write!(f, "int32"),
+            Type::FixedString { len, .. } => write!(f, "FixedString({len})"),
+            Type::Enum(variants) => write!(f, "Enum({})", variants.iter().format(", ")),
             Type::Array(dim, arg) => {
                 if let Some(dim) = dim {
                     write!(f, "Array({dim}, {arg})")
@@ -564,9 +736,11 @@ impl Display for Type {
                     write!(f, "Array({arg})")
                 }
             }
+            Type::FixedArray { element, len } => write!(f, "FixedArray({element}, {len})"),
             Type::Json => write!(f, "Json"),
             Type::Tuple(args) => write!(f, "tuple[{}]", args.iter().format(", ")),
             Type::List(arg) => write!(f, "list[{arg}]"),
+            Type::Map(arg) => write!(f, "dict[str, {arg}]"),
             Type::PyObjectWrapper => write!(f, "PyObjectWrapper"),
             Type::Optional(arg) => write!(f, "{arg} | None"),
             Type::Future(arg) => write!(f, "Future[{arg}]"),
@@ -595,10 +769,114 @@ impl Value {
             Self::Error => Kind::Error,
             Self::PyObjectWrapper(_) => Kind::PyObjectWrapper,
             Self::Pending => Kind::Pending,
+            Self::Decimal(_) => Kind::Decimal,
+            Self::Uuid(_) => Kind::Uuid,
+            Self::Map(_) => Kind::Map,
+            Self::Date(_) => Kind::Date,
+            Self::Time(_) => Kind::Time,
+            Self::Int128(_) => Kind::Int128,
+        }
+    }
+
+    /// A 64-bit hash of the value, the same across runs and platforms for a given
+    /// [`STABLE_HASH_VERSION`], e.g. for partitioning the rows by their keys.
+    /// Composite values are hashed element by element. Panics on the values
+    /// that can't be a key, like `Value::Error`.
+    #[must_use]
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = Hasher::with_seed(STABLE_HASH_SEED);
+        self.hash_into(&mut hasher);
+        hasher.digest()
+    }
+
+    /// An estimate of the memory taken by the value, both inline and on the
+    /// heap, for memory accounting. It grows with the lengths of strings and
+    /// bytes and with the numbers of elements. An allocation shared by several
+    /// values is counted for each of them.
+    #[must_use]
+    pub fn size_bytes(&self) -> usize {
+        let heap = match self {
+            Self::String(s) => s.len(),
+            Self::Bytes(b) => b.len(),
+            Self::Tuple(vals) => vals.iter().map(Self::size_bytes).sum(),
+            Self::IntArray(array) => array.len() * size_of::<i64>(),
+            Self::FloatArray(array) => array.len() * size_of::<f64>(),
+            Self::Json(json) => json_size_bytes(json),
+            Self::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| size_of::<ArcStr>() + key.len() + value.size_bytes())
+                .sum(),
+            Self::None
+            | Self::Bool(_)
+            | Self::Int(_)
+            | Self::Float(_)
+            | Self::Pointer(_)
+            | Self::DateTimeNaive(_)
+            | Self::DateTimeUtc(_)
+            | Self::Duration(_)
+            | Self::Error
+            | Self::PyObjectWrapper(_)
+            | Self::Pending
+            | Self::Decimal(_)
+            | Self::Uuid(_)
+            | Self::Date(_)
+            | Self::Time(_)
+            | Self::Int128(_) => 0,
+        };
+        size_of::<Self>() + heap
+    }
+
+    /// Renders the value with its variant, e.g. `Tuple[Int(1), Err]` or
+    /// `Str("abc")`, for telling apart the values that print alike in the
+    /// messages of failed tests. The format may change and should not be
+    /// parsed.
+    #[must_use]
+    pub fn display_annotated(&self) -> String {
+        Annotated(self).to_string()
+    }
+
+    /// Converts the value to `target` with the rules of the parsers, such as
+    /// reading numbers, booleans and dates from strings or widening `Int` to
+    /// `Float`.
+    pub fn try_coerce(&self, target: &Type) -> Result<Value, CoercionError> {
+        self.try_coerce_with(target, &CoercionOptions::lenient())
+    }
+
+    /// Like [`Value::try_coerce`], with only the conversions enabled in `options`
+    /// applied to strings.
+    pub fn try_coerce_with(
+        &self,
+        target: &Type,
+        options: &CoercionOptions,
+    ) -> Result<Value, CoercionError> {
+        let error = |reason| CoercionError {
+            from: self.kind(),
+            to: target.clone(),
+            reason,
+        };
+        let coerced = coerce_to_type(self, target, options).map_err(error)?;
+        if !value_fits_type(&coerced, target) {
+            return Err(error(
+                CoerceError::UnexpectedValueType {
+                    expected: target.clone(),
+                    got: format!("{:?}", coerced.kind()),
+                }
+                .into(),
+            ));
         }
+        Ok(coerced)
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("cannot coerce a value of kind {from:?} to {to}: {reason}")]
+pub struct CoercionError {
+    pub from: Kind,
+    pub to: Type,
+    #[source]
+    pub reason: DynError,
+}
+
 pub trait HashInto {
     fn hash_into(&self, hasher: &mut Hasher);
 }
@@ -719,6 +997,13 @@ impl HashInto for Duration {
     }
 }
 
+impl HashInto for Decimal {
+    fn hash_into(&self, hasher: &mut Hasher) {
+        // equal decimals may differ in scale, e.g. 1.0 and 1.00
+        hasher.update(&self.normalize().serialize());
+    }
+}
+
 impl HashInto for Value {
     fn hash_into(&self, hasher: &mut Hasher) {
         (self.kind() as u8).hash_into(hasher);
@@ -740,6 +1025,21 @@ impl HashInto for Value {
             Self::Error => panic!("trying to hash error"), // FIXME
             Self::PyObjectWrapper(ob) => ob.hash_into(hasher),
             Self::Pending => panic!("trying to hash pending"), // FIXME
+            Self::Decimal(d) => d.hash_into(hasher),
+            Self::Uuid(u) => u.as_u128().hash_into(hasher),
+            Self::Map(entries) => {
+                entries.len().hash_into(hasher);
+                for (key, value) in entries.iter() {
+                    key.hash_into(hasher);
+                    value.hash_into(hasher);
+                }
+            }
+            Self::Date(d) => d.num_days_from_ce().hash_into(hasher),
+            Self::Time(t) => {
+                t.num_seconds_from_midnight().hash_into(hasher);
+                t.nanosecond().hash_into(hasher);
+            }
+            Self::Int128(i) => i.hash_into(hasher),
         }
     }
 }
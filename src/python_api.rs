@@ -15,9 +15,11 @@ use crate::engine::{
 };
 use crate::persistence::frontier::OffsetAntichain;
 
+use arcstr::ArcStr;
 use async_nats::connect as nats_connect;
 use async_nats::Client as NatsClient;
 use async_nats::Subscriber as NatsSubscriber;
+use chrono::{NaiveDate, NaiveTime, Timelike};
 use csv::ReaderBuilder as CsvReaderBuilder;
 use elasticsearch::{
     auth::Credentials as ESCredentials,
@@ -49,6 +51,7 @@ use rdkafka::producer::{DefaultProducerContext, ThreadedProducer};
 use rdkafka::{ClientConfig, Offset as KafkaOffset, TopicPartitionList};
 use rusqlite::Connection as SqliteConnection;
 use rusqlite::OpenFlags as SqliteOpenFlags;
+use rust_decimal::Decimal;
 use s3::bucket::Bucket as S3Bucket;
 use scopeguard::defer;
 use send_wrapper::SendWrapper;
@@ -56,7 +59,7 @@ use serde_json::Value as JsonValue;
 use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufWriter, Read};
 use std::mem::take;
@@ -64,6 +67,7 @@ use std::os::unix::prelude::*;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time;
+use uuid::Uuid;
 
 use self::external_index_wrappers::{
     PyBruteForceKnnMetricKind, PyExternalIndexData, PyExternalIndexQuery, PyUSearchMetricKind,
@@ -333,6 +337,46 @@ fn py_type_error(ob: &Bound<PyAny>, type_: &Type) -> PyErr {
     ))
 }
 
+fn extract_uuid(ob: &Bound<PyAny>) -> Option<Value> {
+    let uuid = ob.str().ok()?.to_str().ok()?.parse::<Uuid>().ok()?;
+    Some(Value::from(uuid))
+}
+
+fn extract_date(ob: &Bound<PyAny>) -> Option<Value> {
+    // XXX: check types, not names
+    if ob.get_type().qualname().ok()? != "date" {
+        return None;
+    }
+    let date = ob.call_method0(intern!(ob.py(), "isoformat")).ok()?;
+    let date = date.extract::<String>().ok()?.parse::<NaiveDate>().ok()?;
+    Some(Value::from(date))
+}
+
+fn extract_time(ob: &Bound<PyAny>) -> Option<Value> {
+    // XXX: check types, not names
+    if ob.get_type().qualname().ok()? != "time" {
+        return None;
+    }
+    let component = |name| ob.getattr(name).ok()?.extract::<u32>().ok();
+    let time = NaiveTime::from_hms_micro_opt(
+        component(intern!(ob.py(), "hour"))?,
+        component(intern!(ob.py(), "minute"))?,
+        component(intern!(ob.py(), "second"))?,
+        component(intern!(ob.py(), "microsecond"))?,
+    )?;
+    Some(Value::from(time))
+}
+
+fn extract_map(ob: &Bound<PyAny>, arg: &Type) -> PyResult<Value> {
+    let entries: BTreeMap<_, _> = ob
+        .extract::<HashMap<String, Bound<PyAny>>>()?
+        .into_iter()
+        .map(|(key, ob)| Ok::<_, PyErr>((ArcStr::from(key), extract_value(&ob, arg)?)))
+        .try_collect()?;
+    Ok(Value::from(entries))
+}
+
+#[allow(clippy::too_many_lines)]
 pub fn extract_value(ob: &Bound<PyAny>, type_: &Type) -> PyResult<Value> {
     if ob.is_instance_of::<Error>() {
         return Ok(Value::Error);
@@ -351,9 +395,11 @@ pub fn extract_value(ob: &Bound<PyAny>, type_: &Type) -> PyResult<Value> {
             .ok()
             .map(|b| Value::from(b.is_true())),
         Type::Int => ob.extract::<i64>().ok().map(Value::from),
+        Type::Int32 => ob.extract::<i32>().ok().map(|i| Value::from(i64::from(i))),
+        Type::Int128 => ob.extract::<i128>().ok().map(Value::Int128),
         Type::Float => ob.extract::<f64>().ok().map(Value::from),
         Type::Pointer => ob.extract::<Key>().ok().map(Value::from),
-        Type::String => ob
+        Type::String | Type::FixedString { .. } | Type::Enum(_) => ob
             .downcast::<PyString>()
             .ok()
             .and_then(|s| s.to_str().ok())
@@ -363,6 +409,10 @@ pub fn extract_value(ob: &Bound<PyAny>, type_: &Type) -> PyResult<Value> {
             .ok()
             .map(|b| Value::from(b.as_bytes())),
         Type::DateTimeNaive | Type::DateTimeUtc => extract_datetime(ob, type_),
+        Type::Decimal => ob.extract::<Decimal>().ok().map(Value::from),
+        Type::Uuid => extract_uuid(ob),
+        Type::Date => extract_date(ob),
+        Type::Time => extract_time(ob),
         Type::Duration => {
             // XXX: check types, not names
             let type_name = ob.get_type().qualname()?;
@@ -412,6 +462,19 @@ pub fn extract_value(ob: &Bound<PyAny>, type_: &Type) -> PyResult<Value> {
                 .try_collect()?;
             Some(Value::from(values.as_slice()))
         }
+        Type::FixedArray { element, len } => {
+            let obs = ob.extract::<Vec<Bound<PyAny>>>()?;
+            if obs.len() == *len {
+                let values: Vec<_> = obs
+                    .into_iter()
+                    .map(|ob| extract_value(&ob, element))
+                    .try_collect()?;
+                Some(Value::from(values.as_slice()))
+            } else {
+                None
+            }
+        }
+        Type::Map(arg) => Some(extract_map(ob, arg)?),
         Type::PyObjectWrapper => {
             let value = if let Ok(ob) = ob.extract::<PyObjectWrapper>() {
                 ob
@@ -523,6 +586,36 @@ impl<'py> FromPyObject<'py> for Value {
     }
 }
 
+fn uuid_to_py_object(py: Python<'_>, uuid: Uuid) -> PyObject {
+    py.import_bound(intern!(py, "uuid"))
+        .and_then(|module| module.call_method1(intern!(py, "UUID"), (uuid.to_string(),)))
+        .unwrap()
+        .into_py(py)
+}
+
+fn date_to_py_object(py: Python<'_>, date: NaiveDate) -> PyObject {
+    py.import_bound(intern!(py, "datetime"))
+        .and_then(|module| module.getattr(intern!(py, "date")))
+        .and_then(|date_type| {
+            date_type.call_method1(intern!(py, "fromisoformat"), (date.to_string(),))
+        })
+        .unwrap()
+        .into_py(py)
+}
+
+fn time_to_py_object(py: Python<'_>, time: NaiveTime) -> PyObject {
+    let components = (
+        time.hour(),
+        time.minute(),
+        time.second(),
+        time.nanosecond() / 1_000,
+    );
+    py.import_bound(intern!(py, "datetime"))
+        .and_then(|module| module.call_method1(intern!(py, "time"), components))
+        .unwrap()
+        .into_py(py)
+}
+
 fn json_to_py_object(py: Python<'_>, json: &JsonValue) -> PyObject {
     get_convert_python_module(py)
         .call_method1(intern!(py, "_parse_to_json"), (json.to_string(),))
@@ -550,6 +643,19 @@ impl ToPyObject for Value {
             Self::Error => ERROR.clone_ref(py).into_py(py),
             Self::PyObjectWrapper(op) => PyObjectWrapper::from_internal(py, op).into_py(py),
             Self::Pending => PENDING.clone_ref(py).into_py(py),
+            Self::Decimal(d) => d.into_py(py),
+            Self::Uuid(u) => uuid_to_py_object(py, *u),
+            Self::Map(entries) => {
+                let dict = PyDict::new_bound(py);
+                for (key, value) in entries.iter() {
+                    dict.set_item(key.as_str(), value)
+                        .expect("inserting into a new dict should not fail");
+                }
+                dict.unbind().into_any()
+            }
+            Self::Date(d) => date_to_py_object(py, *d),
+            Self::Time(t) => time_to_py_object(py, *t),
+            Self::Int128(i) => i.into_py(py),
         }
     }
 }
@@ -1715,6 +1821,18 @@ impl PathwayType {
     pub const DATE_TIME_UTC: Type = Type::DateTimeUtc;
     #[classattr]
     pub const DURATION: Type = Type::Duration;
+    #[classattr]
+    pub const DECIMAL: Type = Type::Decimal;
+    #[classattr]
+    pub const UUID: Type = Type::Uuid;
+    #[classattr]
+    pub const DATE: Type = Type::Date;
+    #[classattr]
+    pub const TIME: Type = Type::Time;
+    #[classattr]
+    pub const INT32: Type = Type::Int32;
+    #[classattr]
+    pub const INT128: Type = Type::Int128;
     #[staticmethod]
     #[pyo3(signature = (dim, wrapped))]
     pub fn array(dim: Option<usize>, wrapped: Type) -> Type {
@@ -1731,6 +1849,10 @@ impl PathwayType {
     pub fn list(arg: Type) -> Type {
         Type::List(arg.into())
     }
+    #[staticmethod]
+    pub fn map(arg: Type) -> Type {
+        Type::Map(arg.into())
+    }
     #[classattr]
     pub const BYTES: Type = Type::Bytes;
     #[classattr]
@@ -1780,6 +1902,8 @@ impl PySessionType {
     pub const NATIVE: SessionType = SessionType::Native;
     #[classattr]
     pub const UPSERT: SessionType = SessionType::Upsert;
+    #[classattr]
+    pub const SNAPSHOT: SessionType = SessionType::Snapshot;
 }
 
 #[pyclass(module = "pathway.engine", frozen, name = "PythonConnectorEventType")]
@@ -4491,7 +4615,10 @@ impl DataFormat {
     }
 
     fn is_native_session_used(&self) -> bool {
-        matches!(self.session_type, SessionType::Native)
+        matches!(
+            self.session_type,
+            SessionType::Native | SessionType::Snapshot
+        )
     }
 }
 
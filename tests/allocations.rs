@@ -0,0 +1,73 @@
+// Copyright © 2024 Pathway
+
+//! Counts the bytes allocated while parsing. The library installs its own
+//! allocator by default, so run with
+//! `cargo test --test allocations --features standard-allocator`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use arcstr::ArcStr;
+use pathway_engine::connectors::data_format::{InnerSchemaField, Parser, TransparentParser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::connectors::SessionType;
+use pathway_engine::engine::{Type, Value};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.set(ALLOCATED.get() + layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL_ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// The bytes allocated on the current thread by `f`
+fn allocated_by<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATED.get();
+    drop(f());
+    ALLOCATED.get() - before
+}
+
+fn allocated_by_parse(text: &ArcStr) -> usize {
+    let schema = [
+        ("text".to_owned(), InnerSchemaField::new(Type::String, None)),
+        (
+            "maybe_text".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["text".to_owned(), "maybe_text".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )
+    .expect("the schema should be valid");
+    let values = HashMap::from([
+        ("text".to_owned(), Ok(Value::from(text.clone()))),
+        ("maybe_text".to_owned(), Ok(Value::from(text.clone()))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    allocated_by(|| parser.parse(&context).expect("parsing should not fail"))
+}
+
+#[test]
+fn test_transparent_parser_does_not_copy_strings() {
+    let short = ArcStr::from("a".repeat(16));
+    let long = ArcStr::from("a".repeat(1024));
+    assert!(allocated_by(|| Value::from(long.as_str())) >= 1024);
+    assert_eq!(allocated_by_parse(&long), allocated_by_parse(&short));
+}
@@ -141,12 +141,18 @@ pub fn full_cycle_read(
                             let values = value_from_fields_with_errors(values);
                             ParsedEvent::Delete((key, values))
                         }
+                        ParsedEventWithErrors::Upsert((key, values)) => {
+                            let key = key_from_fields_with_errors(key);
+                            let values = value_from_fields_with_errors(values);
+                            ParsedEvent::Upsert((key, values))
+                        }
                     };
                     if let Some(ref mut snapshot_writer) = snapshot_writer {
                         let snapshot_event = match event {
                             // Random key generation is used only for testing purposes and
                             // doesn't reflect the logic used in pathway applications.
-                            ParsedEvent::Insert((_, ref values)) => {
+                            ParsedEvent::Insert((_, ref values))
+                            | ParsedEvent::Upsert((_, ref values)) => {
                                 let key = Key::random();
                                 SnapshotEvent::Insert(key, values.clone())
                             }
@@ -293,7 +299,8 @@ impl ErrorPlacement {
                 .into_iter()
                 .map(|result| match result {
                     ParsedEventWithErrors::Insert((key, _))
-                    | ParsedEventWithErrors::Delete((key, _)) => key
+                    | ParsedEventWithErrors::Delete((key, _))
+                    | ParsedEventWithErrors::Upsert((key, _)) => key
                         .expect("key has to be Some to contain error")
                         .expect_err("error should be in the key but it is not present there"),
                     ParsedEventWithErrors::AdvanceTime => {
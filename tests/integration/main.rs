@@ -4,11 +4,15 @@ mod helpers;
 mod operator_test_utils;
 
 mod test_arrow;
+mod test_arrow_ipc;
+mod test_avro;
 mod test_bson;
 mod test_bytes;
 mod test_cached_object_storage;
+mod test_cbor;
 mod test_connector_field_defaults;
 mod test_connector_sync;
+mod test_csv;
 mod test_dd_distinct_total;
 mod test_debezium;
 mod test_deltalake;
@@ -16,15 +20,19 @@ mod test_dsv;
 mod test_dsv_dir;
 mod test_dsv_output;
 mod test_file_kv;
+mod test_fixed_width;
 mod test_json_output;
 mod test_jsonlines;
 mod test_metadata;
+mod test_msgpack;
 mod test_null_writer;
 mod test_offsets_storage;
 mod test_operator_persistence;
+mod test_parquet;
 mod test_parser;
 mod test_parser_errors;
 mod test_prev_next;
+mod test_protobuf;
 mod test_psql_output;
 mod test_psql_snapshot;
 mod test_seek;
@@ -32,5 +40,8 @@ mod test_sqlite;
 mod test_stream_snapshot;
 mod test_time;
 mod test_time_column;
+mod test_toml;
 mod test_types;
 mod test_value_to_sql;
+mod test_xml;
+mod test_yaml;
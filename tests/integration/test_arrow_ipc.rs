@@ -0,0 +1,86 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use std::sync::Arc;
+
+use deltalake::arrow::array::{Int64Array, RecordBatch as ArrowRecordBatch, StringArray};
+use deltalake::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use deltalake::arrow::ipc::writer::StreamWriter;
+
+use pathway_engine::connectors::data_format::{
+    ArrowIpcParser, InnerSchemaField, ParsedEvent, Parser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn write_ipc_stream(batch: &ArrowRecordBatch) -> eyre::Result<Vec<u8>> {
+    let mut stream = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut stream, &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    drop(writer);
+    Ok(stream)
+}
+
+#[test]
+fn test_arrow_ipc_stream() -> eyre::Result<()> {
+    let arrow_schema = ArrowSchema::new(vec![
+        Field::new("id", ArrowDataType::Int64, false),
+        Field::new("name", ArrowDataType::Utf8, true),
+    ]);
+    let batch = ArrowRecordBatch::try_new(
+        Arc::new(arrow_schema),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec![Some("one"), None, Some("three")])),
+        ],
+    )?;
+
+    let schema = [
+        ("id".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+        (
+            "source".to_string(),
+            InnerSchemaField::new(Type::String, Some(Value::from("ipc"))),
+        ),
+    ];
+    let mut parser = ArrowIpcParser::new(
+        vec!["name".to_string(), "id".to_string(), "source".to_string()],
+        schema.into(),
+    )?;
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            write_ipc_stream(&batch)?,
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("one"), Value::Int(1), Value::from("ipc")]
+            )),
+            ParsedEvent::Insert((None, vec![Value::None, Value::Int(2), Value::from("ipc")])),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("three"), Value::Int(3), Value::from("ipc")]
+            )),
+        ]
+    );
+
+    assert!(parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            b"not an arrow stream".to_vec(),
+        ))
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use apache_avro::types::Record;
+use apache_avro::Schema as AvroSchema;
+use itertools::Itertools;
+
+use pathway_engine::connectors::data_format::{AvroParser, InnerSchemaField, ParsedEvent, Parser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+const WRITER_SCHEMA: &str = r#"
+{
+    "type": "record",
+    "name": "test",
+    "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "name", "type": ["null", "string"]},
+        {"name": "ignored", "type": "string"}
+    ]
+}
+"#;
+
+fn create_parser() -> eyre::Result<AvroParser> {
+    let writer_schema = AvroSchema::parse_str(WRITER_SCHEMA)?;
+    let schema = [
+        ("id".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ];
+    Ok(AvroParser::new(
+        writer_schema,
+        vec!["id".to_string(), "name".to_string()],
+        schema.into(),
+    )?)
+}
+
+fn encode_record(name: Option<&str>) -> eyre::Result<Vec<u8>> {
+    let writer_schema = AvroSchema::parse_str(WRITER_SCHEMA)?;
+    let mut record = Record::new(&writer_schema).expect("schema must be a record");
+    record.put("id", 42_i64);
+    record.put("name", name.map(ToString::to_string));
+    record.put("ignored", "abc");
+    Ok(apache_avro::to_avro_datum(&writer_schema, record)?)
+}
+
+#[test]
+fn test_avro_record() -> eyre::Result<()> {
+    let mut parser = create_parser()?;
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, encode_record(Some("x"))?);
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    assert_eq!(
+        event,
+        ParsedEvent::Insert((None, vec![Value::Int(42), Value::from("x")]))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_avro_nullable_field() -> eyre::Result<()> {
+    let mut parser = create_parser()?;
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, encode_record(None)?);
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    assert_eq!(
+        event,
+        ParsedEvent::Insert((None, vec![Value::Int(42), Value::None]))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_avro_malformed_record() -> eyre::Result<()> {
+    let mut parser = create_parser()?;
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, vec![0xff]);
+    let event = parser
+        .parse(&context)
+        .expect("a malformed record should not fail the whole batch")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    assert_eq!(
+        event,
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error]))
+    );
+    Ok(())
+}
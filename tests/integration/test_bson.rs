@@ -6,7 +6,15 @@ use ndarray::{arr1, ArrayD};
 use ordered_float::OrderedFloat;
 use serde_json::json;
 
-use pathway_engine::connectors::data_format::{BsonFormatter, Formatter};
+use crate::helpers::ReplaceErrors;
+
+use mongodb::bson::{doc, oid::ObjectId};
+
+use pathway_engine::connectors::data_format::{
+    BsonFormatter, BsonObjectIdKey, BsonParser, Formatter, InnerSchemaField, ParsedEvent,
+    ParsedEventWithErrors, Parser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
 use pathway_engine::engine::time::DateTime;
 use pathway_engine::engine::{DateTimeNaive, DateTimeUtc, Duration, Key, Timestamp, Type, Value};
 
@@ -220,3 +228,57 @@ fn test_unsupported_type() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_bson_parser() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_string(),
+            InnerSchemaField::new(
+                Type::Optional(Type::String.into()),
+                Some(Value::from("default")),
+            ),
+        ),
+    ];
+    let mut parser = BsonParser::new(vec!["a".to_string(), "b".to_string()], schema.into())?
+        .with_object_id_key(BsonObjectIdKey::HexString);
+
+    let id = ObjectId::new();
+    let mut payload = Vec::new();
+    doc! { "_id": id, "a": 42_i32 }.to_writer(&mut payload)?;
+    doc! { "_id": ObjectId::new(), "a": 1_i64 << 40, "b": "x" }.to_writer(&mut payload)?;
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload,
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[0],
+        ParsedEvent::Insert((
+            Some(vec![Value::from(id.to_hex().as_str())]),
+            vec![Value::Int(42), Value::from("default")]
+        ))
+    );
+    let ParsedEvent::Insert((_, values)) = &events[1] else {
+        panic!("unexpected event: {:?}", events[1]);
+    };
+    assert_eq!(values, &vec![Value::Int(1 << 40), Value::from("x")]);
+
+    let malformed = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            vec![0xff, 0, 0, 0, 1],
+        ))
+        .expect("a malformed document should not fail the whole batch");
+    let [ParsedEventWithErrors::Insert((Some(Err(_)), values))] = malformed.as_slice() else {
+        panic!("unexpected events: {malformed:?}");
+    };
+    assert!(values.iter().all(Result::is_err));
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use ciborium::Value as CborValue;
+
+use pathway_engine::connectors::data_format::{CborParser, InnerSchemaField, ParsedEvent, Parser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn encode_record(entries: Vec<(&str, CborValue)>) -> eyre::Result<Vec<u8>> {
+    let record = CborValue::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (CborValue::Text(key.to_string()), value))
+            .collect(),
+    );
+    let mut encoded = Vec::new();
+    ciborium::ser::into_writer(&record, &mut encoded)?;
+    Ok(encoded)
+}
+
+#[test]
+fn test_cbor_records() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::String, None)),
+        ("c".to_string(), InnerSchemaField::new(Type::Bytes, None)),
+        (
+            "d".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::Float.into()), Some(Value::None)),
+        ),
+    ];
+    let mut parser = CborParser::new(
+        vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ],
+        schema.into(),
+    )?;
+
+    let mut payload = encode_record(vec![
+        ("a", CborValue::Integer(1.into())),
+        ("b", CborValue::Text("x".to_string())),
+        ("c", CborValue::Bytes(vec![0, 1])),
+        ("d", CborValue::Null),
+        ("ignored", CborValue::Bool(true)),
+    ])?;
+    payload.extend(encode_record(vec![
+        ("a", CborValue::Text("wrong type".to_string())),
+        ("b", CborValue::Text("y".to_string())),
+        ("c", CborValue::Bytes(vec![2])),
+    ])?);
+    let truncated = encode_record(vec![("a", CborValue::Integer(3.into()))])?;
+    payload.extend(&truncated[..truncated.len() - 1]);
+
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload,
+        ))
+        .expect("a truncated record should not fail the whole batch")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Int(1),
+                    Value::from("x"),
+                    Value::Bytes([0, 1].as_slice().into()),
+                    Value::None
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Error,
+                    Value::from("y"),
+                    Value::Bytes([2].as_slice().into()),
+                    Value::None
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::Error, Value::Error, Value::Error]
+            )),
+        ]
+    );
+    Ok(())
+}
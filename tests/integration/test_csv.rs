@@ -0,0 +1,132 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use std::collections::HashMap;
+
+use pathway_engine::connectors::data_format::{CsvParser, InnerSchemaField, ParsedEvent, Parser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn csv_schema() -> HashMap<String, InnerSchemaField> {
+    [
+        ("a".to_string(), InnerSchemaField::new(Type::String, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "c".to_string(),
+            InnerSchemaField::new(Type::String, Some(Value::from("default"))),
+        ),
+    ]
+    .into()
+}
+
+fn parse_payload(parser: &mut CsvParser, payload: &[u8]) -> Vec<ParsedEvent> {
+    parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload.to_vec(),
+        ))
+        .expect("payload should be parsed")
+        .into_iter()
+        .map(|entry| entry.replace_errors())
+        .collect()
+}
+
+#[test]
+fn test_csv_header_and_rows() -> eyre::Result<()> {
+    let mut parser = CsvParser::new(
+        b';',
+        true,
+        vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        csv_schema(),
+    )?;
+
+    let events = parse_payload(
+        &mut parser,
+        b"c;b;a\n\"x;\"\"y\"\"\";1;\"quoted; text\"\n\"short\";2\n",
+    );
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::from("quoted; text"),
+                    Value::Int(1),
+                    Value::from("x;\"y\""),
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::Int(2), Value::from("short")]
+            )),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_csv_without_header() -> eyre::Result<()> {
+    let mut parser = CsvParser::new(
+        b',',
+        false,
+        vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        csv_schema(),
+    )?;
+
+    let events = parse_payload(&mut parser, b"x,1\ny,2,z\nw,3,v,extra\n");
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("x"), Value::Int(1), Value::from("default")]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("y"), Value::Int(2), Value::from("z")]
+            )),
+            ParsedEvent::Insert((None, vec![Value::Error, Value::Error, Value::Error])),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_csv_quoted_newlines() -> eyre::Result<()> {
+    let mut parser = CsvParser::new(
+        b',',
+        false,
+        vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        csv_schema(),
+    )?;
+
+    let events = parse_payload(
+        &mut parser,
+        b"\"first line\nsecond \"\"line\"\"\",1,x\ny,2,z\n",
+    );
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::from("first line\nsecond \"line\""),
+                    Value::Int(1),
+                    Value::from("x")
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("y"), Value::Int(2), Value::from("z")]
+            )),
+        ]
+    );
+
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, b"x,1,\"open\ny,2".to_vec());
+    assert!(parser.parse(&context).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use std::collections::HashMap;
+
+use pathway_engine::connectors::data_format::{
+    FieldBeyondLine, FixedWidthParser, InnerSchemaField, ParsedEvent, Parser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn new_parser() -> eyre::Result<FixedWidthParser> {
+    let schema = [
+        ("id".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "city".to_string(),
+            InnerSchemaField::new(Type::String, Some(Value::from("unknown"))),
+        ),
+    ];
+    let ranges = [
+        ("id".to_string(), (0, 5)),
+        ("name".to_string(), (5, 10)),
+        ("city".to_string(), (15, 8)),
+    ];
+    Ok(FixedWidthParser::new(
+        vec!["id".to_string(), "name".to_string(), "city".to_string()],
+        ranges.into(),
+        schema.into(),
+    )?)
+}
+
+fn parse_payload(parser: &mut FixedWidthParser, payload: &[u8]) -> Vec<ParsedEvent> {
+    parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload.to_vec(),
+        ))
+        .expect("payload should be parsed")
+        .into_iter()
+        .map(|entry| entry.replace_errors())
+        .collect()
+}
+
+#[test]
+fn test_fixed_width_records() -> eyre::Result<()> {
+    let mut parser = new_parser()?;
+    let events = parse_payload(&mut parser, b"00042Alice     Paris   \r\n   7 Bob\n");
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Int(42), Value::from("Alice"), Value::from("Paris")]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Int(7), Value::from("Bob"), Value::from("unknown")]
+            )),
+        ]
+    );
+
+    let mut parser = new_parser()?.with_field_beyond_line(FieldBeyondLine::Error);
+    let events = parse_payload(&mut parser, b"   7 Bob");
+    assert_eq!(
+        events,
+        vec![ParsedEvent::Insert((
+            None,
+            vec![Value::Int(7), Value::from("Bob"), Value::Error]
+        ))]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fixed_width_overlapping_ranges() -> eyre::Result<()> {
+    let schema = [
+        (
+            "date".to_string(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        ("year".to_string(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let ranges = [("date".to_string(), (0, 8)), ("year".to_string(), (0, 4))];
+    let mut parser = FixedWidthParser::new(
+        vec!["date".to_string(), "year".to_string()],
+        ranges.into(),
+        schema.into(),
+    )?;
+    let events = parse_payload(&mut parser, b"20240315");
+    assert_eq!(
+        events,
+        vec![ParsedEvent::Insert((
+            None,
+            vec![Value::from("20240315"), Value::Int(2024)]
+        ))]
+    );
+
+    let schema = [("a".to_string(), InnerSchemaField::new(Type::Int, None))];
+    assert!(FixedWidthParser::new(vec!["a".to_string()], HashMap::new(), schema.into()).is_err());
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 // Copyright © 2024 Pathway
 
-use crate::helpers::ErrorPlacement;
+use crate::helpers::{ErrorPlacement, ReplaceErrors};
 
 use super::helpers::{assert_error_shown, read_data_from_reader};
 
@@ -8,8 +8,12 @@ use std::collections::HashMap;
 
 use std::sync::Arc;
 
-use pathway_engine::connectors::data_format::{InnerSchemaField, JsonLinesParser, ParsedEvent};
-use pathway_engine::connectors::data_storage::{new_filesystem_reader, ConnectorMode, ReadMethod};
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, JsonLinesParser, ParsedEvent, Parser,
+};
+use pathway_engine::connectors::data_storage::{
+    new_filesystem_reader, ConnectorMode, DataEventType, ReadMethod, ReaderContext,
+};
 use pathway_engine::connectors::SessionType;
 use pathway_engine::engine::{DateTimeNaive, DateTimeUtc, Type, Value};
 
@@ -599,3 +603,138 @@ fn test_jsonlines_timestamp() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_jsonlines_multiple_lines_in_payload() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::String, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "c".to_string(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(0))),
+        ),
+    ];
+    let mut parser = JsonLinesParser::new(
+        None,
+        vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        HashMap::new(),
+        true,
+        schema.into(),
+        SessionType::Native,
+    )?;
+
+    let payload = concat!(
+        r#"{"a": "abc", "b": 1, "c": 2}"#,
+        "\n",
+        r#"{"a": "def", "b": "two"}"#,
+        "\n",
+        r#"{"a": "ghi", "b": 3}"#,
+        "\n",
+    );
+    let entries: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload.as_bytes().to_vec(),
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(ReplaceErrors::replace_errors)
+        .collect();
+
+    let expected_values = vec![
+        ParsedEvent::Insert((None, vec![Value::from("abc"), Value::Int(1), Value::Int(2)])),
+        ParsedEvent::Insert((None, vec![Value::from("def"), Value::Error, Value::Int(0)])),
+        ParsedEvent::Insert((None, vec![Value::from("ghi"), Value::Int(3), Value::Int(0)])),
+    ];
+    assert_eq!(entries, expected_values);
+
+    Ok(())
+}
+
+#[test]
+fn test_jsonlines_malformed_line_in_payload() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::String, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let mut parser = JsonLinesParser::new(
+        None,
+        vec!["a".to_string(), "b".to_string()],
+        HashMap::new(),
+        true,
+        schema.into(),
+        SessionType::Native,
+    )?;
+
+    let payload = concat!(r#"{"a": "abc", "b": 1}"#, "\n", r#"{"a": "def", "#, "\n");
+    let entries: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload.as_bytes().to_vec(),
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(ReplaceErrors::replace_errors)
+        .collect();
+
+    let expected_values = vec![
+        ParsedEvent::Insert((None, vec![Value::from("abc"), Value::Int(1)])),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error])),
+    ];
+    assert_eq!(entries, expected_values);
+
+    Ok(())
+}
+
+#[test]
+fn test_jsonlines_simd_matches_serde_json() -> eyre::Result<()> {
+    let new_parser = || {
+        let schema = [
+            ("a".to_string(), InnerSchemaField::new(Type::String, None)),
+            ("b".to_string(), InnerSchemaField::new(Type::Int, None)),
+            ("c".to_string(), InnerSchemaField::new(Type::Float, None)),
+            ("d".to_string(), InnerSchemaField::new(Type::Json, None)),
+        ];
+        JsonLinesParser::new(
+            Some(vec!["a".to_string()]),
+            vec!["b".to_string(), "c".to_string(), "d".to_string()],
+            HashMap::new(),
+            true,
+            schema.into(),
+            SessionType::Native,
+        )
+    };
+    let lines: [&[u8]; 8] = [
+        br#"{"a": "abc", "b": 7, "c": 1.5, "d": {"x": [1, 2.0, null]}}"#,
+        br#"{"a": "\u00e9\n", "b": -9223372036854775808, "c": 1e300, "d": "s"}"#,
+        br#"{"a": "missing", "c": 2}"#,
+        br#"{"a": "types", "b": "7", "c": true, "d": null}"#,
+        br#"{"a": "big", "b": 18446744073709551616, "c": 0.1, "d": 1}"#,
+        br#"{"a": "x", "b": 1, "c": 1.0, "d": 1} trailing"#,
+        b"{\"a\": \"l1\", \"b\": 1, \"c\": 1.0, \"d\": 1}\n{\"a\": \"l2\", \"b\": oops}",
+        b"*COMMIT*",
+    ];
+    let contexts: Vec<_> = lines
+        .iter()
+        .map(|line| ReaderContext::from_raw_bytes(DataEventType::Insert, line.to_vec()))
+        .collect();
+    let parse = |mut parser: JsonLinesParser| {
+        contexts
+            .iter()
+            .map(|context| {
+                let events = parser.parse(context).map(|events| {
+                    let described = format!("{events:?}");
+                    let replaced: Vec<_> = events
+                        .into_iter()
+                        .map(ReplaceErrors::replace_errors)
+                        .collect();
+                    (described, replaced)
+                });
+                events.map_err(|error| error.to_string())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(parse(new_parser()?), parse(new_parser()?.without_simd()));
+    Ok(())
+}
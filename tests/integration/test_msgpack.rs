@@ -0,0 +1,95 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use rmpv::Value as MsgPackValue;
+
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, MsgPackParser, ParsedEvent, Parser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn encode_record(entries: Vec<(&str, MsgPackValue)>) -> eyre::Result<Vec<u8>> {
+    let record = MsgPackValue::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (MsgPackValue::from(key), value))
+            .collect(),
+    );
+    let mut encoded = Vec::new();
+    rmpv::encode::write_value(&mut encoded, &record)?;
+    Ok(encoded)
+}
+
+#[test]
+fn test_msgpack_records() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::String, None)),
+        (
+            "c".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+        (
+            "d".to_string(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(-1))),
+        ),
+    ];
+    let mut parser = MsgPackParser::new(
+        vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ],
+        schema.into(),
+    )?;
+
+    let mut payload = encode_record(vec![
+        ("a", MsgPackValue::from(1)),
+        ("b", MsgPackValue::from("x")),
+        ("c", MsgPackValue::Nil),
+    ])?;
+    payload.extend(encode_record(vec![
+        ("a", MsgPackValue::from("wrong type")),
+        ("b", MsgPackValue::from("y")),
+        ("c", MsgPackValue::from("z")),
+        ("d", MsgPackValue::from(4)),
+    ])?);
+    // A map of two entries, which ends after the first key
+    payload.extend([0x82, 0xa1, b'a']);
+
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload,
+        ))
+        .expect("a malformed record should not fail the whole batch")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Int(1), Value::from("x"), Value::None, Value::Int(-1)]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Error,
+                    Value::from("y"),
+                    Value::from("z"),
+                    Value::Int(4)
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::Error, Value::Error, Value::Error]
+            )),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,76 @@
+// Copyright © 2024 Pathway
+
+use std::sync::Arc;
+
+use crate::helpers::ReplaceErrors;
+
+use deltalake::arrow::array::{Int64Array, RecordBatch as ArrowRecordBatch, StringArray};
+use deltalake::arrow::datatypes::{
+    DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
+};
+use deltalake::parquet::arrow::ArrowWriter;
+
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, ParquetParser, ParsedEvent, Parser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn encode_parquet(batch: &ArrowRecordBatch) -> eyre::Result<Vec<u8>> {
+    let mut encoded = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut encoded, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(encoded)
+}
+
+#[test]
+fn test_parquet_rows() -> eyre::Result<()> {
+    let schema = Arc::new(ArrowSchema::new(vec![
+        ArrowField::new("id", ArrowDataType::Int64, false),
+        ArrowField::new("name", ArrowDataType::Utf8, true),
+        ArrowField::new("ignored", ArrowDataType::Int64, false),
+    ]));
+    let batch = ArrowRecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec![Some("a"), None])),
+            Arc::new(Int64Array::from(vec![0, 0])),
+        ],
+    )?;
+    let payload = encode_parquet(&batch)?;
+
+    let schema = [
+        ("id".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), Some(Value::None)),
+        ),
+        (
+            "absent".to_string(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(-1))),
+        ),
+    ];
+    let mut parser = ParquetParser::new(
+        vec!["id".to_string(), "name".to_string(), "absent".to_string()],
+        schema.into(),
+    )?;
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload,
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("a"), Value::Int(-1)])),
+            ParsedEvent::Insert((None, vec![Value::Int(2), Value::None, Value::Int(-1)])),
+        ]
+    );
+    Ok(())
+}
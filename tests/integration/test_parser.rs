@@ -1,16 +1,38 @@
 // Copyright © 2024 Pathway
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::helpers::ReplaceErrors;
 
+use arcstr::ArcStr;
+use chrono::{NaiveDate, NaiveTime};
+use flate2::write::GzEncoder;
+use flate2::Compression as GzCompression;
 use itertools::Itertools;
 use pathway_engine::connectors::data_format::{
-    InnerSchemaField, ParsedEvent, Parser, TransparentParser,
+    infer_schema, AutoKey, BytesEncoding, CdcImageParser, CompressionCodec, CsvParser,
+    DecompressingParser, DedupParser, DedupTtl, DefaultChange, DelimitedList, EnrichParser,
+    EpochTimestamps, EpochUnit, ExplodeParser, FieldConstraints, FieldDiagnostic, FieldStats,
+    FirstOfParser, IncompatibleVersion, InnerSchemaField, JsonLinesParser, LengthPrefixedParser,
+    Masking, MergeParser, MergeWinner, MissingBehavior, MultiSchemaParser, NumericStats, OnError,
+    ParseError, ParseResult, ParsedEvent, ParsedEventWithErrors, Parser, ParserStats,
+    RetryingParser, SampleParser, Schema, SchemaVersioning, SequenceParser, SnapshotCoalescer,
+    ThrottleParser, TransparentParser, TypeChange, UnknownDiscriminator, UnknownFields,
 };
 use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::connectors::metadata::SourceMetadata;
 use pathway_engine::connectors::SessionType;
-use pathway_engine::engine::{Type, Value};
+use pathway_engine::engine::coerce::{BoolTokens, CoercionOptions, DurationFormat, Utf8Mode};
+use pathway_engine::engine::{
+    DateTimeUtc, Duration as EngineDuration, FixedStringAlign, Type, Value,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
 
 #[test]
 fn test_transparent_parser() -> eyre::Result<()> {
@@ -61,27 +83,32 @@ fn test_transparent_parser() -> eyre::Result<()> {
             expected_i
         );
     }
+    assert_eq!(
+        parser.stats(),
+        ParserStats {
+            events: 3,
+            rows_with_errors: 2,
+            errors: 2,
+            bytes_consumed: 0,
+        }
+    );
     Ok(())
 }
 
 #[test]
-fn test_transparent_parser_defaults() -> eyre::Result<()> {
+fn test_transparent_parser_filter() -> eyre::Result<()> {
     let value_field_names = vec!["a".to_owned(), "b".to_owned()];
     let schema = [
-        (
-            "a".to_owned(),
-            InnerSchemaField::new(Type::Int, Some(Value::Int(10))),
-        ),
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
         (
             "b".to_owned(),
-            InnerSchemaField::new(
-                Type::Optional(Type::String.into()),
-                Some(Value::from("default")),
-            ),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
         ),
     ];
+    // Malformed rows are kept, so that they can be reported further
     let mut parser =
-        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Native)?;
+        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Native)?
+            .with_filter(|row| !matches!(row[0], Value::Int(a) if a < 3));
     let contexts = vec![
         ReaderContext::from_diff(
             DataEventType::Insert,
@@ -105,72 +132,4785 @@ fn test_transparent_parser_defaults() -> eyre::Result<()> {
         ReaderContext::from_diff(
             DataEventType::Delete,
             None,
-            HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(2))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::from(3), Value::from("abc")])),
+            ParsedEvent::Insert((None, vec![Value::Error, Value::from("abc")])),
+            ParsedEvent::Delete((None, vec![Value::from(2), Value::from("abc")])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_select() -> eyre::Result<()> {
+    let new_parser = || {
+        let schema = [
+            ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ];
+        TransparentParser::new(
+            Some(vec!["a".to_owned()]),
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+    };
+    let mut parser = new_parser()?.with_select(vec!["b".to_owned()])?;
+    assert_eq!(parser.column_count(), 1);
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(1))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(2))),
+                ("b".to_owned(), Ok(Value::Int(5))),
+            ])
+            .into(),
+        ),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![Value::from("abc")])),
+            ParsedEvent::Insert((Some(vec![Value::Int(2)]), vec![Value::Error])),
+        ]
+    );
+
+    assert!(new_parser()?.with_select(vec!["c".to_owned()]).is_err());
+    assert!(new_parser()?
+        .with_select(vec!["b".to_owned(), "b".to_owned()])
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_case_insensitive_fields() -> eyre::Result<()> {
+    let schema = [
+        ("userId".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("anonymous"))),
         ),
     ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["userId".to_owned(), "name".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_case_insensitive_fields()?;
+    let contexts = vec![
+        HashMap::from([
+            ("USERID".to_owned(), Ok(Value::Int(1))),
+            ("Name".to_owned(), Ok(Value::from("alice"))),
+        ]),
+        HashMap::from([
+            ("userid".to_owned(), Ok(Value::Int(2))),
+            ("userId".to_owned(), Ok(Value::Int(3))),
+        ]),
+    ];
     let expected = vec![
-        ParsedEvent::Insert((None, vec![Value::from(3), Value::from("abc")])),
-        ParsedEvent::Insert((None, vec![Value::from(10), Value::from("abc")])),
-        ParsedEvent::Insert((None, vec![Value::from(2), Value::from("default")])),
-        ParsedEvent::Delete((None, vec![Value::from(2), Value::from("default")])),
+        ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("alice")])),
+        ParsedEvent::Insert((None, vec![Value::Int(3), Value::from("anonymous")])),
     ];
-    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
         assert_eq!(
             parser
-                .parse(&context_i)
-                .expect("creating message should not fail")
+                .parse(&context)
+                .expect("parsing should not fail")
                 .into_iter()
                 .exactly_one()?
                 .replace_errors(),
             expected_i
         );
     }
+
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("ID".to_owned(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let parser = TransparentParser::new(
+        None,
+        vec!["id".to_owned(), "ID".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    assert_eq!(
+        parser
+            .with_case_insensitive_fields()
+            .err()
+            .map(|e| e.to_string()),
+        Some("columns ID and id differ only in case".to_owned())
+    );
     Ok(())
 }
 
 #[test]
-fn test_transparent_parser_upsert() -> eyre::Result<()> {
-    let value_field_names = vec!["a".to_owned(), "b".to_owned()];
+fn test_transparent_parser_dead_letters() -> eyre::Result<()> {
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+    let new_parser = || {
+        TransparentParser::new(
+            None,
+            vec!["a".to_owned()],
+            schema.clone().into(),
+            SessionType::Native,
+        )
+    };
+    let row = |a: i64| {
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(a)))]).into(),
+        )
+    };
+    let contexts = vec![
+        row(1),
+        ReaderContext::from_raw_bytes(DataEventType::Insert, b"a=2".to_vec()),
+        row(3),
+    ];
+
+    let mut parser = new_parser()?.with_dead_letters();
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("a failed context should not fail the batch")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(1)])),
+            ParsedEvent::Insert((None, vec![Value::Int(3)])),
+        ]
+    );
+    let (context, reason) = parser.take_dead_letters().into_iter().exactly_one()?;
+    assert!(matches!(context, ReaderContext::RawBytes(_, ref bytes) if bytes == b"a=2"));
+    assert_eq!(reason, ParseError::UnsupportedReaderContext.to_string());
+    assert!(parser.take_dead_letters().is_empty());
+
+    assert!(new_parser()?.parse_batch(&contexts).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_fail_on_error() -> eyre::Result<()> {
     let schema = [
         ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
         ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
     ];
-    let mut parser =
-        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Upsert)?;
-    let contexts = vec![
-        ReaderContext::from_diff(
-            DataEventType::Insert,
+    let new_parser = || {
+        TransparentParser::new(
             None,
-            HashMap::from([
-                ("a".to_owned(), Ok(Value::Int(3))),
-                ("b".to_owned(), Ok(Value::from("abc"))),
-            ])
-            .into(),
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.clone().into(),
+            SessionType::Native,
+        )
+        .map(TransparentParser::with_fail_on_error)
+    };
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+    );
+
+    let error = new_parser()?
+        .parse(&context)
+        .expect_err("a missing field should fail the parsing");
+    let Some(ParseError::FieldFailedInStrictMode {
+        field_name,
+        raw_value,
+        ..
+    }) = error.downcast_ref::<ParseError>()
+    else {
+        panic!("unexpected error: {error}");
+    };
+    assert_eq!(field_name, "b");
+    assert_eq!(raw_value, "missing");
+
+    let mut parser = new_parser()?.with_dead_letters();
+    assert!(parser
+        .parse(&context)
+        .expect("the failure should become a dead letter")
+        .is_empty());
+    let (_, reason) = parser.take_dead_letters().into_iter().exactly_one()?;
+    assert!(reason.contains("\"b\""));
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_on_error() -> eyre::Result<()> {
+    let schema = [
+        (
+            "a".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(-1))).with_on_error(OnError::Default),
         ),
-        ReaderContext::from_diff(
-            DataEventType::Delete,
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::Int.into()), None)
+                .with_on_error(OnError::Null),
+        ),
+        ("c".to_owned(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from("abc"))),
+            ("b".to_owned(), Ok(Value::from("abc"))),
+            ("c".to_owned(), Ok(Value::from("abc"))),
+        ])
+        .into(),
+    );
+    assert_eq!(
+        parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors(),
+        ParsedEvent::Insert((None, vec![Value::Int(-1), Value::None, Value::Error]))
+    );
+
+    let new_parser = |field: InnerSchemaField| {
+        TransparentParser::new(
             None,
-            HashMap::from([
-                ("a".to_owned(), Ok(Value::Int(3))),
-                ("b".to_owned(), Ok(Value::from("abc"))),
-            ])
-            .into(),
+            vec!["a".to_owned()],
+            [("a".to_owned(), field)].into(),
+            SessionType::Native,
+        )
+    };
+    assert!(
+        new_parser(InnerSchemaField::new(Type::Int, None).with_on_error(OnError::Null)).is_err()
+    );
+    assert!(
+        new_parser(InnerSchemaField::new(Type::Int, None).with_on_error(OnError::Default)).is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_computed_default() -> eyre::Result<()> {
+    let counter = Arc::new(AtomicI64::new(0));
+    let next_id = {
+        let counter = counter.clone();
+        move || Value::Int(counter.fetch_add(1, Ordering::SeqCst))
+    };
+    let schema = [
+        (
+            "id".to_owned(),
+            InnerSchemaField::new(Type::Int, None).with_computed_default(next_id),
         ),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["id".to_owned(), "name".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    // The default was called once to check its type
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    let contexts = vec![
+        HashMap::from([("name".to_owned(), Ok(Value::from("a")))]),
+        HashMap::from([
+            ("id".to_owned(), Ok(Value::Int(10))),
+            ("name".to_owned(), Ok(Value::from("b"))),
+        ]),
+        HashMap::from([("name".to_owned(), Ok(Value::from("c")))]),
     ];
     let expected = vec![
-        ParsedEvent::Insert((None, vec![Value::from(3), Value::from("abc")])),
-        ParsedEvent::Delete((None, vec![])),
+        ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("a")])),
+        ParsedEvent::Insert((None, vec![Value::Int(10), Value::from("b")])),
+        ParsedEvent::Insert((None, vec![Value::Int(2), Value::from("c")])),
     ];
-    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
         assert_eq!(
             parser
-                .parse(&context_i)
-                .expect("creating message should not fail")
+                .parse(&context)
+                .expect("parsing should not fail")
                 .into_iter()
                 .exactly_one()?
                 .replace_errors(),
             expected_i
         );
     }
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    let invalid_schema = [(
+        "id".to_owned(),
+        InnerSchemaField::new(Type::Int, None).with_computed_default(|| Value::from("x")),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["id".to_owned()],
+        invalid_schema.into(),
+        SessionType::Native,
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_utf8_modes() -> eyre::Result<()> {
+    let new_parser = |utf8_mode| {
+        let schema = [
+            ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("text".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ];
+        TransparentParser::new(
+            None,
+            vec!["id".to_owned(), "text".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+        .map(|parser| {
+            parser
+                .with_coercion_options(CoercionOptions {
+                    utf8_mode,
+                    ..Default::default()
+                })
+                .with_dead_letters()
+        })
+    };
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([
+            ("id".to_owned(), Ok(Value::Int(1))),
+            ("text".to_owned(), Ok(Value::from(b"ab\xffc".as_slice()))),
+        ])
+        .into(),
+    );
+    let parse = |parser: &mut TransparentParser| -> Vec<ParsedEvent> {
+        parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|e| e.replace_errors())
+            .collect()
+    };
+
+    let mut parser = new_parser(Utf8Mode::Strict)?;
+    assert_eq!(
+        parse(&mut parser),
+        vec![ParsedEvent::Insert((
+            None,
+            vec![Value::Int(1), Value::Error]
+        ))]
+    );
+    assert!(parser.take_dead_letters().is_empty());
+
+    let mut parser = new_parser(Utf8Mode::Lossy)?;
+    assert_eq!(
+        parse(&mut parser),
+        vec![ParsedEvent::Insert((
+            None,
+            vec![Value::Int(1), Value::from("ab\u{FFFD}c")]
+        ))]
+    );
+
+    let mut parser = new_parser(Utf8Mode::Reject)?;
+    assert_eq!(parse(&mut parser), vec![]);
+    parser.take_dead_letters().into_iter().exactly_one()?;
+    Ok(())
+}
+
+#[test]
+fn test_reader_context_payload_len() {
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, b"abcde".to_vec());
+    assert_eq!(context.payload_len(), Some(5));
+    let context = ReaderContext::from_key_value(Some(b"key".to_vec()), Some(b"{}".to_vec()));
+    assert_eq!(context.payload_len(), Some(2));
+    assert_eq!(
+        ReaderContext::from_key_value(None, None).payload_len(),
+        None
+    );
+    let values = HashMap::from([("a".to_owned(), Ok(Value::Int(1)))]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    assert_eq!(context.payload_len(), None);
+}
+
+#[test]
+fn test_transparent_parser_bytes_consumed() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["id".to_owned()]),
+        vec!["id".to_owned(), "name".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_dead_letters();
+    let payload = br#"{"id": 7, "name": "abc"}"#.to_vec();
+    let values = HashMap::from([
+        ("id".to_owned(), Ok(Value::Int(8))),
+        ("name".to_owned(), Ok(Value::from("def"))),
+    ]);
+    let contexts = [
+        ReaderContext::from_key_value(Some(b"7".to_vec()), Some(payload.clone())),
+        ReaderContext::from_key_value_with_headers(
+            Some(b"7".to_vec()),
+            Some(payload),
+            HashMap::new(),
+        ),
+        ReaderContext::from_key_value(Some(b"7".to_vec()), Some(b"{".to_vec())),
+        ReaderContext::from_diff(DataEventType::Insert, None, values.into()),
+    ];
+    parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail");
+    assert_eq!(parser.take_dead_letters().len(), 1);
+
+    let expected: usize = contexts.iter().filter_map(ReaderContext::payload_len).sum();
+    assert_eq!(expected, 49);
+    assert_eq!(parser.stats().bytes_consumed, expected as u64);
+    parser.reset();
+    assert_eq!(parser.stats().bytes_consumed, expected as u64);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_key_value() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["id".to_owned()]),
+        vec!["id".to_owned(), "name".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = [
+        ReaderContext::from_key_value(
+            Some(b"7".to_vec()),
+            Some(br#"{"id": 7, "name": "abc"}"#.to_vec()),
+        ),
+        ReaderContext::from_key_value(None, Some(br#"{"id": 7, "name": "abc"}"#.to_vec())),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(7)]),
+                vec![Value::Int(7), Value::from("abc")]
+            )),
+            ParsedEvent::Delete((
+                Some(vec![Value::Int(7)]),
+                vec![Value::Int(7), Value::from("abc")]
+            )),
+        ]
+    );
+    assert_eq!(parser.stats().bytes_consumed, 48);
+
+    let context = ReaderContext::from_key_value(Some(b"x".to_vec()), Some(b"{}".to_vec()));
+    assert!(parser.parse(&context).is_err());
+    assert_eq!(parser.stats().bytes_consumed, 50);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_raw_payload() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["id".to_owned()]),
+        vec!["id".to_owned(), "name".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_raw_payload()?;
+    assert_eq!(parser.column_count(), 3);
+    let payloads = [
+        br#"{"id": 7, "name": "abc"}"#.to_vec(),
+        br#"{"id": 8, "name": 5}"#.to_vec(),
+    ];
+    let contexts: Vec<_> = ["7", "8"]
+        .into_iter()
+        .zip(&payloads)
+        .map(|(key, payload)| {
+            ReaderContext::from_key_value(Some(key.as_bytes().to_vec()), Some(payload.clone()))
+        })
+        .collect();
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(7)]),
+                vec![
+                    Value::Int(7),
+                    Value::from("abc"),
+                    Value::Bytes(payloads[0].as_slice().into())
+                ]
+            )),
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(8)]),
+                vec![
+                    Value::Int(8),
+                    Value::Error,
+                    Value::Bytes(payloads[1].as_slice().into())
+                ]
+            )),
+        ]
+    );
+
+    let values = HashMap::from([
+        ("name".to_owned(), Ok(Value::from("abc"))),
+        ("id".to_owned(), Ok(Value::Int(9))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    assert_eq!(
+        event,
+        ParsedEvent::Insert((
+            Some(vec![Value::Int(9)]),
+            vec![
+                Value::Int(9),
+                Value::from("abc"),
+                Value::from(r#"{"id":9,"name":"abc"}"#)
+            ]
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_key_lists() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let context = |event, a| {
+        let values = HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(a))),
+            ("b".to_owned(), Ok(Value::from("x"))),
+        ]);
+        ReaderContext::from_diff(event, None, values.into())
+    };
+    let row = |a| {
+        (
+            Some(vec![Value::Int(a)]),
+            vec![Value::Int(a), Value::from("x")],
+        )
+    };
+    let parse = |parser: &mut TransparentParser, contexts: &[ReaderContext]| {
+        parser
+            .parse_batch(contexts)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|event| event.replace_errors())
+            .collect::<Vec<_>>()
+    };
+
+    parser.set_key_allowlist(Some(HashSet::from([Value::Int(3)])));
+    let contexts = [
+        context(DataEventType::Insert, 3),
+        context(DataEventType::Insert, 2),
+    ];
+    assert_eq!(
+        parse(&mut parser, &contexts),
+        vec![ParsedEvent::Insert(row(3))]
+    );
+
+    parser.set_key_allowlist(None);
+    parser.set_key_denylist(HashSet::from([Value::Int(3)]));
+    let contexts = [
+        context(DataEventType::Delete, 3),
+        context(DataEventType::Insert, 2),
+    ];
+    assert_eq!(
+        parse(&mut parser, &contexts),
+        vec![ParsedEvent::Insert(row(2))]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_missing_behavior() -> eyre::Result<()> {
+    let parse_missing = |missing| -> eyre::Result<Value> {
+        let schema = [
+            ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            (
+                "b".to_owned(),
+                InnerSchemaField::new(Type::Optional(Type::String.into()), None)
+                    .with_missing_behavior(missing),
+            ),
+        ];
+        let mut parser = TransparentParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )?;
+        let values = HashMap::from([("a".to_owned(), Ok(Value::Int(1)))]);
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        let event = parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors();
+        let ParsedEvent::Insert((_, values)) = event else {
+            panic!("expected an insertion, got {event:?}");
+        };
+        Ok(values[1].clone())
+    };
+
+    assert_eq!(parse_missing(MissingBehavior::Null)?, Value::None);
+    assert_eq!(
+        parse_missing(MissingBehavior::Default(Value::from("x")))?,
+        Value::from("x")
+    );
+    assert_eq!(parse_missing(MissingBehavior::Error)?, Value::Error);
+
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Int, None).with_missing_behavior(MissingBehavior::Null),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_header_field() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+        (
+            "trace_id".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("untraced"))),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["id".to_owned()]),
+        vec!["id".to_owned(), "name".to_owned(), "trace_id".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_header_field("trace_id", "trace-id")?;
+    let payload = br#"{"id": 7, "name": "abc", "trace_id": "ignored"}"#;
+    let context = |trace_id: Option<&[u8]>| {
+        let headers = trace_id
+            .map(|trace_id| ("trace-id".to_owned(), trace_id.to_vec()))
+            .into_iter()
+            .collect();
+        ReaderContext::from_key_value_with_headers(
+            Some(b"7".to_vec()),
+            Some(payload.to_vec()),
+            headers,
+        )
+    };
+    let contexts = [
+        context(Some(b"t-1")),
+        context(None),
+        context(Some(b"\xff\xfe")),
+    ];
+    let trace_ids: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| {
+            let ParsedEvent::Insert((_, values)) = event.replace_errors() else {
+                panic!("expected an insertion");
+            };
+            assert_eq!(values[..2], [Value::Int(7), Value::from("abc")]);
+            values[2].clone()
+        })
+        .collect();
+    assert_eq!(
+        trace_ids,
+        vec![Value::from("t-1"), Value::from("untraced"), Value::Error]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_accumulation() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("none"))),
+        ),
+        (
+            "c".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(0))),
+        ),
+    ];
+    let new_parser = |key_field_names, session_type| {
+        TransparentParser::new(
+            key_field_names,
+            vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+            schema.clone().into(),
+            session_type,
+        )
+    };
+    assert!(new_parser(Some(vec!["a".to_owned()]), SessionType::Native)?
+        .with_accumulation()
+        .is_err());
+    assert!(new_parser(None, SessionType::Upsert)?
+        .with_accumulation()
+        .is_err());
+
+    let mut parser =
+        new_parser(Some(vec!["a".to_owned()]), SessionType::Upsert)?.with_accumulation()?;
+    let context = |event, fields: &[(&str, Value)]| {
+        let values: HashMap<_, _> = fields
+            .iter()
+            .map(|(name, value)| ((*name).to_owned(), Ok(value.clone())))
+            .collect();
+        ReaderContext::from_diff(event, None, values.into())
+    };
+    let contexts = [
+        context(
+            DataEventType::Insert,
+            &[("a", Value::Int(1)), ("b", Value::from("x"))],
+        ),
+        context(
+            DataEventType::Insert,
+            &[("a", Value::Int(1)), ("c", Value::Int(5))],
+        ),
+        context(
+            DataEventType::Insert,
+            &[("a", Value::Int(1)), ("b", Value::from("y"))],
+        ),
+        context(DataEventType::Delete, &[("a", Value::Int(1))]),
+        context(DataEventType::Insert, &[("a", Value::Int(1))]),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    let insert = |b, c| {
+        ParsedEvent::Insert((
+            Some(vec![Value::Int(1)]),
+            vec![Value::Int(1), Value::from(b), Value::Int(c)],
+        ))
+    };
+    assert_eq!(
+        events,
+        vec![
+            insert("x", 0),
+            insert("x", 5),
+            insert("y", 5),
+            ParsedEvent::Delete((Some(vec![Value::Int(1)]), vec![])),
+            insert("none", 0),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_source_field_order() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+        (
+            "score".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(0))),
+        ),
+    ];
+    let new_parser = |session_type| {
+        TransparentParser::new(
+            Some(vec!["id".to_owned()]),
+            vec!["id".to_owned(), "name".to_owned(), "score".to_owned()],
+            schema.clone().into(),
+            session_type,
+        )
+    };
+    assert!(new_parser(SessionType::Snapshot)?
+        .with_source_field_order()
+        .is_err());
+
+    let mut parser = new_parser(SessionType::Native)?.with_source_field_order()?;
+    let contexts = [
+        ReaderContext::from_key_value(
+            Some(b"7".to_vec()),
+            Some(br#"{"name": "abc", "id": 7}"#.to_vec()),
+        ),
+        ReaderContext::from_key_value(
+            Some(b"8".to_vec()),
+            Some(br#"{"score": 3, "id": 8, "name": "def"}"#.to_vec()),
+        ),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(7)]),
+                vec![Value::from("abc"), Value::Int(7), Value::Int(0)]
+            )),
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(8)]),
+                vec![Value::Int(3), Value::Int(8), Value::from("def")]
+            )),
+        ]
+    );
+    assert_eq!(
+        parser.take_field_orders(),
+        vec![
+            vec!["name".to_owned(), "id".to_owned(), "score".to_owned()],
+            vec!["score".to_owned(), "id".to_owned(), "name".to_owned()],
+        ]
+    );
+    assert!(parser.take_field_orders().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_unknown_fields() -> eyre::Result<()> {
+    let new_parser = |unknown_fields| {
+        let schema = [
+            ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ];
+        TransparentParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+        .and_then(|parser| parser.with_renames(HashMap::from([("B".to_owned(), "b".to_owned())])))
+        .map(|parser| {
+            parser
+                .with_unknown_fields(unknown_fields)
+                .with_dead_letters()
+        })
+    };
+    let row = |extra: Option<&str>| {
+        let mut values = HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(1))),
+            ("B".to_owned(), Ok(Value::from("x"))),
+        ]);
+        if let Some(extra) = extra {
+            values.insert(extra.to_owned(), Ok(Value::Int(2)));
+        }
+        ReaderContext::from_diff(DataEventType::Insert, None, values.into())
+    };
+    let contexts = [row(None), row(Some("c"))];
+    let parse = |parser: &mut TransparentParser| -> Vec<ParsedEvent> {
+        parser
+            .parse_batch(&contexts)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|e| e.replace_errors())
+            .collect()
+    };
+    let valid_row = ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("x")]));
+
+    let mut parser = new_parser(UnknownFields::Ignore)?;
+    assert_eq!(
+        parse(&mut parser),
+        vec![valid_row.clone(), valid_row.clone()]
+    );
+
+    let mut parser = new_parser(UnknownFields::Error)?;
+    let events = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail");
+    let diagnostics = events[1].field_diagnostics(&["a".to_owned(), "b".to_owned()]);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+        diagnostics[0].reason,
+        ParseError::UnknownFields(vec!["c".to_owned()]).to_string()
+    );
+    assert_eq!(
+        events
+            .into_iter()
+            .map(|e| e.replace_errors())
+            .collect::<Vec<_>>(),
+        vec![
+            valid_row.clone(),
+            ParsedEvent::Insert((None, vec![Value::Error, Value::Error]))
+        ]
+    );
+
+    let mut parser = new_parser(UnknownFields::DeadLetter)?;
+    assert_eq!(parse(&mut parser), vec![valid_row]);
+    let (_, reason) = parser.take_dead_letters().into_iter().exactly_one()?;
+    assert_eq!(
+        reason,
+        ParseError::UnknownFields(vec!["c".to_owned()]).to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_schema_backward_compatibility() {
+    let schema = |fields: &[(&str, InnerSchemaField)]| -> HashMap<String, InnerSchemaField> {
+        fields
+            .iter()
+            .map(|(name, field)| ((*name).to_owned(), field.clone()))
+            .collect()
+    };
+    let required = || InnerSchemaField::new(Type::Int, None);
+    let optional = || InnerSchemaField::new(Type::Optional(Type::String.into()), None);
+    let previous = schema(&[("a", required()), ("b", optional())]);
+
+    let added_optional = schema(&[("a", required()), ("b", optional()), ("c", optional())]);
+    assert!(added_optional.is_backward_compatible_with(&previous));
+    let dropped_optional = schema(&[("a", required())]);
+    assert!(dropped_optional.is_backward_compatible_with(&previous));
+
+    let dropped_required = schema(&[("b", optional())]);
+    assert!(!dropped_required.is_backward_compatible_with(&previous));
+    let added_required = schema(&[("a", required()), ("b", optional()), ("c", required())]);
+    assert!(!added_required.is_backward_compatible_with(&previous));
+    let changed_type = schema(&[
+        ("a", InnerSchemaField::new(Type::String, None)),
+        ("b", optional()),
+    ]);
+    assert!(!changed_type.is_backward_compatible_with(&previous));
+}
+
+#[test]
+fn test_diff_schemas() {
+    let schema = |fields: &[(&str, InnerSchemaField)]| -> HashMap<String, InnerSchemaField> {
+        fields
+            .iter()
+            .map(|(name, field)| ((*name).to_owned(), field.clone()))
+            .collect()
+    };
+    let previous = schema(&[
+        ("a", InnerSchemaField::new(Type::Int, None)),
+        ("b", InnerSchemaField::new(Type::Int, Some(Value::Int(0)))),
+        ("c", InnerSchemaField::new(Type::Bool, None)),
+    ]);
+    let current = schema(&[
+        ("a", InnerSchemaField::new(Type::String, None)),
+        ("b", InnerSchemaField::new(Type::Int, Some(Value::Int(1)))),
+        (
+            "d",
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ]);
+
+    let diff = current.diff(&previous);
+    assert_eq!(diff.added, ["d"]);
+    assert_eq!(diff.removed, ["c"]);
+    assert_eq!(
+        diff.type_changes,
+        [TypeChange {
+            field_name: "a".to_owned(),
+            previous: Type::Int,
+            current: Type::String,
+        }]
+    );
+    assert_eq!(
+        diff.default_changes,
+        [DefaultChange {
+            field_name: "b".to_owned(),
+            previous: Some(Value::Int(0)),
+            current: Some(Value::Int(1)),
+        }]
+    );
+    assert!(previous.diff(&previous).is_empty());
+}
+
+#[test]
+fn test_transparent_parser_schema_versioning() -> eyre::Result<()> {
+    let new_parser = |policy| {
+        let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+        TransparentParser::new(
+            None,
+            vec!["a".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+        .map(|parser| {
+            parser
+                .with_schema_versioning(SchemaVersioning {
+                    version: "2".to_owned(),
+                    version_field: "version".to_owned(),
+                    incompatible_versions: HashSet::from(["1".to_owned()]),
+                    policy,
+                })
+                .with_unknown_fields(UnknownFields::Error)
+                .with_dead_letters()
+        })
+    };
+    let row = |version: &str| {
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(1))),
+                ("version".to_owned(), Ok(Value::from(version))),
+            ])
+            .into(),
+        )
+    };
+    let contexts = [row("2"), row("1")];
+    let valid_row = ParsedEvent::Insert((None, vec![Value::Int(1)]));
+
+    let mut parser = new_parser(IncompatibleVersion::Error)?;
+    assert_eq!(parser.schema_version(), Some("2"));
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            valid_row.clone(),
+            ParsedEvent::Insert((None, vec![Value::Error]))
+        ]
+    );
+
+    let mut parser = new_parser(IncompatibleVersion::DeadLetter)?;
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(events, vec![valid_row]);
+    let (_, reason) = parser.take_dead_letters().into_iter().exactly_one()?;
+    assert_eq!(
+        reason,
+        ParseError::IncompatibleSchemaVersion("1".to_owned()).to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_shares_string_buffers() -> eyre::Result<()> {
+    let schema = [
+        ("text".to_owned(), InnerSchemaField::new(Type::String, None)),
+        (
+            "maybe_text".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["text".to_owned(), "maybe_text".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let text = ArcStr::from("a".repeat(1024));
+    let values = HashMap::from([
+        ("text".to_owned(), Ok(Value::from(text.clone()))),
+        ("maybe_text".to_owned(), Ok(Value::from(text.clone()))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    let ParsedEvent::Insert((None, ref parsed)) = event else {
+        panic!("unexpected event: {event:?}");
+    };
+    for value in parsed {
+        assert_eq!(value, &Value::from(text.as_str()));
+        assert!(ArcStr::ptr_eq(
+            value.as_string().expect("the value should be a string"),
+            &text
+        ));
+    }
+    assert_eq!(event.clone(), event);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_batch() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ];
+    let new_parser = || {
+        TransparentParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.clone().into(),
+            SessionType::Native,
+        )
+    };
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(3))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("b".to_owned(), Ok(Value::from("abc")))]).into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+        ),
+    ];
+
+    let mut parser = new_parser()?;
+    let mut expected = Vec::new();
+    for context in &contexts {
+        expected.extend(parser.parse(context).expect("parsing should not fail"));
+    }
+    let expected: Vec<_> = expected.into_iter().map(|e| e.replace_errors()).collect();
+    let batch: Vec<_> = new_parser()?
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(batch, expected);
+
+    let mut contexts = contexts;
+    contexts.insert(
+        1,
+        ReaderContext::from_raw_bytes(DataEventType::Insert, vec![]),
+    );
+    let error = new_parser()?
+        .parse_batch(&contexts)
+        .expect_err("raw bytes are not supported");
+    assert!(matches!(
+        error.downcast_ref::<ParseError>(),
+        Some(ParseError::BatchContextFailed { index: 1, .. })
+    ));
+    Ok(())
+}
+
+struct CapturingLogger;
+
+static CAPTURED_LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Debug
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() == log::Level::Debug {
+            CAPTURED_LOGS
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_transparent_parser_debug_logging() -> eyre::Result<()> {
+    log::set_logger(&CapturingLogger).expect("no other logger should be set");
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let schema = [
+        ("debug_a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "debug_b".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["debug_a".to_owned(), "debug_b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_debug_logging();
+    let contexts = vec![
+        HashMap::from([
+            ("debug_a".to_owned(), Ok(Value::Int(3))),
+            ("debug_b".to_owned(), Ok(Value::from("abc"))),
+        ]),
+        HashMap::from([("debug_b".to_owned(), Ok(Value::from("abc")))]),
+        HashMap::from([("debug_a".to_owned(), Ok(Value::Int(2)))]),
+    ];
+    for values in contexts {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        parser.parse(&context).expect("parsing should not fail");
+    }
+
+    let logs: Vec<_> = CAPTURED_LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|line| line.contains("debug_a") || line.contains("debug_b"))
+        .cloned()
+        .collect();
+    assert_eq!(logs.len(), 2);
+    assert!(logs[0].contains("field \"debug_a\" of type int with raw value missing"));
+    assert!(logs[1].contains("field \"debug_b\" of type str | None with raw value missing"));
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_defaults() -> eyre::Result<()> {
+    let value_field_names = vec!["a".to_owned(), "b".to_owned()];
+    let schema = [
+        (
+            "a".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(10))),
+        ),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(
+                Type::Optional(Type::String.into()),
+                Some(Value::from("default")),
+            ),
+        ),
+    ];
+    let mut parser =
+        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Native)?;
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(3))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("b".to_owned(), Ok(Value::from("abc")))]).into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Delete,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+        ),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::from(3), Value::from("abc")])),
+        ParsedEvent::Insert((None, vec![Value::from(10), Value::from("abc")])),
+        ParsedEvent::Insert((None, vec![Value::from(2), Value::from("default")])),
+        ParsedEvent::Delete((None, vec![Value::from(2), Value::from("default")])),
+    ];
+    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+        assert_eq!(
+            parser
+                .parse(&context_i)
+                .expect("creating message should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    // The deletion of the last row takes `b` from the default as well
+    assert_eq!(
+        parser.coercion_report(),
+        HashMap::from([
+            (
+                "a".to_owned(),
+                FieldStats {
+                    ok: 3,
+                    defaulted: 1,
+                    errored: 0,
+                },
+            ),
+            (
+                "b".to_owned(),
+                FieldStats {
+                    ok: 2,
+                    defaulted: 2,
+                    errored: 0,
+                },
+            ),
+        ])
+    );
+    parser.reset();
+    assert!(parser.coercion_report().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_numeric_profile() -> eyre::Result<()> {
+    let schema = [
+        (
+            "a".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::Int.into()), None),
+        ),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts: Vec<_> = [
+        Value::Int(3),
+        Value::Int(2),
+        Value::None,
+        Value::from("text"),
+    ]
+    .into_iter()
+    .map(|a| {
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(a)),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        )
+    })
+    .collect();
+    parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail");
+    assert_eq!(
+        parser.numeric_profile(),
+        HashMap::from([(
+            "a".to_owned(),
+            NumericStats {
+                min: 2.0,
+                max: 3.0,
+                sum: 5.0,
+                count: 2,
+            },
+        )])
+    );
+    parser.reset();
+    assert!(parser.numeric_profile().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_upsert() -> eyre::Result<()> {
+    let value_field_names = vec!["a".to_owned(), "b".to_owned()];
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser =
+        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Upsert)?;
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(3))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Delete,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(3))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::from(3), Value::from("abc")])),
+        ParsedEvent::Delete((None, vec![])),
+    ];
+    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+        assert_eq!(
+            parser
+                .parse(&context_i)
+                .expect("creating message should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_decimal() -> eyre::Result<()> {
+    let value_field_names = vec!["a".to_owned(), "b".to_owned()];
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Decimal, Some(Value::Decimal(Decimal::new(100, 2)))),
+        ),
+    ];
+    let mut parser =
+        TransparentParser::new(None, value_field_names, schema.into(), SessionType::Native)?;
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(1))),
+                ("b".to_owned(), Ok(Value::from("3.14"))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(2)))]).into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(3))),
+                ("b".to_owned(), Ok(Value::from("abc"))),
+            ])
+            .into(),
+        ),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from(1), Value::Decimal(Decimal::new(314, 2))],
+        )),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from(2), Value::Decimal(Decimal::new(100, 2))],
+        )),
+        ParsedEvent::Insert((None, vec![Value::from(3), Value::Error])),
+    ];
+    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+        assert_eq!(
+            parser
+                .parse(&context_i)
+                .expect("creating message should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_coalescing_within_batch() {
+    let key = || Some(Ok(vec![Value::Int(1)]));
+    let row = || vec![Ok(Value::Int(1)), Ok(Value::from("x"))];
+    let batch = vec![
+        ParsedEventWithErrors::new(SessionType::Snapshot, DataEventType::Insert, key(), row()),
+        ParsedEventWithErrors::new(SessionType::Snapshot, DataEventType::Delete, key(), row()),
+        ParsedEventWithErrors::new(SessionType::Snapshot, DataEventType::Insert, key(), row()),
+    ];
+    let events: Vec<_> = SnapshotCoalescer::new()
+        .coalesce(batch)
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![ParsedEvent::Insert((
+            Some(vec![Value::Int(1)]),
+            vec![Value::Int(1), Value::from("x")]
+        ))]
+    );
+}
+
+#[test]
+fn test_snapshot_coalescing_preserves_order() {
+    let event = |data_event, key: i64, value: &str| {
+        ParsedEventWithErrors::new(
+            SessionType::Snapshot,
+            data_event,
+            Some(Ok(vec![Value::Int(key)])),
+            vec![Ok(Value::Int(key)), Ok(Value::from(value))],
+        )
+    };
+    let batch = || {
+        let mut batch: Vec<_> = (0..20)
+            .rev()
+            .map(|key| event(DataEventType::Insert, key, "x"))
+            .collect();
+        batch.push(event(DataEventType::Insert, 7, "y"));
+        batch.push(event(DataEventType::Delete, 3, "x"));
+        batch
+    };
+    let coalesce = || {
+        SnapshotCoalescer::new()
+            .coalesce(batch())
+            .into_iter()
+            .map(|event| event.replace_errors())
+            .collect::<Vec<_>>()
+    };
+    let events = coalesce();
+    let expected: Vec<_> = (0..20)
+        .rev()
+        .filter(|key| *key != 3)
+        .map(|key| {
+            let value = if key == 7 { "y" } else { "x" };
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(key)]),
+                vec![Value::Int(key), Value::from(value)],
+            ))
+        })
+        .collect();
+    assert_eq!(events, expected);
+    assert_eq!(coalesce(), events);
+}
+
+#[test]
+fn test_transparent_parser_snapshot() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Snapshot,
+    )?;
+    let row = |a: i64, b: &str| {
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(a))),
+            ("b".to_owned(), Ok(Value::from(b))),
+        ])
+    };
+    let event = |data_event, b: &str| {
+        ParsedEventWithErrors::new(
+            SessionType::Snapshot,
+            data_event,
+            Some(Ok(vec![Value::Int(1)])),
+            vec![Ok(Value::Int(1)), Ok(Value::from(b))],
+        )
+        .replace_errors()
+    };
+    // Each message is coalesced on its own, the emitted rows are remembered
+    // between them
+    let contexts = [
+        ReaderContext::from_diff(DataEventType::Insert, None, row(1, "x").into()),
+        ReaderContext::from_diff(DataEventType::Delete, None, row(1, "x").into()),
+        ReaderContext::from_diff(DataEventType::Insert, None, row(1, "y").into()),
+        ReaderContext::from_diff(DataEventType::Insert, None, row(1, "y").into()),
+        ReaderContext::from_diff(DataEventType::Delete, None, row(2, "x").into()),
+    ];
+    let events: Vec<Vec<_>> = contexts
+        .iter()
+        .map(|context| {
+            parser
+                .parse(context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .map(|event| event.replace_errors())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            vec![event(DataEventType::Insert, "x")],
+            vec![event(DataEventType::Delete, "x")],
+            vec![event(DataEventType::Insert, "y")],
+            vec![],
+            vec![],
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_snapshot_batch_matches_parse() -> eyre::Result<()> {
+    let new_parser = || {
+        let schema = [
+            ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ];
+        TransparentParser::new(
+            Some(vec!["a".to_owned()]),
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.into(),
+            SessionType::Snapshot,
+        )
+    };
+    let context = |data_event, a: i64, b: &str| {
+        ReaderContext::from_diff(
+            data_event,
+            None,
+            HashMap::from([
+                ("a".to_owned(), Ok(Value::Int(a))),
+                ("b".to_owned(), Ok(Value::from(b))),
+            ])
+            .into(),
+        )
+    };
+    let contexts = [
+        context(DataEventType::Insert, 1, "x"),
+        context(DataEventType::Insert, 2, "x"),
+        context(DataEventType::Delete, 1, "x"),
+        context(DataEventType::Insert, 1, "y"),
+        context(DataEventType::Insert, 2, "x"),
+        context(DataEventType::Delete, 3, "x"),
+    ];
+
+    let mut parser = new_parser()?;
+    let mut expected = Vec::new();
+    for context in &contexts {
+        let events = parser.parse(context).expect("parsing should not fail");
+        expected.extend(events.into_iter().map(|event| event.replace_errors()));
+    }
+    let expected_stats = parser.stats();
+
+    let mut parser = new_parser()?;
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(events, expected);
+    assert_eq!(events.len(), 4);
+    assert_eq!(parser.stats(), expected_stats);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_composite_key() -> eyre::Result<()> {
+    let schema = [
+        (
+            "tenant".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "id".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(0))),
+        ),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["tenant".to_owned(), "id".to_owned()]),
+        vec!["b".to_owned()],
+        schema.into(),
+        SessionType::Upsert,
+    )?;
+    let row = |b: &str| {
+        HashMap::from([
+            ("tenant".to_owned(), Ok(Value::from("acme"))),
+            ("id".to_owned(), Ok(Value::Int(7))),
+            ("b".to_owned(), Ok(Value::from(b))),
+        ])
+    };
+    let contexts = vec![
+        ReaderContext::from_diff(DataEventType::Insert, None, row("x").into()),
+        ReaderContext::from_diff(DataEventType::Delete, None, row("x").into()),
+    ];
+    let mut events = Vec::new();
+    for context in contexts {
+        events.extend(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .map(|event| event.replace_errors()),
+        );
+    }
+    let key = Some(vec![Value::from("acme"), Value::Int(7)]);
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((key.clone(), vec![Value::from("x")])),
+            ParsedEvent::Delete((key, vec![])),
+        ]
+    );
+
+    // The key field with a default is still required to be present
+    let values = HashMap::from([
+        ("tenant".to_owned(), Ok(Value::from("acme"))),
+        ("b".to_owned(), Ok(Value::from("x"))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?;
+    assert!(matches!(
+        event,
+        ParsedEventWithErrors::Insert((Some(Err(_)), _))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_datetime_utc() -> eyre::Result<()> {
+    let default = DateTimeUtc::new(0);
+    let schema = [(
+        "t".to_owned(),
+        InnerSchemaField::new(Type::DateTimeUtc, Some(Value::DateTimeUtc(default))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["t".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([("t".to_owned(), Ok(Value::from("2024-01-02T03:04:05Z")))]),
+        HashMap::new(),
+        HashMap::from([("t".to_owned(), Ok(Value::from("not a date")))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::DateTimeUtc(DateTimeUtc::new(
+                1_704_164_645_000_000_000,
+            ))],
+        )),
+        ParsedEvent::Insert((None, vec![Value::DateTimeUtc(default)])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_datetime_utc_timezones() -> eyre::Result<()> {
+    let make_parser = |default_timezone: Option<&str>| {
+        let schema = [(
+            "t".to_owned(),
+            InnerSchemaField::new(Type::DateTimeUtc, None),
+        )];
+        TransparentParser::new(
+            None,
+            vec!["t".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+        .map(|parser| {
+            parser.with_coercion_options(CoercionOptions {
+                default_timezone: default_timezone.map(str::to_owned),
+                ..Default::default()
+            })
+        })
+    };
+    let cases = [
+        (
+            None,
+            "2024-01-02T03:04:05+05:30",
+            Value::DateTimeUtc(DateTimeUtc::new(1_704_144_845_000_000_000)),
+        ),
+        (None, "2024-01-02T03:04:05", Value::Error),
+        (
+            Some("America/New_York"),
+            "2024-01-02T03:04:05",
+            Value::DateTimeUtc(DateTimeUtc::new(1_704_182_645_000_000_000)),
+        ),
+        (
+            Some("America/New_York"),
+            "2024-01-02T03:04:05Z",
+            Value::DateTimeUtc(DateTimeUtc::new(1_704_164_645_000_000_000)),
+        ),
+    ];
+    for (default_timezone, raw, expected) in cases {
+        let mut parser = make_parser(default_timezone)?;
+        let context = ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("t".to_owned(), Ok(Value::from(raw)))]).into(),
+        );
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            ParsedEvent::Insert((None, vec![expected]))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_epoch_timestamps() -> eyre::Result<()> {
+    let make_parser = |unit| {
+        let schema = [(
+            "t".to_owned(),
+            InnerSchemaField::new(Type::DateTimeUtc, None)
+                .with_epoch_timestamps(EpochTimestamps::new(unit)),
+        )];
+        TransparentParser::new(
+            None,
+            vec!["t".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+    };
+    let instant = Value::DateTimeUtc(DateTimeUtc::new(1_700_000_000_000_000_000));
+    let cases = [
+        (
+            EpochUnit::Seconds,
+            Value::Int(1_700_000_000),
+            instant.clone(),
+        ),
+        (
+            EpochUnit::Milliseconds,
+            Value::Int(1_700_000_000_000),
+            instant.clone(),
+        ),
+        (
+            EpochUnit::Microseconds,
+            Value::from("1700000000000000"),
+            instant,
+        ),
+        // Milliseconds read as seconds are far in the future
+        (
+            EpochUnit::Seconds,
+            Value::Int(1_700_000_000_000),
+            Value::Error,
+        ),
+        (EpochUnit::Milliseconds, Value::Int(-1), Value::Error),
+    ];
+    for (unit, raw, expected) in cases {
+        let mut parser = make_parser(unit)?;
+        let context = ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("t".to_owned(), Ok(raw))]).into(),
+        );
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            ParsedEvent::Insert((None, vec![expected]))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_dedup_parser() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let inner = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let mut parser = DedupParser::new(Box::new(inner));
+    let row = |b: &str| {
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(1))),
+            ("b".to_owned(), Ok(Value::from(b))),
+        ])
+    };
+    let contexts = vec![
+        ReaderContext::from_diff(DataEventType::Insert, None, row("x").into()),
+        ReaderContext::from_diff(DataEventType::Insert, None, row("y").into()),
+        ReaderContext::from_diff(DataEventType::Delete, None, row("x").into()),
+        ReaderContext::from_diff(DataEventType::Insert, None, row("z").into()),
+    ];
+    let mut events = Vec::new();
+    for context in contexts {
+        events.extend(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .map(|event| event.replace_errors()),
+        );
+    }
+    let key = Some(vec![Value::Int(1)]);
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((key.clone(), vec![Value::Int(1), Value::from("x")])),
+            ParsedEvent::Delete((key.clone(), vec![Value::Int(1), Value::from("x")])),
+            ParsedEvent::Insert((key, vec![Value::Int(1), Value::from("z")])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dedup_parser_ttl() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let new_inner = || {
+        TransparentParser::new(
+            Some(vec!["a".to_owned()]),
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.clone().into(),
+            SessionType::Native,
+        )
+    };
+    let context = |event: DataEventType, a: i64, b: &str| {
+        let values = HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(a))),
+            ("b".to_owned(), Ok(Value::from(b))),
+        ]);
+        ReaderContext::from_diff(event, None, values.into())
+    };
+
+    let mut parser = DedupParser::new(Box::new(new_inner()?)).with_ttl(DedupTtl::Events(2));
+    let steps = [
+        (context(DataEventType::Insert, 1, "x"), true, 1),
+        (context(DataEventType::Insert, 1, "y"), false, 1),
+        // The second insertion after the first one makes it expire
+        (context(DataEventType::Insert, 2, "x"), true, 1),
+        (context(DataEventType::Insert, 1, "z"), true, 2),
+        // A deletion doesn't wait for the TTL
+        (context(DataEventType::Delete, 1, "z"), true, 1),
+    ];
+    for (context, passes, seen_keys_count) in steps {
+        let events = parser.parse(&context).expect("parsing should not fail");
+        assert_eq!(events.len(), usize::from(passes));
+        assert_eq!(parser.seen_keys_count(), seen_keys_count);
+    }
+
+    let mut parser = DedupParser::new(Box::new(new_inner()?))
+        .with_ttl(DedupTtl::Time(Duration::from_millis(50)));
+    let insertion = context(DataEventType::Insert, 1, "x");
+    assert_eq!(
+        parser
+            .parse(&insertion)
+            .expect("parsing should not fail")
+            .len(),
+        1
+    );
+    assert!(parser
+        .parse(&insertion)
+        .expect("parsing should not fail")
+        .is_empty());
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(
+        parser
+            .parse(&insertion)
+            .expect("parsing should not fail")
+            .len(),
+        1
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dedup_parser_reset() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let inner = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let mut parser = DedupParser::new(Box::new(inner));
+    let values = HashMap::from([
+        ("a".to_owned(), Ok(Value::Int(1))),
+        ("b".to_owned(), Ok(Value::from("x"))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    let key = Some(vec![Value::Int(1)]);
+    let expected = ParsedEvent::Insert((key, vec![Value::Int(1), Value::from("x")]));
+
+    let events = parser.parse(&context).expect("parsing should not fail");
+    assert_eq!(events.into_iter().exactly_one()?.replace_errors(), expected);
+    assert!(parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .is_empty());
+
+    parser.reset();
+    assert_eq!(parser.column_count(), 2);
+    let events = parser.parse(&context).expect("parsing should not fail");
+    assert_eq!(events.into_iter().exactly_one()?.replace_errors(), expected);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_list() -> eyre::Result<()> {
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(
+            Type::List(Type::Int.into()),
+            Some(Value::from(Vec::<Value>::new())),
+        ),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([(
+            "a".to_owned(),
+            Ok(Value::from(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::from("bad"),
+            ])),
+        )]),
+        HashMap::new(),
+        HashMap::from([("a".to_owned(), Ok(Value::Int(1)))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Error,
+            ])],
+        )),
+        ParsedEvent::Insert((None, vec![Value::from(Vec::<Value>::new())])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_delimited_list() -> eyre::Result<()> {
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::List(Type::Int.into()), None),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_delimited_list("a", DelimitedList::new(";"))?;
+    let contexts = ["1;2;x", ""];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Error,
+            ])],
+        )),
+        ParsedEvent::Insert((None, vec![Value::from(Vec::<Value>::new())])),
+    ];
+    for (raw, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let values = HashMap::from([("a".to_owned(), Ok(Value::from(raw)))]);
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [("b".to_owned(), InnerSchemaField::new(Type::String, None))];
+    let parser = TransparentParser::new(
+        None,
+        vec!["b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    assert!(parser
+        .with_delimited_list("b", DelimitedList::new(";"))
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_upsert_events() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Upsert,
+    )?
+    .with_upsert_events();
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(3))),
+            ("b".to_owned(), Ok(Value::from("abc"))),
+        ])
+        .into(),
+    );
+    assert_eq!(
+        parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors(),
+        ParsedEvent::Upsert((
+            Some(vec![Value::Int(3)]),
+            vec![Value::Int(3), Value::from("abc")]
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_json() -> eyre::Result<()> {
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Json, Some(Value::from(json!({"default": true})))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([(
+            "a".to_owned(),
+            Ok(Value::from(json!({"b": [1, {"c": null}]}))),
+        )]),
+        HashMap::from([("a".to_owned(), Ok(Value::from(json!([1, "x", null]))))]),
+        HashMap::from([("a".to_owned(), Ok(Value::Int(5)))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::from(json!({"b": [1, {"c": null}]}))])),
+        ParsedEvent::Insert((None, vec![Value::from(json!([1, "x", null]))])),
+        ParsedEvent::Insert((None, vec![Value::from(json!(5))])),
+        ParsedEvent::Insert((None, vec![Value::from(json!({"default": true}))])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_field_diagnostics() -> eyre::Result<()> {
+    let value_field_names = vec!["a".to_owned(), "b".to_owned()];
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        value_field_names.clone(),
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([("a".to_owned(), Ok(Value::from("x")))]).into(),
+    );
+    let event = parser
+        .parse(&context)
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?;
+    assert_eq!(
+        event.field_diagnostics(&value_field_names),
+        vec![
+            FieldDiagnostic {
+                field_name: "a".to_owned(),
+                reason: "expected int, got String".to_owned(),
+            },
+            FieldDiagnostic {
+                field_name: "b".to_owned(),
+                reason: "no value for \"b\" field and no default specified".to_owned(),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_autoincrement_key() -> eyre::Result<()> {
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_auto_key(AutoKey::Autoincrement);
+    let row = |a: i64| HashMap::from([("a".to_owned(), Ok(Value::Int(a)))]).into();
+    let contexts = vec![
+        ReaderContext::from_diff(DataEventType::Insert, None, row(10)),
+        ReaderContext::from_diff(DataEventType::Insert, None, row(20)),
+        ReaderContext::from_diff(DataEventType::Delete, None, row(10)),
+        ReaderContext::from_diff(DataEventType::Insert, None, row(30)),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((Some(vec![Value::Int(0)]), vec![Value::Int(10)])),
+        ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![Value::Int(20)])),
+        ParsedEvent::Delete((None, vec![Value::Int(10)])),
+        ParsedEvent::Insert((Some(vec![Value::Int(2)]), vec![Value::Int(30)])),
+    ];
+    for (context_i, expected_i) in contexts.into_iter().zip_eq(expected) {
+        assert_eq!(
+            parser
+                .parse(&context_i)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_bytes() -> eyre::Result<()> {
+    let schema = [
+        (
+            "raw".to_owned(),
+            InnerSchemaField::new(
+                Type::Bytes,
+                Some(Value::Bytes(b"default".as_slice().into())),
+            ),
+        ),
+        (
+            "encoded".to_owned(),
+            InnerSchemaField::new(Type::Bytes, None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["raw".to_owned(), "encoded".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        base64_bytes: true,
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([
+            (
+                "raw".to_owned(),
+                Ok(Value::Bytes([0, 1, 255].as_slice().into())),
+            ),
+            ("encoded".to_owned(), Ok(Value::from("aGVsbG8="))),
+        ]),
+        HashMap::from([("encoded".to_owned(), Ok(Value::from("not base64!")))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::Bytes([0, 1, 255].as_slice().into()),
+                Value::Bytes(b"hello".as_slice().into()),
+            ],
+        )),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Bytes(b"default".as_slice().into()), Value::Error],
+        )),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_hex_bytes() -> eyre::Result<()> {
+    let new_parser = |default: Option<Value>| {
+        let schema = [(
+            "a".to_owned(),
+            InnerSchemaField::new(Type::Bytes, default).with_bytes_encoding(BytesEncoding::Hex),
+        )];
+        TransparentParser::new(
+            None,
+            vec!["a".to_owned()],
+            schema.into(),
+            SessionType::Native,
+        )
+    };
+    let mut parser = new_parser(Some(Value::from("00ff")))?;
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("DEADBEEF")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("deadbeef")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("xyz")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Bytes([0xde, 0xad, 0xbe, 0xef].as_slice().into())],
+        )),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Bytes([0xde, 0xad, 0xbe, 0xef].as_slice().into())],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Bytes([0, 255].as_slice().into())])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    assert!(new_parser(Some(Value::from("abc"))).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_numeric_strings() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::Int.into()), Some(Value::None)),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        numeric_strings: true,
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("42")))]),
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from(" 7 "))),
+            ("b".to_owned(), Ok(Value::from(""))),
+        ]),
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from("x"))),
+            ("b".to_owned(), Ok(Value::Int(5))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::Int(42), Value::None])),
+        ParsedEvent::Insert((None, vec![Value::Int(7), Value::None])),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Int(5)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_float_strings() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Float, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Float, Some(Value::Float(0.0.into()))),
+        ),
+    ];
+    let values = |a: &str| HashMap::from([("a".to_owned(), Ok(Value::from(a)))]);
+    let contexts = [
+        values("2.5"),
+        values("1e-3"),
+        values("abc"),
+        values("Infinity"),
+    ];
+    for (finite_floats, expected) in [
+        (
+            false,
+            vec![
+                Value::Float(2.5.into()),
+                Value::Float(1e-3.into()),
+                Value::Error,
+                Value::Float(f64::INFINITY.into()),
+            ],
+        ),
+        (
+            true,
+            vec![
+                Value::Float(2.5.into()),
+                Value::Float(1e-3.into()),
+                Value::Error,
+                Value::Error,
+            ],
+        ),
+    ] {
+        let mut parser = TransparentParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned()],
+            schema.clone().into(),
+            SessionType::Native,
+        )?
+        .with_coercion_options(CoercionOptions {
+            numeric_strings: true,
+            finite_floats,
+            ..Default::default()
+        });
+        for (values, expected_i) in contexts.iter().cloned().zip_eq(expected) {
+            let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+            assert_eq!(
+                parser
+                    .parse(&context)
+                    .expect("parsing should not fail")
+                    .into_iter()
+                    .exactly_one()?
+                    .replace_errors(),
+                ParsedEvent::Insert((None, vec![expected_i, Value::Float(0.0.into())]))
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_null_sentinels() -> eyre::Result<()> {
+    let schema = [
+        (
+            "a".to_owned(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::Int, Some(Value::Int(0))),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        null_sentinels: vec!["NULL".to_owned(), "\\N".to_owned()],
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from("\\N"))),
+            ("b".to_owned(), Ok(Value::Int(1))),
+        ]),
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from("NULL"))),
+            ("b".to_owned(), Ok(Value::from("NULL"))),
+        ]),
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from(" NULL"))),
+            ("b".to_owned(), Ok(Value::Int(2))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::None, Value::Int(1)])),
+        ParsedEvent::Insert((None, vec![Value::None, Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::from(" NULL"), Value::Int(2)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_bool_tokens() -> eyre::Result<()> {
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Bool, Some(Value::Bool(false))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        bool_tokens: Some(BoolTokens::default()),
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("yes")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("0")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("TRUE")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::Bool(true)))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("maybe")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::Bool(true)])),
+        ParsedEvent::Insert((None, vec![Value::Bool(false)])),
+        ParsedEvent::Insert((None, vec![Value::Bool(true)])),
+        ParsedEvent::Insert((None, vec![Value::Bool(true)])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Bool(false)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_masking() -> eyre::Result<()> {
+    let value_field_names = vec!["email".to_owned(), "card".to_owned(), "ssn".to_owned()];
+    let schema = value_field_names
+        .iter()
+        .map(|name| (name.clone(), InnerSchemaField::new(Type::String, None)))
+        .collect();
+    let mut parser =
+        TransparentParser::new(None, value_field_names.clone(), schema, SessionType::Native)?
+            .with_masking("email", Masking::HashSha256)?
+            .with_masking("card", Masking::PartialLast4)?
+            .with_masking("ssn", Masking::Redact("***".to_owned()))?;
+    let contexts = vec![
+        HashMap::from([
+            ("email".to_owned(), Ok(Value::from("alice@example.com"))),
+            ("card".to_owned(), Ok(Value::from("4111111111111111"))),
+            ("ssn".to_owned(), Ok(Value::from("078-05-1120"))),
+        ]),
+        HashMap::from([
+            ("email".to_owned(), Ok(Value::Int(1))),
+            ("card".to_owned(), Ok(Value::from("123"))),
+            ("ssn".to_owned(), Ok(Value::from(""))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::from("ff8d9819fc0e12bf0d24892e45987e249a28dce836a85cad60e28eaaa8c6d976"),
+                Value::from("1111"),
+                Value::from("***"),
+            ],
+        )),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Error, Value::from("123"), Value::from("***")],
+        )),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+    let parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    assert!(parser.with_masking("a", Masking::HashSha256).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_constraints() -> eyre::Result<()> {
+    let schema = [
+        (
+            "age".to_owned(),
+            InnerSchemaField::new(Type::Int, None).with_constraints(FieldConstraints {
+                min: Some(0.0),
+                max: Some(150.0),
+                ..Default::default()
+            }),
+        ),
+        (
+            "code".to_owned(),
+            InnerSchemaField::new(Type::String, None).with_constraints(FieldConstraints {
+                min_length: Some(3),
+                max_length: Some(3),
+                ..Default::default()
+            }),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["age".to_owned(), "code".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([
+            ("age".to_owned(), Ok(Value::Int(30))),
+            ("code".to_owned(), Ok(Value::from("abc"))),
+        ]),
+        HashMap::from([
+            ("age".to_owned(), Ok(Value::Int(200))),
+            ("code".to_owned(), Ok(Value::from("ab"))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::Int(30), Value::from("abc")])),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [(
+        "age".to_owned(),
+        InnerSchemaField::new(Type::Int, Some(Value::Int(-1))).with_constraints(FieldConstraints {
+            min: Some(0.0),
+            ..Default::default()
+        }),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["age".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_date() -> eyre::Result<()> {
+    let default = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Date, Some(Value::Date(default))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        date_formats: vec!["%d.%m.%Y".to_owned()],
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("2024-02-29")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("2023-02-29")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("2024-13-40")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("31.12.2023")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Date(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap())],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Date(default)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_nested_optional() -> eyre::Result<()> {
+    let nested = |type_: Type| Type::Optional(Type::Optional(type_.into()).into());
+    let schema = [
+        (
+            "a".to_owned(),
+            InnerSchemaField::new(nested(Type::String), Some(Value::None)),
+        ),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(nested(Type::Int), None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        numeric_strings: true,
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([("b".to_owned(), Ok(Value::from("")))]),
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::from("abc"))),
+            ("b".to_owned(), Ok(Value::from("5"))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::None, Value::None])),
+        ParsedEvent::Insert((None, vec![Value::from("abc"), Value::Int(5)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_json_pointers() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "c".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("default"))),
+        ),
+        ("sku".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "c".to_owned(), "sku".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_json_pointers(HashMap::from([
+        ("a".to_owned(), "/a/b".to_owned()),
+        ("c".to_owned(), "/a/c".to_owned()),
+        ("sku".to_owned(), "/a/items/1/sku".to_owned()),
+    ]))?;
+    let contexts = vec![
+        HashMap::from([(
+            "a".to_owned(),
+            Ok(Value::from(
+                json!({"b": 1, "items": [{"sku": "x"}, {"sku": "y"}]}),
+            )),
+        )]),
+        HashMap::from([(
+            "a".to_owned(),
+            Ok(Value::from(json!({"b": "text", "c": "z", "items": []}))),
+        )]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Int(1), Value::from("default"), Value::from("y")],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::from("z"), Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_flatten() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "address.city".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "address.zip".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("00000"))),
+        ),
+        (
+            "address.lines".to_owned(),
+            InnerSchemaField::new(Type::Json, None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec![
+            "id".to_owned(),
+            "address.city".to_owned(),
+            "address.zip".to_owned(),
+            "address.lines".to_owned(),
+        ],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_flatten()?;
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([
+            ("id".to_owned(), Ok(Value::Int(1))),
+            (
+                "address".to_owned(),
+                Ok(Value::from(json!({
+                    "city": "Warsaw",
+                    "lines": ["Main St 1", "apt 2"],
+                }))),
+            ),
+        ])
+        .into(),
+    );
+    assert_eq!(
+        parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors(),
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::Int(1),
+                Value::from("Warsaw"),
+                Value::from("00000"),
+                Value::from(json!(["Main St 1", "apt 2"])),
+            ]
+        ))
+    );
+
+    let colliding_schema = [
+        (
+            "address".to_owned(),
+            InnerSchemaField::new(Type::Json, None),
+        ),
+        (
+            "address.city".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+    ];
+    assert!(TransparentParser::new(
+        None,
+        vec!["address".to_owned(), "address.city".to_owned()],
+        colliding_schema.into(),
+        SessionType::Native,
+    )?
+    .with_flatten()
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_coercion_hooks() -> eyre::Result<()> {
+    let schema = [
+        ("code".to_owned(), InnerSchemaField::new(Type::String, None)),
+        (
+            "plate".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "other".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["code".to_owned(), "plate".to_owned(), "other".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_hook("code", |value| {
+        let code = value.as_string().map_err(|e| e.to_string())?;
+        Ok(Value::from(code.to_uppercase().as_str()))
+    })?
+    .with_coercion_hook("plate", |value| match value {
+        Value::String(s) if s.len() >= 4 => Ok(value.clone()),
+        _ => Err("plate is too short".to_owned()),
+    })?;
+    let contexts = vec![
+        HashMap::from([
+            ("code".to_owned(), Ok(Value::from("abc"))),
+            ("plate".to_owned(), Ok(Value::from("XY12"))),
+            ("other".to_owned(), Ok(Value::from("abc"))),
+        ]),
+        HashMap::from([
+            ("code".to_owned(), Ok(Value::Int(1))),
+            ("plate".to_owned(), Ok(Value::from("XY1"))),
+            ("other".to_owned(), Ok(Value::from("def"))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from("ABC"), Value::from("XY12"), Value::from("abc")],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error, Value::from("def")])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_derived_field() -> eyre::Result<()> {
+    let schema = [
+        (
+            "first".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        ("last".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["first".to_owned(), "last".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_derived_field("full_name", Type::String, |values| match values {
+        [Value::String(first), Value::String(last)] => {
+            Ok(Value::from(format!("{first} {last}").as_str()))
+        }
+        _ => Err("a name is missing".to_owned()),
+    })?;
+    assert_eq!(parser.column_count(), 3);
+    let contexts = vec![
+        HashMap::from([
+            ("first".to_owned(), Ok(Value::from("Ada"))),
+            ("last".to_owned(), Ok(Value::from("Lovelace"))),
+        ]),
+        HashMap::from([("first".to_owned(), Ok(Value::from("Ada")))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::from("Ada"),
+                Value::from("Lovelace"),
+                Value::from("Ada Lovelace"),
+            ],
+        )),
+        ParsedEvent::Insert((None, vec![Value::from("Ada"), Value::Error, Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    assert!(parser
+        .with_derived_field("last", Type::String, |_| Ok(Value::None))
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_cached_delete_values() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        Some(vec!["a".to_owned()]),
+        vec!["a".to_owned(), "b".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_cached_delete_values();
+    let contexts = vec![
+        HashMap::from([
+            ("a".to_owned(), Ok(Value::Int(3))),
+            ("b".to_owned(), Ok(Value::from("x"))),
+        ]),
+        HashMap::from([("a".to_owned(), Ok(Value::Int(3)))]),
+        HashMap::from([("a".to_owned(), Ok(Value::Int(4)))]),
+    ];
+    let event_types = [
+        DataEventType::Insert,
+        DataEventType::Delete,
+        DataEventType::Delete,
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            Some(vec![Value::Int(3)]),
+            vec![Value::Int(3), Value::from("x")],
+        )),
+        ParsedEvent::Delete((
+            Some(vec![Value::Int(3)]),
+            vec![Value::Int(3), Value::from("x")],
+        )),
+        ParsedEvent::Delete((Some(vec![Value::Int(4)]), vec![Value::Int(4), Value::Error])),
+    ];
+    for ((values, event_type), expected_i) in contexts.into_iter().zip(event_types).zip_eq(expected)
+    {
+        let context = ReaderContext::from_diff(event_type, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_time() -> eyre::Result<()> {
+    let default = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Time, Some(Value::Time(default))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("23:59:59.5")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("14:30:00")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("25:00:00")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("12:61:00")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Time(
+                NaiveTime::from_hms_milli_opt(23, 59, 59, 500).unwrap(),
+            )],
+        )),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Time(NaiveTime::from_hms_opt(14, 30, 0).unwrap())],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Time(default)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_duration() -> eyre::Result<()> {
+    let minutes = |n| EngineDuration::new_with_unit(n, "m").unwrap();
+    let schema = [(
+        "a".to_owned(),
+        InnerSchemaField::new(Type::Duration, Some(Value::Duration(minutes(1)))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("PT1H30M")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("1h30m")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("P1DT0.5S")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("banana")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("-PT1H")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("P1M")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::Duration(minutes(90))])),
+        ParsedEvent::Insert((None, vec![Value::Duration(minutes(90))])),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Duration(
+                EngineDuration::new_with_unit(86_400_500, "ms").unwrap(),
+            )],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Duration(minutes(1))])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_duration_format() -> eyre::Result<()> {
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Duration, None))];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        duration_format: DurationFormat::Iso8601,
+        ..CoercionOptions::default()
+    });
+    let contexts = vec![
+        HashMap::from([("a".to_owned(), Ok(Value::from("PT90M")))]),
+        HashMap::from([("a".to_owned(), Ok(Value::from("90m")))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Duration(
+                EngineDuration::new_with_unit(90, "m").unwrap(),
+            )],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_int32() -> eyre::Result<()> {
+    let schema = [
+        (
+            "narrow".to_owned(),
+            InnerSchemaField::new(Type::Int32, None),
+        ),
+        ("wide".to_owned(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["narrow".to_owned(), "wide".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_coercion_options(CoercionOptions {
+        numeric_strings: true,
+        ..Default::default()
+    });
+    let contexts = vec![
+        HashMap::from([
+            ("narrow".to_owned(), Ok(Value::from("2147483647"))),
+            ("wide".to_owned(), Ok(Value::from("2147483647"))),
+        ]),
+        HashMap::from([
+            ("narrow".to_owned(), Ok(Value::from("3000000000"))),
+            ("wide".to_owned(), Ok(Value::from("3000000000"))),
+        ]),
+        HashMap::from([
+            ("narrow".to_owned(), Ok(Value::Int(-3_000_000_000))),
+            ("wide".to_owned(), Ok(Value::Int(-3_000_000_000))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Int(2_147_483_647), Value::Int(2_147_483_647)],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Int(3_000_000_000)])),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Int(-3_000_000_000)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [(
+        "narrow".to_owned(),
+        InnerSchemaField::new(Type::Int32, Some(Value::Int(3_000_000_000))),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["narrow".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_int128() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int128, None)),
+        (
+            "count".to_owned(),
+            InnerSchemaField::new(Type::Int128, Some(Value::Int128(0))),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["id".to_owned(), "count".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([
+            (
+                "id".to_owned(),
+                Ok(Value::from("123456789012345678901234567890")),
+            ),
+            ("count".to_owned(), Ok(Value::Int(5))),
+        ]),
+        HashMap::from([(
+            "id".to_owned(),
+            Ok(Value::from(
+                "12345678901234567890123456789012345678901234567890",
+            )),
+        )]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::Int128(123_456_789_012_345_678_901_234_567_890),
+                Value::Int128(5),
+            ],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Int128(0)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_fixed_string() -> eyre::Result<()> {
+    let fixed_string = |align| Type::FixedString {
+        len: 5,
+        pad: ' ',
+        align,
+    };
+    let schema = [
+        (
+            "code".to_owned(),
+            InnerSchemaField::new(fixed_string(FixedStringAlign::Left), None),
+        ),
+        (
+            "amount".to_owned(),
+            InnerSchemaField::new(
+                Type::FixedString {
+                    len: 4,
+                    pad: '0',
+                    align: FixedStringAlign::Right,
+                },
+                Some(Value::from("0")),
+            ),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["code".to_owned(), "amount".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([
+            ("code".to_owned(), Ok(Value::from("AB   "))),
+            ("amount".to_owned(), Ok(Value::from("0042"))),
+        ]),
+        HashMap::from([("code".to_owned(), Ok(Value::from("ABCDEFG")))]),
+        HashMap::from([
+            ("code".to_owned(), Ok(Value::from("     "))),
+            ("amount".to_owned(), Ok(Value::from("0000"))),
+        ]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::from("AB"), Value::from("42")])),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::from("0")])),
+        ParsedEvent::Insert((None, vec![Value::from(" "), Value::from("0")])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [(
+        "code".to_owned(),
+        InnerSchemaField::new(
+            fixed_string(FixedStringAlign::Left),
+            Some(Value::from("TOO LONG")),
+        ),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["code".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_fixed_array() -> eyre::Result<()> {
+    let vector = Type::FixedArray {
+        element: Type::Float.into(),
+        len: 3,
+    };
+    let floats = |values: &[f64]| {
+        Value::from(
+            values
+                .iter()
+                .map(|value| Value::from(*value))
+                .collect::<Vec<_>>(),
+        )
+    };
+    let schema = [(
+        "embedding".to_owned(),
+        InnerSchemaField::new(vector.clone(), None),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["embedding".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        floats(&[0.1, 0.2, 0.3]),
+        floats(&[0.1, 0.2]),
+        Value::from(vec![Value::from(0.1), Value::from("x"), Value::from(0.3)]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![floats(&[0.1, 0.2, 0.3])])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from(vec![
+                Value::from(0.1),
+                Value::Error,
+                Value::from(0.3),
+            ])],
+        )),
+    ];
+    for (value, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("embedding".to_owned(), Ok(value))]).into(),
+        );
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let schema = [(
+        "embedding".to_owned(),
+        InnerSchemaField::new(vector, Some(floats(&[0.0, 0.0]))),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["embedding".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_tuple() -> eyre::Result<()> {
+    let labelled_point = Type::Tuple([Type::Int, Type::String, Type::Float].into());
+    let schema = [(
+        "point".to_owned(),
+        InnerSchemaField::new(labelled_point.clone(), None),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["point".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        Value::from(json!([1, "x", 2.5])),
+        Value::from(json!([1, "x"])),
+        Value::from(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+        Value::from("x"),
+    ];
+    let expected = vec![
+        Value::from(vec![Value::Int(1), Value::from("x"), Value::from(2.5)]),
+        Value::Error,
+        Value::from(vec![Value::Int(1), Value::Error, Value::from(3.0)]),
+        Value::Error,
+    ];
+    for (value, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([("point".to_owned(), Ok(value))]).into(),
+        );
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            ParsedEvent::Insert((None, vec![expected_i]))
+        );
+    }
+
+    let schema = [(
+        "point".to_owned(),
+        InnerSchemaField::new(
+            labelled_point,
+            Some(Value::from(vec![Value::Int(0), Value::from("")])),
+        ),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["point".to_owned()],
+        schema.into(),
+        SessionType::Native
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_uuid() -> eyre::Result<()> {
+    let default = Uuid::nil();
+    let schema = [(
+        "id".to_owned(),
+        InnerSchemaField::new(Type::Uuid, Some(Value::Uuid(default))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["id".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let expected_uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+    let contexts = vec![
+        HashMap::from([(
+            "id".to_owned(),
+            Ok(Value::from("67E55044-10B1-426F-9247-BB680E5FE0C8")),
+        )]),
+        HashMap::from([(
+            "id".to_owned(),
+            Ok(Value::from("67e5504410b1426f9247bb680e5fe0c8")),
+        )]),
+        HashMap::from([("id".to_owned(), Ok(Value::from("67e5504410b1")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::Uuid(expected_uuid)])),
+        ParsedEvent::Insert((None, vec![Value::Uuid(expected_uuid)])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::Uuid(default)])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_map() -> eyre::Result<()> {
+    let schema = [
+        (
+            "attributes".to_owned(),
+            InnerSchemaField::new(
+                Type::Map(Type::String.into()),
+                Some(Value::from(BTreeMap::new())),
+            ),
+        ),
+        (
+            "sizes".to_owned(),
+            InnerSchemaField::new(Type::Map(Type::Int.into()), None),
+        ),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["attributes".to_owned(), "sizes".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([
+            ("attributes".to_owned(), Ok(Value::from(json!({"a": "x"})))),
+            (
+                "sizes".to_owned(),
+                Ok(Value::from(BTreeMap::from([
+                    ("s".into(), Value::Int(1)),
+                    ("m".into(), Value::from("two")),
+                ]))),
+            ),
+        ]),
+        HashMap::from([("sizes".to_owned(), Ok(Value::from("not a map")))]),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![
+                Value::from(BTreeMap::from([("a".into(), Value::from("x"))])),
+                Value::from(BTreeMap::from([
+                    ("s".into(), Value::Int(1)),
+                    ("m".into(), Value::Error),
+                ])),
+            ],
+        )),
+        ParsedEvent::Insert((None, vec![Value::from(BTreeMap::new()), Value::Error])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_renames() -> eyre::Result<()> {
+    let schema = [
+        (
+            "firstName".to_owned(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "lastName".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("unknown"))),
+        ),
+        ("age".to_owned(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let mut parser = TransparentParser::new(
+        None,
+        vec![
+            "firstName".to_owned(),
+            "lastName".to_owned(),
+            "age".to_owned(),
+        ],
+        schema.into(),
+        SessionType::Native,
+    )?
+    .with_renames(HashMap::from([
+        ("first_name".to_owned(), "firstName".to_owned()),
+        ("last_name".to_owned(), "lastName".to_owned()),
+    ]))?;
+    let values = HashMap::from([
+        ("first_name".to_owned(), Ok(Value::from("Ada"))),
+        ("age".to_owned(), Ok(Value::Int(36))),
+    ]);
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+    assert_eq!(
+        parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors(),
+        ParsedEvent::Insert((
+            None,
+            vec![Value::from("Ada"), Value::from("unknown"), Value::Int(36)]
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_rename_to_unknown_field() -> eyre::Result<()> {
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+    let parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    assert!(parser
+        .with_renames(HashMap::from([("x".to_owned(), "b".to_owned())]))
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_explode_parser() -> eyre::Result<()> {
+    let schema = [
+        (
+            "batch_id".to_owned(),
+            InnerSchemaField::new(Type::Int, None),
+        ),
+        (
+            "items".to_owned(),
+            InnerSchemaField::new(Type::List(Type::Int.into()), None),
+        ),
+    ];
+    let value_field_names = vec!["batch_id".to_owned(), "items".to_owned()];
+    let inner = TransparentParser::new(
+        None,
+        value_field_names.clone(),
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let mut parser = ExplodeParser::new(Box::new(inner), &value_field_names, "items")?;
+    let contexts = [
+        HashMap::from([
+            ("batch_id".to_owned(), Ok(Value::Int(7))),
+            (
+                "items".to_owned(),
+                Ok(Value::from(vec![
+                    Value::Int(1),
+                    Value::from("bad"),
+                    Value::Int(3),
+                ])),
+            ),
+        ]),
+        HashMap::from([
+            ("batch_id".to_owned(), Ok(Value::Int(8))),
+            ("items".to_owned(), Ok(Value::Int(4))),
+        ]),
+    ];
+    let mut events = Vec::new();
+    for values in contexts {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        events.extend(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .map(|event| event.replace_errors()),
+        );
+    }
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(7), Value::Int(1)])),
+            ParsedEvent::Insert((None, vec![Value::Int(7), Value::Error])),
+            ParsedEvent::Insert((None, vec![Value::Int(7), Value::Int(3)])),
+            ParsedEvent::Insert((None, vec![Value::Int(8), Value::Error])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_enrich_parser() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("batch".to_owned(), InnerSchemaField::new(Type::Int, None)),
+    ];
+    let value_field_names = vec!["id".to_owned(), "batch".to_owned()];
+    let inner = TransparentParser::new(
+        None,
+        value_field_names.clone(),
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let mut parser = EnrichParser::new(
+        Box::new(inner),
+        &value_field_names,
+        vec![
+            ("batch".to_owned(), Value::Int(42)),
+            ("source".to_owned(), Value::from("kafka")),
+        ],
+    );
+    assert_eq!(parser.value_field_names(), ["id", "batch", "source"]);
+    assert_eq!(parser.column_count(), 3);
+    let values = || {
+        HashMap::from([
+            ("id".to_owned(), Ok(Value::Int(1))),
+            ("batch".to_owned(), Ok(Value::Int(5))),
+        ])
+        .into()
+    };
+    let contexts = [
+        ReaderContext::from_diff(DataEventType::Insert, None, values()),
+        ReaderContext::from_diff(DataEventType::Delete, None, values()),
+    ];
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Int(1), Value::Int(42), Value::from("kafka")]
+            )),
+            ParsedEvent::Delete((None, vec![Value::Int(1), Value::Int(5)])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sequence_parser() -> eyre::Result<()> {
+    let value_field_names = vec!["id".to_owned()];
+    let new_inner = || -> eyre::Result<Box<dyn Parser>> {
+        let schema = [("id".to_owned(), InnerSchemaField::new(Type::Int, None))];
+        Ok(Box::new(TransparentParser::new(
+            None,
+            value_field_names.clone(),
+            schema.into(),
+            SessionType::Native,
+        )?))
+    };
+    assert!(SequenceParser::new(new_inner()?, &value_field_names, "id".to_owned()).is_err());
+    let mut parser = SequenceParser::new(new_inner()?, &value_field_names, "seq".to_owned())?;
+    assert_eq!(parser.value_field_names(), ["id", "seq"]);
+    assert_eq!(parser.column_count(), 2);
+    let context = |event, id| {
+        let values = HashMap::from([("id".to_owned(), Ok(Value::Int(id)))]);
+        ReaderContext::from_diff(event, None, values.into())
+    };
+    let mut parse = |contexts: &[ReaderContext]| {
+        parser
+            .parse_batch(contexts)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|event| event.replace_errors())
+            .collect::<Vec<_>>()
+    };
+    let insert = |id, seq| ParsedEvent::Insert((None, vec![Value::Int(id), Value::Int(seq)]));
+
+    assert_eq!(
+        parse(&[
+            context(DataEventType::Insert, 5),
+            context(DataEventType::Delete, 5),
+            context(DataEventType::Insert, 6),
+        ]),
+        vec![
+            insert(5, 0),
+            ParsedEvent::Delete((None, vec![Value::Int(5)])),
+            insert(6, 1),
+        ]
+    );
+    assert_eq!(
+        parse(&[context(DataEventType::Insert, 7)]),
+        vec![insert(7, 2)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sample_parser() -> eyre::Result<()> {
+    let value_field_names = vec!["id".to_owned(), "name".to_owned()];
+    let new_parser = || {
+        let schema = [
+            ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ];
+        let inner = TransparentParser::new(
+            Some(vec!["id".to_owned()]),
+            value_field_names.clone(),
+            schema.into(),
+            SessionType::Native,
+        )?;
+        SampleParser::new(Box::new(inner), 0.5, &value_field_names, None)
+    };
+    let context = |event, id: i64| {
+        ReaderContext::from_diff(
+            event,
+            None,
+            HashMap::from([
+                ("id".to_owned(), Ok(Value::Int(id))),
+                (
+                    "name".to_owned(),
+                    Ok(Value::from(format!("row {id}").as_str())),
+                ),
+            ])
+            .into(),
+        )
+    };
+    let sampled_ids = |event| -> eyre::Result<Vec<Value>> {
+        let contexts: Vec<_> = (0..100).map(|id| context(event, id)).collect();
+        Ok(new_parser()?
+            .parse_batch(&contexts)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|event| match event.replace_errors() {
+                ParsedEvent::Insert((Some(key), _)) | ParsedEvent::Delete((Some(key), _)) => key
+                    .into_iter()
+                    .exactly_one()
+                    .expect("the key has one field"),
+                event => panic!("unexpected event: {event:?}"),
+            })
+            .collect())
+    };
+
+    let inserted = sampled_ids(DataEventType::Insert)?;
+    assert!((30..=70).contains(&inserted.len()));
+    assert_eq!(sampled_ids(DataEventType::Insert)?, inserted);
+    assert_eq!(sampled_ids(DataEventType::Delete)?, inserted);
+    Ok(())
+}
+
+struct FlakyParser {
+    failures_left: usize,
+}
+
+impl Parser for FlakyParser {
+    fn parse(&mut self, _data: &ReaderContext) -> ParseResult {
+        if self.failures_left > 0 {
+            self.failures_left -= 1;
+            return Err(ParseError::UnsupportedReaderContext.into());
+        }
+        Ok(vec![ParsedEventWithErrors::Insert((
+            None,
+            vec![Ok(Value::Int(1))],
+        ))])
+    }
+
+    fn on_new_source_started(&mut self, _metadata: &SourceMetadata) {}
+
+    fn column_count(&self) -> usize {
+        1
+    }
+}
+
+#[test]
+fn test_retrying_parser() -> eyre::Result<()> {
+    let mut parser = RetryingParser::new(
+        Box::new(FlakyParser { failures_left: 2 }),
+        3,
+        Duration::ZERO,
+    );
+    let event = parser
+        .parse(&ReaderContext::Empty)
+        .expect("the third attempt should succeed")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors();
+    assert_eq!(event, ParsedEvent::Insert((None, vec![Value::Int(1)])));
+    assert_eq!(parser.retries(), 2);
+    assert!(parser.last_error().is_some());
+    Ok(())
+}
+
+#[test]
+fn test_retrying_parser_gives_up() {
+    let mut parser = RetryingParser::new(
+        Box::new(FlakyParser { failures_left: 5 }),
+        3,
+        Duration::ZERO,
+    );
+    assert!(parser.parse(&ReaderContext::Empty).is_err());
+    assert_eq!(parser.retries(), 2);
+}
+
+#[test]
+fn test_infer_schema() -> eyre::Result<()> {
+    let records = [
+        vec![
+            ("a", Value::Int(1)),
+            ("b", Value::from("x")),
+            ("c", Value::Float(1.5.into())),
+        ],
+        vec![("a", Value::Int(2)), ("b", Value::Int(3))],
+        vec![
+            ("a", Value::Int(3)),
+            ("b", Value::from("y")),
+            ("c", Value::Int(2)),
+        ],
+    ];
+    let to_context = |record: Vec<(&str, Value)>| {
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            record
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), Ok(value)))
+                .collect::<HashMap<_, _>>()
+                .into(),
+        )
+    };
+    let samples: Vec<_> = records.into_iter().map(to_context).collect();
+    let schema = infer_schema(&samples, 10);
+    assert_eq!(schema.keys().sorted().collect_vec(), vec!["a", "b", "c"]);
+
+    // `a` is an int, `b` a string and `c` an optional float
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+        schema,
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        vec![
+            ("a", Value::Int(4)),
+            ("b", Value::from("z")),
+            ("c", Value::Int(3)),
+        ],
+        vec![("a", Value::from("4")), ("b", Value::Int(5))],
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((
+            None,
+            vec![Value::Int(4), Value::from("z"), Value::Float(3.0.into())],
+        )),
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error, Value::None])),
+    ];
+    for (record, expected_i) in contexts.into_iter().zip_eq(expected) {
+        assert_eq!(
+            parser
+                .parse(&to_context(record))
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    // In the first record alone `c` is always present
+    let schema = infer_schema(&samples, 1);
+    assert_eq!(schema.len(), 3);
+    let mut parser =
+        TransparentParser::new(None, vec!["c".to_owned()], schema, SessionType::Native)?;
+    assert_eq!(
+        parser
+            .parse(&to_context(vec![("a", Value::Int(4))]))
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors(),
+        ParsedEvent::Insert((None, vec![Value::Error]))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_first_of_parser() -> eyre::Result<()> {
+    let schema = || {
+        HashMap::from([
+            ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+            ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+        ])
+    };
+    let names = vec!["a".to_owned(), "b".to_owned()];
+    let json_parser = JsonLinesParser::new(
+        None,
+        names.clone(),
+        HashMap::new(),
+        true,
+        schema(),
+        SessionType::Native,
+    )?;
+    let csv_parser = CsvParser::new(b',', false, names, schema())?;
+    let mut parser =
+        FirstOfParser::new(vec![Box::new(json_parser), Box::new(csv_parser)]).with_dead_letters();
+    let expected = ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("abc")]));
+
+    for (payload, index) in [(&b"1,abc"[..], 1), (&br#"{"a": 1, "b": "abc"}"#[..], 0)] {
+        let context = ReaderContext::from_raw_bytes(DataEventType::Insert, payload.to_vec());
+        let event = parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors();
+        assert_eq!(event, expected);
+        assert_eq!(parser.last_success(), Some(index));
+    }
+
+    let context = ReaderContext::from_diff(DataEventType::Insert, None, HashMap::new().into());
+    assert!(parser
+        .parse(&context)
+        .expect("the failed context should become a dead letter")
+        .is_empty());
+    assert_eq!(parser.last_success(), None);
+    let (_, reason) = parser.take_dead_letters().into_iter().exactly_one()?;
+    assert_eq!(reason, ParseError::UnsupportedReaderContext.to_string());
+    Ok(())
+}
+
+#[test]
+fn test_decompressing_parser() -> eyre::Result<()> {
+    let schema = HashMap::from([
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ]);
+    let new_parser = |codec| -> eyre::Result<DecompressingParser> {
+        let inner = JsonLinesParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned()],
+            HashMap::new(),
+            true,
+            schema.clone(),
+            SessionType::Native,
+        )?;
+        Ok(DecompressingParser::new(Box::new(inner), codec))
+    };
+    let payload = br#"{"a": 1, "b": "abc"}"#;
+    let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+    encoder.write_all(payload)?;
+    let gzipped = encoder.finish()?;
+    let zstd_compressed = zstd::encode_all(&payload[..], 0)?;
+    let uncompressed = payload.to_vec();
+    let expected = ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("abc")]));
+
+    for (codec, data) in [
+        (CompressionCodec::Gzip, &gzipped),
+        (CompressionCodec::Zstd, &zstd_compressed),
+        (CompressionCodec::Auto, &gzipped),
+        (CompressionCodec::Auto, &zstd_compressed),
+        (CompressionCodec::Auto, &uncompressed),
+    ] {
+        let context = ReaderContext::from_raw_bytes(DataEventType::Insert, data.clone());
+        let event = new_parser(codec)?
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors();
+        assert_eq!(event, expected);
+    }
+
+    let truncated = gzipped[..gzipped.len() / 2].to_vec();
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, truncated);
+    assert!(new_parser(CompressionCodec::Gzip)?.parse(&context).is_err());
+    let mut parser = new_parser(CompressionCodec::Gzip)?.with_dead_letters();
+    assert!(parser
+        .parse(&context)
+        .expect("the failed context should become a dead letter")
+        .is_empty());
+    parser.take_dead_letters().into_iter().exactly_one()?;
+    Ok(())
+}
+
+#[test]
+fn test_length_prefixed_parser() -> eyre::Result<()> {
+    let schema = HashMap::from([
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ]);
+    let inner = JsonLinesParser::new(
+        None,
+        vec!["a".to_owned(), "b".to_owned()],
+        HashMap::new(),
+        true,
+        schema,
+        SessionType::Native,
+    )?;
+    let mut parser = LengthPrefixedParser::new(Box::new(inner), 64).with_dead_letters();
+    let mut stream = Vec::new();
+    for record in [&br#"{"a": 1, "b": "abc"}"#[..], br#"{"a": 2, "b": "de"}"#] {
+        stream.extend(u32::try_from(record.len())?.to_be_bytes());
+        stream.extend(record);
+    }
+    // The first chunk ends inside the first record, the second one inside the
+    // length of the second record
+    let mut events = Vec::new();
+    for chunk in [&stream[..10], &stream[10..26], &stream[26..]] {
+        let context = ReaderContext::from_raw_bytes(DataEventType::Insert, chunk.to_vec());
+        events.extend(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .map(|event| event.replace_errors()),
+        );
+    }
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("abc")])),
+            ParsedEvent::Insert((None, vec![Value::Int(2), Value::from("de")])),
+        ]
+    );
+
+    let oversized = 1000_u32.to_be_bytes().to_vec();
+    let context = ReaderContext::from_raw_bytes(DataEventType::Insert, oversized);
+    assert!(parser
+        .parse(&context)
+        .expect("the failed context should become a dead letter")
+        .is_empty());
+    parser.take_dead_letters().into_iter().exactly_one()?;
+    Ok(())
+}
+
+#[test]
+fn test_throttle_parser() -> eyre::Result<()> {
+    let schema = [("a".to_owned(), InnerSchemaField::new(Type::Int, None))];
+    let inner = TransparentParser::new(
+        None,
+        vec!["a".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let mut parser = ThrottleParser::new(Box::new(inner), 2);
+    let contexts: Vec<_> = [
+        (DataEventType::Insert, 1),
+        (DataEventType::Insert, 2),
+        (DataEventType::Delete, 1),
+        (DataEventType::Insert, 3),
+        (DataEventType::Delete, 2),
+    ]
+    .into_iter()
+    .map(|(event, a)| {
+        ReaderContext::from_diff(
+            event,
+            None,
+            HashMap::from([("a".to_owned(), Ok(Value::Int(a)))]).into(),
+        )
+    })
+    .collect();
+    let mut parse = |contexts: &[ReaderContext]| -> Vec<ParsedEvent> {
+        parser
+            .parse_batch(contexts)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|e| e.replace_errors())
+            .collect()
+    };
+    assert_eq!(
+        parse(&contexts),
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(1)])),
+            ParsedEvent::Insert((None, vec![Value::Int(2)])),
+        ]
+    );
+    assert_eq!(
+        parse(&[ReaderContext::Empty]),
+        vec![
+            ParsedEvent::Delete((None, vec![Value::Int(1)])),
+            ParsedEvent::Insert((None, vec![Value::Int(3)])),
+        ]
+    );
+    assert_eq!(
+        parse(&[ReaderContext::Empty]),
+        vec![ParsedEvent::Delete((None, vec![Value::Int(2)]))]
+    );
+    assert_eq!(parse(&[ReaderContext::Empty]), vec![]);
+
+    parse(&contexts);
+    assert_eq!(parser.buffered(), 3);
+    parser.reset();
+    assert_eq!(parser.buffered(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_transparent_parser_enum() -> eyre::Result<()> {
+    let status_type = Type::Enum(["active".to_owned(), "inactive".to_owned()].into());
+    let schema = [(
+        "status".to_owned(),
+        InnerSchemaField::new(status_type.clone(), Some(Value::from("inactive"))),
+    )];
+    let mut parser = TransparentParser::new(
+        None,
+        vec!["status".to_owned()],
+        schema.into(),
+        SessionType::Native,
+    )?;
+    let contexts = vec![
+        HashMap::from([("status".to_owned(), Ok(Value::from("active")))]),
+        HashMap::from([("status".to_owned(), Ok(Value::from("frozen")))]),
+        HashMap::new(),
+    ];
+    let expected = vec![
+        ParsedEvent::Insert((None, vec![Value::from("active")])),
+        ParsedEvent::Insert((None, vec![Value::Error])),
+        ParsedEvent::Insert((None, vec![Value::from("inactive")])),
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        assert_eq!(
+            parser
+                .parse(&context)
+                .expect("parsing should not fail")
+                .into_iter()
+                .exactly_one()?
+                .replace_errors(),
+            expected_i
+        );
+    }
+
+    let invalid_schema = [(
+        "status".to_owned(),
+        InnerSchemaField::new(status_type, Some(Value::from("frozen"))),
+    )];
+    assert!(TransparentParser::new(
+        None,
+        vec!["status".to_owned()],
+        invalid_schema.into(),
+        SessionType::Native,
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_multi_schema_parser() -> eyre::Result<()> {
+    let schemas = HashMap::from([
+        (
+            "order".to_owned(),
+            (
+                vec!["id".to_owned(), "amount".to_owned()],
+                HashMap::from([
+                    ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+                    (
+                        "amount".to_owned(),
+                        InnerSchemaField::new(Type::Float, None),
+                    ),
+                ]),
+            ),
+        ),
+        (
+            "refund".to_owned(),
+            (
+                vec!["order_id".to_owned(), "reason".to_owned()],
+                HashMap::from([
+                    (
+                        "order_id".to_owned(),
+                        InnerSchemaField::new(Type::Int, None),
+                    ),
+                    (
+                        "reason".to_owned(),
+                        InnerSchemaField::new(Type::String, Some(Value::from("unknown"))),
+                    ),
+                ]),
+            ),
+        ),
+    ]);
+    let contexts = vec![
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("type".to_owned(), Ok(Value::from("order"))),
+                ("id".to_owned(), Ok(Value::Int(1))),
+                ("amount".to_owned(), Ok(Value::Float(9.5.into()))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("type".to_owned(), Ok(Value::from("refund"))),
+                ("order_id".to_owned(), Ok(Value::Int(1))),
+            ])
+            .into(),
+        ),
+        ReaderContext::from_diff(
+            DataEventType::Insert,
+            None,
+            HashMap::from([
+                ("type".to_owned(), Ok(Value::from("chargeback"))),
+                ("id".to_owned(), Ok(Value::Int(2))),
+            ])
+            .into(),
+        ),
+    ];
+
+    let mut parser =
+        MultiSchemaParser::new("type".to_owned(), schemas.clone(), SessionType::Native)?
+            .with_discriminator_in_output(true);
+    assert_eq!(parser.column_count(), 3);
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::from("order"),
+                    Value::Int(1),
+                    Value::Float(9.5.into())
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::from("refund"), Value::Int(1), Value::from("unknown")]
+            )),
+            ParsedEvent::Insert((None, vec![Value::Error, Value::Error, Value::Error])),
+        ]
+    );
+    assert_eq!(parser.stats().events, 3);
+
+    let mut parser = MultiSchemaParser::new("type".to_owned(), schemas, SessionType::Native)?
+        .with_unknown_discriminator(UnknownDiscriminator::Skip);
+    let events: Vec<_> = parser
+        .parse_batch(&contexts)
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|e| e.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(1), Value::Float(9.5.into())])),
+            ParsedEvent::Insert((None, vec![Value::Int(1), Value::from("unknown")])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parsed_event_by_key() {
+    let first = ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![Value::from("a")]));
+    let second = ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![Value::from("b")]));
+    let deletion = ParsedEvent::Delete((Some(vec![Value::Int(1)]), vec![]));
+    let other_key = ParsedEvent::Insert((Some(vec![Value::Int(2)]), vec![Value::from("a")]));
+    assert_eq!(first.raw_key(), Some([Value::Int(1)].as_slice()));
+    assert_eq!(first.clone().by_key(), second.clone().by_key());
+    assert_eq!(first.clone().by_key(), deletion.by_key());
+    assert_ne!(first.clone().by_key(), other_key.by_key());
+
+    let latest: HashMap<_, _> = [first, second.clone()]
+        .into_iter()
+        .map(|event| (event.clone().by_key(), event))
+        .collect();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[&second.clone().by_key()], second);
+}
+
+#[test]
+fn test_parsed_event_by_key_keyless() {
+    let first = ParsedEvent::Insert((None, vec![Value::from("a")]));
+    let second = ParsedEvent::Insert((None, vec![Value::from("a")]));
+    let keyed = ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![Value::from("a")]));
+    assert_eq!(first.raw_key(), None);
+    let first_by_key = first.clone().by_key();
+    assert_eq!(first_by_key, first_by_key.clone());
+    assert_ne!(first_by_key, second.clone().by_key());
+    assert_ne!(first_by_key, first.clone().by_key());
+    assert_ne!(first_by_key, keyed.clone().by_key());
+
+    let latest: HashMap<_, _> = [first, second, keyed]
+        .into_iter()
+        .map(|event| (event.clone().by_key(), event))
+        .collect();
+    assert_eq!(latest.len(), 3);
+}
+
+#[test]
+fn test_cdc_image_parser() -> eyre::Result<()> {
+    let schema = [
+        ("id".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        ("name".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let mut parser = CdcImageParser::new(
+        Some(vec!["id".to_owned()]),
+        vec!["id".to_owned(), "name".to_owned()],
+        schema.into(),
+        "before".to_owned(),
+        "after".to_owned(),
+    )?;
+    let contexts = vec![
+        HashMap::from([
+            (
+                "before".to_owned(),
+                Ok(Value::from(json!({"id": 1, "name": "old"}))),
+            ),
+            (
+                "after".to_owned(),
+                Ok(Value::from(json!({"id": 1, "name": "new"}))),
+            ),
+        ]),
+        HashMap::from([
+            ("before".to_owned(), Ok(Value::from(json!(null)))),
+            (
+                "after".to_owned(),
+                Ok(Value::from(json!({"id": 2, "name": "created"}))),
+            ),
+        ]),
+        HashMap::from([(
+            "before".to_owned(),
+            Ok(Value::from(json!({"id": 1, "name": "new"}))),
+        )]),
+    ];
+    let expected = vec![
+        vec![
+            ParsedEvent::Delete((
+                Some(vec![Value::Int(1)]),
+                vec![Value::Int(1), Value::from("old")],
+            )),
+            ParsedEvent::Insert((
+                Some(vec![Value::Int(1)]),
+                vec![Value::Int(1), Value::from("new")],
+            )),
+        ],
+        vec![ParsedEvent::Insert((
+            Some(vec![Value::Int(2)]),
+            vec![Value::Int(2), Value::from("created")],
+        ))],
+        vec![ParsedEvent::Delete((
+            Some(vec![Value::Int(1)]),
+            vec![Value::Int(1), Value::from("new")],
+        ))],
+    ];
+    for (values, expected_i) in contexts.into_iter().zip_eq(expected) {
+        let context = ReaderContext::from_diff(DataEventType::Insert, None, values.into());
+        let events: Vec<_> = parser
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .map(|e| e.replace_errors())
+            .collect();
+        assert_eq!(events, expected_i);
+    }
+
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([("after".to_owned(), Ok(Value::from("text")))]).into(),
+    );
+    assert!(parser.parse(&context).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_merge_parser() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_owned(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "b".to_owned(),
+            InnerSchemaField::new(Type::String, Some(Value::from("-"))),
+        ),
+        ("c".to_owned(), InnerSchemaField::new(Type::String, None)),
+    ];
+    let new_parser = |winner| {
+        MergeParser::new(
+            None,
+            vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+            schema.clone().into(),
+            "header".to_owned(),
+            "body".to_owned(),
+        )
+        .map(|parser| parser.with_winner(winner))
+    };
+    let record = |header: serde_json::Value, body: Option<serde_json::Value>| {
+        let mut values = HashMap::from([("header".to_owned(), Ok(Value::from(header)))]);
+        if let Some(body) = body {
+            values.insert("body".to_owned(), Ok(Value::from(body)));
+        }
+        ReaderContext::from_diff(DataEventType::Insert, None, values.into())
+    };
+    let cases = [
+        (
+            MergeWinner::Body,
+            record(json!({"a": 1, "c": "h"}), Some(json!({"b": "x", "c": "b"}))),
+            vec![Value::Int(1), Value::from("x"), Value::from("b")],
+        ),
+        (
+            MergeWinner::Header,
+            record(json!({"a": 1, "c": "h"}), Some(json!({"b": "x", "c": "b"}))),
+            vec![Value::Int(1), Value::from("x"), Value::from("h")],
+        ),
+        (
+            MergeWinner::Body,
+            record(json!({"a": 1, "c": "h"}), Some(json!({"b": "x", "c": 5}))),
+            vec![Value::Int(1), Value::from("x"), Value::Error],
+        ),
+        (
+            MergeWinner::Body,
+            record(json!({"a": 1, "c": "h"}), None),
+            vec![Value::Int(1), Value::from("-"), Value::from("h")],
+        ),
+    ];
+    for (winner, context, expected) in cases {
+        let event = new_parser(winner)?
+            .parse(&context)
+            .expect("parsing should not fail")
+            .into_iter()
+            .exactly_one()?
+            .replace_errors();
+        assert_eq!(event, ParsedEvent::Insert((None, expected)));
+    }
+
+    let context = ReaderContext::from_diff(
+        DataEventType::Insert,
+        None,
+        HashMap::from([("body".to_owned(), Ok(Value::from("text")))]).into(),
+    );
+    assert!(new_parser(MergeWinner::Body)?.parse(&context).is_err());
     Ok(())
 }
@@ -0,0 +1,107 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use itertools::Itertools;
+use prost_reflect::prost::Message;
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type as ProtoType};
+use prost_reflect::prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+};
+use prost_reflect::{DescriptorPool, DynamicMessage, Value as ProtobufValue};
+
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, ParsedEvent, Parser, ProtobufParser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn field(name: &str, number: i32, type_: ProtoType) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(Label::Optional.into()),
+        r#type: Some(type_.into()),
+        ..Default::default()
+    }
+}
+
+fn descriptor_set() -> Vec<u8> {
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            syntax: Some("proto2".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Item".to_string()),
+                field: vec![
+                    field("id", 1, ProtoType::Int64),
+                    field("name", 2, ProtoType::String),
+                    field("extra", 3, ProtoType::String),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+    .encode_to_vec()
+}
+
+fn create_parser() -> eyre::Result<ProtobufParser> {
+    let schema = [
+        ("id".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(
+                Type::Optional(Type::String.into()),
+                Some(Value::from("unknown")),
+            ),
+        ),
+    ];
+    Ok(ProtobufParser::new(
+        &descriptor_set(),
+        "test.Item",
+        vec!["id".to_string(), "name".to_string()],
+        schema.into(),
+    )?)
+}
+
+fn parse_single(parser: &mut ProtobufParser, raw_bytes: Vec<u8>) -> eyre::Result<ParsedEvent> {
+    Ok(parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            raw_bytes,
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .exactly_one()?
+        .replace_errors())
+}
+
+#[test]
+fn test_protobuf_unset_optional_field() -> eyre::Result<()> {
+    let pool = DescriptorPool::decode(descriptor_set().as_slice())?;
+    let descriptor = pool
+        .get_message_by_name("test.Item")
+        .expect("message must be present");
+    let mut message = DynamicMessage::new(descriptor);
+    message.set_field_by_name("id", ProtobufValue::I64(7));
+    message.set_field_by_name("extra", ProtobufValue::String("dropped".to_string()));
+
+    let mut parser = create_parser()?;
+    assert_eq!(
+        parse_single(&mut parser, message.encode_to_vec())?,
+        ParsedEvent::Insert((None, vec![Value::Int(7), Value::from("unknown")]))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_protobuf_malformed_message() -> eyre::Result<()> {
+    let mut parser = create_parser()?;
+    assert_eq!(
+        parse_single(&mut parser, vec![0xff])?,
+        ParsedEvent::Insert((None, vec![Value::Error, Value::Error]))
+    );
+    Ok(())
+}
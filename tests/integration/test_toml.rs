@@ -0,0 +1,123 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use serde_json::json;
+
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, ParsedEvent, Parser, TomlParser, TomlTables,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn parse_documents(parser: &mut TomlParser, documents: &[&str]) -> Vec<ParsedEvent> {
+    documents
+        .iter()
+        .flat_map(|document| {
+            parser
+                .parse(&ReaderContext::from_raw_bytes(
+                    DataEventType::Insert,
+                    document.as_bytes().to_vec(),
+                ))
+                .expect("a malformed document should not fail the parsing")
+        })
+        .map(|event| event.replace_errors())
+        .collect()
+}
+
+#[test]
+fn test_toml_documents() -> eyre::Result<()> {
+    let schema = [
+        ("port".to_string(), InnerSchemaField::new(Type::Int, None)),
+        (
+            "host".to_string(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "comment".to_string(),
+            InnerSchemaField::new(
+                Type::Optional(Type::String.into()),
+                Some(Value::from("none")),
+            ),
+        ),
+        (
+            "limits".to_string(),
+            InnerSchemaField::new(Type::Json, Some(Value::from(json!({})))),
+        ),
+    ];
+    let mut parser = TomlParser::new(
+        vec![
+            "port".to_string(),
+            "host".to_string(),
+            "comment".to_string(),
+            "limits".to_string(),
+        ],
+        schema.into(),
+    )?;
+
+    let documents = [
+        "port = 8080\nhost = \"localhost\"\n\n[limits]\nrequests = 100\n",
+        "port = \"8080\"\nhost = \"example.com\"\ncomment = \"string port\"\n",
+        "port = [unclosed\n",
+    ];
+    assert_eq!(
+        parse_documents(&mut parser, &documents),
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Int(8080),
+                    Value::from("localhost"),
+                    Value::from("none"),
+                    Value::from(json!({"requests": 100})),
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Error,
+                    Value::from("example.com"),
+                    Value::from("string port"),
+                    Value::from(json!({})),
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::Error, Value::Error, Value::Error]
+            )),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_toml_flattened_tables() -> eyre::Result<()> {
+    let schema = [
+        (
+            "server.port".to_string(),
+            InnerSchemaField::new(Type::Int, None),
+        ),
+        (
+            "server.tls.enabled".to_string(),
+            InnerSchemaField::new(Type::Bool, Some(Value::Bool(false))),
+        ),
+    ];
+    let mut parser = TomlParser::new(
+        vec!["server.port".to_string(), "server.tls.enabled".to_string()],
+        schema.into(),
+    )?
+    .with_tables(TomlTables::Flatten);
+
+    let documents = [
+        "[server]\nport = 443\ntls = { enabled = true }\n",
+        "server.port = 80\n",
+    ];
+    assert_eq!(
+        parse_documents(&mut parser, &documents),
+        vec![
+            ParsedEvent::Insert((None, vec![Value::Int(443), Value::Bool(true)])),
+            ParsedEvent::Insert((None, vec![Value::Int(80), Value::Bool(false)])),
+        ]
+    );
+    Ok(())
+}
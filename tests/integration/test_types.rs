@@ -1,6 +1,9 @@
 // Copyright © 2024 Pathway
 
-use pathway_engine::engine::Type;
+use chrono::NaiveDate;
+use pathway_engine::connectors::data_format::ParsedEvent;
+use pathway_engine::engine::value::{Kind, STABLE_HASH_VERSION};
+use pathway_engine::engine::{Type, Value};
 
 #[test]
 fn test_type_display() {
@@ -37,3 +40,72 @@ fn test_type_display() {
     );
     assert_eq!(Type::Optional(Type::Int.into()).to_string(), "int | None");
 }
+
+#[test]
+fn test_value_try_coerce() -> eyre::Result<()> {
+    assert_eq!(Value::from("42").try_coerce(&Type::Int)?, Value::Int(42));
+    assert_eq!(
+        Value::Int(3).try_coerce(&Type::Float)?,
+        Value::Float(3.0.into())
+    );
+    assert_eq!(
+        Value::from("yes").try_coerce(&Type::Bool)?,
+        Value::Bool(true)
+    );
+    assert_eq!(
+        Value::from("2024-03-01").try_coerce(&Type::Date)?,
+        Value::Date(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+    );
+    assert_eq!(
+        Value::None.try_coerce(&Type::Optional(Type::Int.into()))?,
+        Value::None
+    );
+
+    let error = Value::from("x")
+        .try_coerce(&Type::Int)
+        .expect_err("x is not a number");
+    assert_eq!(error.from, Kind::String);
+    assert_eq!(error.to, Type::Int);
+    assert!(Value::Bool(true).try_coerce(&Type::Bytes).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_value_stable_hash() {
+    // The hashes must not change without an increase of the version
+    assert_eq!(STABLE_HASH_VERSION, 1);
+    assert_eq!(Value::Int(42).stable_hash(), 0x4851_9b75_0ee5_d677);
+    assert_eq!(Value::from("a").stable_hash(), 0xc4ea_0961_60a5_7ce4);
+    let tuple = Value::from(vec![Value::Int(1), Value::from("a")]);
+    assert_eq!(tuple.stable_hash(), 0x1c8a_dd24_e147_40f6);
+    assert_eq!(tuple.stable_hash(), tuple.clone().stable_hash());
+    assert_ne!(Value::Int(1).stable_hash(), Value::from("1").stable_hash());
+}
+
+#[test]
+fn test_value_display_annotated() {
+    let value = Value::from(vec![Value::Int(1), Value::Error]);
+    assert_eq!(value.display_annotated(), "Tuple[Int(1), Err]");
+    assert_eq!(Value::from("abc").display_annotated(), r#"Str("abc")"#);
+    assert_eq!(Value::from("1").display_annotated(), r#"Str("1")"#);
+    assert_eq!(Value::None.display_annotated(), "None");
+
+    let event = ParsedEvent::Insert((Some(vec![Value::Int(1)]), vec![value, Value::from(2.5)]));
+    assert_eq!(
+        event.display_annotated(),
+        "Insert(key: [Int(1)], values: [Tuple[Int(1), Err], Float(2.5)])"
+    );
+    assert_eq!(ParsedEvent::AdvanceTime.display_annotated(), "AdvanceTime");
+}
+
+#[test]
+fn test_value_size_bytes() {
+    assert!(Value::from("a longer string").size_bytes() > Value::from("short").size_bytes());
+    let list = |n: i64| Value::from((0..n).map(Value::Int).collect::<Vec<_>>());
+    assert!(list(3).size_bytes() > list(2).size_bytes());
+    assert!(list(2).size_bytes() > list(0).size_bytes());
+    assert_eq!(Value::Int(1).size_bytes(), Value::None.size_bytes());
+
+    let event = |key: Option<Vec<Value>>| ParsedEvent::Insert((key, vec![list(2)]));
+    assert!(event(Some(vec![Value::from("key")])).size_bytes() > event(None).size_bytes());
+}
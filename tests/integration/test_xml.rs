@@ -0,0 +1,93 @@
+// Copyright © 2024 Pathway
+
+use std::collections::HashMap;
+
+use crate::helpers::ReplaceErrors;
+
+use pathway_engine::connectors::data_format::{
+    InnerSchemaField, ParsedEvent, ParsedEventWithErrors, Parser, XmlParser,
+};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+fn xml_parser() -> eyre::Result<XmlParser> {
+    let schema = [
+        (
+            "customer_id".to_string(),
+            InnerSchemaField::new(Type::Int, None),
+        ),
+        (
+            "name".to_string(),
+            InnerSchemaField::new(Type::String, None),
+        ),
+        (
+            "note".to_string(),
+            InnerSchemaField::new(Type::String, Some(Value::from("none"))),
+        ),
+    ];
+    let paths = HashMap::from([
+        ("customer_id".to_string(), "order/customer/@id".to_string()),
+        ("name".to_string(), "order/customer/name".to_string()),
+    ]);
+    Ok(XmlParser::new(
+        vec![
+            "customer_id".to_string(),
+            "name".to_string(),
+            "note".to_string(),
+        ],
+        schema.into(),
+        paths,
+    )?)
+}
+
+#[test]
+fn test_xml_records() -> eyre::Result<()> {
+    let mut parser = xml_parser()?;
+    let payload = r#"<?xml version="1.0"?>
+        <order><customer id="7"><name>Alice &amp; Bob</name></customer></order>
+        <order><customer id="x"><name>Carol</name></customer><note>fragile</note></order>
+    "#;
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            payload.as_bytes().to_vec(),
+        ))
+        .expect("parsing should not fail")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Int(7),
+                    Value::from("Alice & Bob"),
+                    Value::from("none")
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::from("Carol"), Value::from("fragile")]
+            )),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_xml_malformed_record() -> eyre::Result<()> {
+    let mut parser = xml_parser()?;
+    let events = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            b"<order><customer id=\"1\"></order>".to_vec(),
+        ))
+        .expect("a malformed record should not fail the whole batch");
+    let [ParsedEventWithErrors::Insert((None, values))] = events.as_slice() else {
+        panic!("unexpected events: {events:?}");
+    };
+    assert!(values.iter().all(Result::is_err));
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// Copyright © 2024 Pathway
+
+use crate::helpers::ReplaceErrors;
+
+use serde_json::json;
+
+use pathway_engine::connectors::data_format::{InnerSchemaField, ParsedEvent, Parser, YamlParser};
+use pathway_engine::connectors::data_storage::{DataEventType, ReaderContext};
+use pathway_engine::engine::{Type, Value};
+
+#[test]
+fn test_yaml_documents() -> eyre::Result<()> {
+    let schema = [
+        ("a".to_string(), InnerSchemaField::new(Type::Int, None)),
+        ("b".to_string(), InnerSchemaField::new(Type::String, None)),
+        (
+            "c".to_string(),
+            InnerSchemaField::new(Type::Optional(Type::String.into()), None),
+        ),
+        (
+            "d".to_string(),
+            InnerSchemaField::new(Type::Json, Some(Value::from(json!({})))),
+        ),
+    ];
+    let mut parser = YamlParser::new(
+        vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ],
+        schema.into(),
+    )?;
+
+    let stream = "---
+a: 1
+b: first
+c: null
+d:
+  nested: [1, 2]
+---
+a: not a number
+b: second
+c: present
+ignored: true
+---
+a: [unclosed
+";
+    let events: Vec<_> = parser
+        .parse(&ReaderContext::from_raw_bytes(
+            DataEventType::Insert,
+            stream.as_bytes().to_vec(),
+        ))
+        .expect("a malformed document should not fail the whole stream")
+        .into_iter()
+        .map(|event| event.replace_errors())
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Int(1),
+                    Value::from("first"),
+                    Value::None,
+                    Value::from(json!({"nested": [1, 2]})),
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![
+                    Value::Error,
+                    Value::from("second"),
+                    Value::from("present"),
+                    Value::from(json!({})),
+                ]
+            )),
+            ParsedEvent::Insert((
+                None,
+                vec![Value::Error, Value::Error, Value::Error, Value::Error]
+            )),
+        ]
+    );
+    Ok(())
+}